@@ -0,0 +1,369 @@
+//! Derive macros for [duners](https://docs.rs/duners).
+//!
+//! Use them through the `derive` feature of duners, which re-exports [`DuneRow`] and
+//! [`DuneParams`] as `duners::DuneRow` and `duners::DuneParams`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Field, Fields, GenericArgument, LitStr,
+    PathArguments, Type,
+};
+
+/// Implements `Deserialize` and `duners::schema::RowSchema` for a result row struct.
+///
+/// Fields are filled from the row's columns by name, matched case-insensitively. Numbers,
+/// booleans and timestamps Dune sends as strings are converted according to the field type
+/// (`f64`, `u64`, `bool`, `DateTime<Utc>`, and `Option`s of them), without `deserialize_with`
+/// attributes. Do not also derive `Deserialize`.
+///
+/// Attributes:
+///
+/// - `#[dune(rename = "Max Price")]` on a field: the column name, when it differs from the
+///   field name.
+/// - `#[dune(alias = "📊 Volume")]` on a field (repeatable): other column names the field is
+///   read from, e.g. when queries name the column differently.
+/// - `#[dune(strict)]` on the struct: match column names exactly and reject rows with columns
+///   the struct does not read.
+/// - `#[dune(lenient)]` on the struct: `Option` fields whose value cannot be converted are
+///   `None` instead of failing the row.
+///
+/// See the `duners::DuneRow` documentation for an example.
+#[proc_macro_derive(DuneRow, attributes(dune))]
+pub fn derive_dune_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How strictly rows are matched to the struct.
+#[derive(PartialEq)]
+enum Mode {
+    Default,
+    Strict,
+    Lenient,
+}
+
+/// Implements `duners::parameters::ToParameters` for a struct of query parameters.
+///
+/// Each field becomes one parameter, typed by its `duners::parameters::ParameterValue`
+/// implementation: strings are text, numbers are numbers, `DateTime`/`NaiveDate` are dates, `Vec`s
+/// are multi-select lists, and `None` fields are left out so the query's default applies.
+///
+/// Attributes:
+///
+/// - `#[dune(rename = "Start Date")]` on a field: the parameter name, when it differs from the
+///   field name.
+/// - `#[dune(rename_all = "...")]` on the struct: derive parameter names from field names with
+///   `"PascalCase"`, `"camelCase"`, `"kebab-case"` or `"Title Case"`.
+/// - `#[dune(list)]` on a field: send the value as a list (dropdown) parameter.
+/// - `#[dune(skip)]` on a field: not a parameter.
+///
+/// See the `duners::DuneParams` documentation for an example.
+#[proc_macro_derive(DuneParams, attributes(dune))]
+pub fn derive_dune_params(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_params(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The named fields of the non-generic struct `input`.
+fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<&'a Punctuated<Field, Comma>> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            format!("{derive} does not support generic structs"),
+        ));
+    }
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(&fields.named),
+            _ => Err(Error::new_spanned(
+                name,
+                format!("{derive} requires a struct with named fields"),
+            )),
+        },
+        _ => Err(Error::new_spanned(
+            name,
+            format!("{derive} can only derive structs"),
+        )),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = named_fields(&input, "DuneRow")?;
+
+    let mut mode = Mode::Default;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("dune"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("strict") {
+                mode = Mode::Strict;
+                Ok(())
+            } else if meta.path.is_ident("lenient") {
+                mode = Mode::Lenient;
+                Ok(())
+            } else {
+                Err(meta.error("expected `strict` or `lenient`"))
+            }
+        })?;
+    }
+    let exact = mode == Mode::Strict;
+
+    let mut initializers = Vec::new();
+    let mut specs = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let mut column = ident.to_string().trim_start_matches("r#").to_string();
+        let mut aliases = Vec::new();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("dune"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    column = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("alias") {
+                    aliases.push(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename = \"column\"` or `alias = \"column\"`"))
+                }
+            })?;
+        }
+
+        let ty = &field.ty;
+        let inner = option_inner(ty);
+        let hint = hint(inner.unwrap_or(ty));
+        let decode = quote! {
+            ::duners::derive_support::decode::<#ty>(
+                #column,
+                row.take(&[#column #(, #aliases)*], #exact),
+                ::duners::derive_support::Hint::#hint,
+            )
+        };
+        let value = match (&mode, inner) {
+            (Mode::Lenient, Some(_)) => quote! { #decode.unwrap_or_default() },
+            _ => quote! { #decode.map_err(D::Error::custom)? },
+        };
+        initializers.push(quote! { #ident: #value });
+
+        let kind = kind(inner.unwrap_or(ty));
+        specs.push(match inner {
+            Some(_) => quote! { ::duners::schema::FieldSpec::new(#column, #kind).optional() },
+            None => quote! { ::duners::schema::FieldSpec::new(#column, #kind) },
+        });
+    }
+    let deny_remaining = match mode {
+        Mode::Strict => quote! { row.deny_remaining().map_err(D::Error::custom)?; },
+        _ => quote! {},
+    };
+
+    Ok(quote! {
+        impl<'de> ::duners::derive_support::serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: ::duners::derive_support::serde::Deserializer<'de>,
+            {
+                use ::duners::derive_support::serde::de::Error as _;
+                let mut row = ::duners::derive_support::Columns::deserialize(deserializer)?;
+                let value = #name {
+                    #(#initializers,)*
+                };
+                #deny_remaining
+                ::std::result::Result::Ok(value)
+            }
+        }
+
+        impl ::duners::schema::RowSchema for #name {
+            fn schema() -> ::std::vec::Vec<::duners::schema::FieldSpec> {
+                ::std::vec![#(#specs),*]
+            }
+        }
+    })
+}
+
+fn expand_params(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = named_fields(&input, "DuneParams")?;
+
+    let mut rename_all: Option<String> = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("dune"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule = meta.value()?.parse::<LitStr>()?;
+                if rename(&rule.value(), "field").is_none() {
+                    return Err(Error::new_spanned(
+                        rule,
+                        "expected \"PascalCase\", \"camelCase\", \"kebab-case\" or \"Title Case\"",
+                    ));
+                }
+                rename_all = Some(rule.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename_all = \"...\"`"))
+            }
+        })?;
+    }
+
+    let mut parameters = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let field_name = ident.to_string().trim_start_matches("r#").to_string();
+        let mut key = match &rename_all {
+            Some(rule) => rename(rule, &field_name).expect("validated rule"),
+            None => field_name,
+        };
+        let (mut list, mut skip) = (false, false);
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("dune"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    key = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("list") {
+                    list = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename = \"name\"`, `list` or `skip`"))
+                }
+            })?;
+        }
+        if skip {
+            continue;
+        }
+        let parameter =
+            quote! { ::duners::parameters::ParameterValue::to_parameter(&self.#ident, #key) };
+        parameters.push(match list {
+            true => quote! { ::duners::derive_support::list(#parameter) },
+            false => parameter,
+        });
+    }
+
+    Ok(quote! {
+        impl ::duners::parameters::ToParameters for #name {
+            fn to_parameters(&self) -> ::std::vec::Vec<::duners::Parameter> {
+                let mut parameters = ::std::vec::Vec::new();
+                #(parameters.extend(#parameters);)*
+                parameters
+            }
+        }
+    })
+}
+
+/// `field` (a snake_case name) in the case of `rule`, or `None` for an unknown rule.
+fn rename(rule: &str, field: &str) -> Option<String> {
+    let words = field.split('_').filter(|word| !word.is_empty());
+    let capitalized = words.clone().map(|word| {
+        let mut characters = word.chars();
+        characters
+            .next()
+            .map(|first| first.to_uppercase().chain(characters).collect::<String>())
+            .unwrap_or_default()
+    });
+    match rule {
+        "PascalCase" => Some(capitalized.collect()),
+        "camelCase" => {
+            let pascal: String = capitalized.collect();
+            let mut characters = pascal.chars();
+            Some(
+                characters
+                    .next()
+                    .map(|first| first.to_lowercase().chain(characters).collect())
+                    .unwrap_or_default(),
+            )
+        }
+        "kebab-case" => Some(words.collect::<Vec<_>>().join("-")),
+        "Title Case" => Some(capitalized.collect::<Vec<_>>().join(" ")),
+        _ => None,
+    }
+}
+
+/// The last path segment of `ty`, e.g. `DateTime` for `chrono::DateTime<Utc>`.
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// The single type argument of `ty` if its last segment is `wrapper`, e.g. `T` of `Option<T>`.
+fn type_argument<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let segment = last_segment(ty)?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    type_argument(ty, "Option")
+}
+
+/// The `Hint` variant telling how string-encoded values of `ty` are converted.
+fn hint(ty: &Type) -> TokenStream2 {
+    let ident = last_segment(ty).map(|segment| segment.ident.to_string());
+    match ident.as_deref() {
+        Some("f32" | "f64") => quote! { Float },
+        Some(
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+            | "usize",
+        ) => quote! { Integer },
+        Some("bool") => quote! { Bool },
+        Some("DateTime") => quote! { Timestamp },
+        _ => quote! { Other },
+    }
+}
+
+/// The `duners::schema::FieldKind` of a field of type `ty`.
+fn kind(ty: &Type) -> TokenStream2 {
+    if let Some(inner) = type_argument(ty, "Vec") {
+        let inner = kind(inner);
+        return quote! { ::duners::schema::FieldKind::Array(::std::boxed::Box::new(#inner)) };
+    }
+    let ident = last_segment(ty).map(|segment| segment.ident.to_string());
+    match ident.as_deref() {
+        Some("String") => quote! { ::duners::schema::FieldKind::String },
+        Some("f32" | "f64") => quote! { ::duners::schema::FieldKind::Float },
+        Some(
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+            | "usize",
+        ) => quote! { ::duners::schema::FieldKind::Integer },
+        Some("bool") => quote! { ::duners::schema::FieldKind::Bool },
+        Some("DateTime" | "NaiveDateTime" | "NaiveDate") => {
+            quote! { ::duners::schema::FieldKind::Timestamp }
+        }
+        _ => quote! { ::duners::schema::FieldKind::Any },
+    }
+}
@@ -0,0 +1,186 @@
+//! Compressed on-disk archives of results (feature `archive`).
+//!
+//! [`GetResultResponse::save`] writes a whole response — rows, metadata and execution times —
+//! to a zstd-compressed JSON file, and [`GetResultResponse::load`] reads it back, e.g. to keep
+//! a history of daily pulls or analyze results offline without spending credits. Result JSON
+//! compresses well, typically to a tenth of its size or less.
+//!
+//! An archive is a single zstd frame holding a JSON object with the [`ArchiveMetadata`] fields
+//! and the response under `response`, in the API's own shape; `zstd -dc results.json.zst | jq`
+//! inspects one.
+
+use crate::error::DuneRequestError;
+use crate::response::{ExecutionStatus, GetResultResponse};
+use chrono::{DateTime, Utc};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Version of the archive layout written by this crate.
+const FORMAT_VERSION: u32 = 1;
+
+/// What an archive records besides the response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArchiveMetadata {
+    /// Version of the archive layout.
+    pub format_version: u32,
+    /// When the archive was written.
+    pub saved_at: DateTime<Utc>,
+    /// Version of duners that wrote the archive.
+    pub duners_version: String,
+    /// ID of the request the results were fetched with, if known.
+    pub request_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ArchiveRef<'a, T> {
+    #[serde(flatten)]
+    metadata: ArchiveMetadata,
+    response: &'a GetResultResponse<T>,
+}
+
+#[derive(Deserialize)]
+struct Archive<R> {
+    #[serde(flatten)]
+    metadata: ArchiveMetadata,
+    response: R,
+}
+
+impl<T: Serialize> GetResultResponse<T> {
+    /// Writes the response to a zstd-compressed archive at `path` (feature `archive`),
+    /// replacing any existing file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde_json::Value;
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = client.refresh::<Value>(971694, None, None).await?;
+    /// results.save(format!("archive/971694-{}.json.zst", results.execution_id))?;
+    /// # Ok(()) }
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DuneRequestError> {
+        let archive = ArchiveRef {
+            metadata: ArchiveMetadata {
+                format_version: FORMAT_VERSION,
+                saved_at: Utc::now(),
+                duners_version: env!("CARGO_PKG_VERSION").to_string(),
+                request_id: self.request_id.clone(),
+            },
+            response: self,
+        };
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+        serde_json::to_writer(&mut encoder, &archive)?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+impl<T: DeserializeOwned> GetResultResponse<T> {
+    /// Reads a response written by [`save`](Self::save) (feature `archive`), deserializing the
+    /// rows as `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::GetResultResponse;
+    /// use serde_json::Value;
+    ///
+    /// let results: GetResultResponse<Value> = serde_json::from_value(serde_json::json!({
+    ///     "execution_id": "01HKZJ2683PHF9Q9PHHQ8FW4Q1", "query_id": 971694,
+    ///     "state": "QUERY_STATE_COMPLETED", "submitted_at": "2024-01-01T00:00:00.000Z",
+    ///     "result": {
+    ///         "rows": [{"symbol": "ETH", "max_price": 3000.5}],
+    ///         "metadata": {
+    ///             "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    ///             "result_set_bytes": 30, "total_row_count": 1, "datapoint_count": 2,
+    ///             "pending_time_millis": null, "execution_time_millis": 1
+    ///         }
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// let path = std::env::temp_dir().join("duners-archive-doc.json.zst");
+    /// results.save(&path).unwrap();
+    /// let loaded = GetResultResponse::<Value>::load(&path).unwrap();
+    /// assert_eq!(loaded, results);
+    /// ```
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DuneRequestError> {
+        let archive: Archive<Self> = read(path.as_ref())?;
+        let mut response = archive.response;
+        response.request_id = archive.metadata.request_id;
+        response.partial = response.state == ExecutionStatus::PartiallyComplete;
+        Ok(response)
+    }
+}
+
+/// The metadata of the archive at `path`, without deserializing its rows.
+pub fn metadata(path: impl AsRef<Path>) -> Result<ArchiveMetadata, DuneRequestError> {
+    read::<IgnoredAny>(path.as_ref()).map(|archive| archive.metadata)
+}
+
+/// Decompresses and parses the archive at `path`, rejecting layouts newer than this crate's.
+fn read<R: DeserializeOwned>(path: &Path) -> Result<Archive<R>, DuneRequestError> {
+    let decoder = zstd::Decoder::new(BufReader::new(File::open(path)?))?;
+    let archive: Archive<R> = serde_json::from_reader(decoder)?;
+    if archive.metadata.format_version > FORMAT_VERSION {
+        return Err(DuneRequestError::Io(format!(
+            "{} is a version {} archive; this duners reads up to version {FORMAT_VERSION}",
+            path.display(),
+            archive.metadata.format_version
+        )));
+    }
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn archive_round_trip() {
+        let mut results: GetResultResponse<Value> = serde_json::from_value(json!({
+            "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED_PARTIAL",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "expires_at": "2024-04-01T00:00:00.000Z",
+            "result": {
+                "rows": [{"a": 1, "b": "x"}, {"a": null, "b": "y"}],
+                "metadata": {
+                    "column_names": ["a", "b"], "column_types": ["bigint", "varchar"],
+                    "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+                    "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        }))
+        .unwrap();
+        results.partial = true;
+        results.request_id = Some("req-1".to_string());
+        let path = std::env::temp_dir().join("duners-archive-test.json.zst");
+        results.save(&path).unwrap();
+
+        let loaded = GetResultResponse::<Value>::load(&path).unwrap();
+        assert_eq!(loaded, results);
+        let info = metadata(&path).unwrap();
+        assert_eq!(info.format_version, FORMAT_VERSION);
+        assert_eq!(info.request_id.as_deref(), Some("req-1"));
+
+        // Newer layouts are rejected, not misread.
+        let mut archive: Value =
+            serde_json::from_reader(zstd::Decoder::new(File::open(&path).unwrap()).unwrap())
+                .unwrap();
+        archive["format_version"] = json!(FORMAT_VERSION + 1);
+        let compressed = zstd::encode_all(archive.to_string().as_bytes(), 0).unwrap();
+        std::fs::write(&path, compressed).unwrap();
+        assert!(matches!(
+            GetResultResponse::<Value>::load(&path),
+            Err(DuneRequestError::Io(_))
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
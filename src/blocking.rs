@@ -0,0 +1,771 @@
+//! A blocking client for the Dune API (feature `blocking`).
+//!
+//! [`DuneClient`] mirrors [`client::DuneClient`] without `async`, for
+//! scripts and build tools that do not want to set up a tokio runtime. Like `reqwest::blocking`,
+//! it wraps the async client and drives it on a small runtime of its own, so it must not be used
+//! from within an async context (calls panic there).
+//!
+//! ```no_run
+//! use duners::blocking::DuneClient;
+//! use duners::DuneRequestError;
+//! use serde_json::Value;
+//!
+//! fn main() -> Result<(), DuneRequestError> {
+//!     let client = DuneClient::from_env();
+//!     let result = client.refresh::<Value>(971694, None, None)?;
+//!     println!("{:?}", result.get_rows());
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Configure it like the async client and convert with `into()`:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! let client: duners::blocking::DuneClient = duners::DuneClient::builder()
+//!     .timeout(Duration::from_secs(30))
+//!     .build()
+//!     .unwrap()
+//!     .into();
+//! ```
+//!
+//! [`status_stream`](crate::client::DuneClient::status_stream),
+//! [`stream_rows_to`](crate::client::DuneClient::stream_rows_to) and
+//! [`watch`](crate::client::DuneClient::watch) are async-only; page through results with
+//! [`get_results_page`](DuneClient::get_results_page) instead.
+
+use crate::cache::ResultCache;
+use crate::client;
+use crate::error::DuneRequestError;
+use crate::join::JoinKind;
+use crate::mapping::ColumnMapping;
+use crate::metrics::{ExecutionReport, SlaTracker};
+use crate::parameters::Parameter;
+use crate::poll::PollStrategy;
+use crate::query::{QueryRef, UpdateQuery};
+use crate::response::{
+    CancellationResponse, ClearTableResponse, ClockSkew, CreateQueryResponse, CreateTableResponse,
+    CsvDownload, DeleteTableResponse, ExecutionResponse, ExecutionTimes, GetResultResponse,
+    GetStatusResponse, InsertTableResponse, Query, RateLimitStatus, UpdateQueryResponse,
+    UploadCsvResponse,
+};
+use crate::rowset::RowSet;
+use crate::store::ExecutionStore;
+use crate::table::{ChunkedInsert, ColumnDef, InsertFormat, InsertProgress};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use std::hash::Hash;
+use std::io::Read;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::runtime::Runtime;
+
+/// Blocking counterpart of [`client::DuneClient`].
+///
+/// Every method behaves like its async namesake and blocks the calling thread until it
+/// completes.
+pub struct DuneClient {
+    inner: client::DuneClient,
+    runtime: Runtime,
+}
+
+impl From<client::DuneClient> for DuneClient {
+    fn from(inner: client::DuneClient) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the blocking client's runtime");
+        DuneClient { inner, runtime }
+    }
+}
+
+impl DuneClient {
+    /// Creates a client with the given API key; see
+    /// [`client::DuneClient::new`](crate::client::DuneClient::new).
+    pub fn new(api_key: &str) -> DuneClient {
+        client::DuneClient::new(api_key).into()
+    }
+
+    /// Creates a client from the environment; see
+    /// [`client::DuneClient::from_env`](crate::client::DuneClient::from_env).
+    pub fn from_env() -> DuneClient {
+        client::DuneClient::from_env().into()
+    }
+
+    /// The async client this one drives.
+    pub fn as_async(&self) -> &client::DuneClient {
+        &self.inner
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// The API root requests are sent to.
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// See [`client::DuneClient::with_poll_strategy`](crate::client::DuneClient::with_poll_strategy).
+    pub fn with_poll_strategy(mut self, strategy: impl PollStrategy + 'static) -> Self {
+        self.inner = self.inner.with_poll_strategy(strategy);
+        self
+    }
+
+    /// See [`client::DuneClient::with_sla_tracker`](crate::client::DuneClient::with_sla_tracker).
+    pub fn with_sla_tracker(mut self, tracker: Arc<SlaTracker>) -> Self {
+        self.inner = self.inner.with_sla_tracker(tracker);
+        self
+    }
+
+    /// See [`client::DuneClient::with_execution_store`](crate::client::DuneClient::with_execution_store).
+    pub fn with_execution_store(mut self, store: impl ExecutionStore + 'static) -> Self {
+        self.inner = self.inner.with_execution_store(store);
+        self
+    }
+
+    /// See [`client::DuneClient::with_request_coalescing`](crate::client::DuneClient::with_request_coalescing);
+    /// only useful when the client is shared between threads.
+    pub fn with_request_coalescing(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_request_coalescing(enabled);
+        self
+    }
+
+    /// See [`client::DuneClient::with_execution_reuse`](crate::client::DuneClient::with_execution_reuse).
+    pub fn with_execution_reuse(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_execution_reuse(enabled);
+        self
+    }
+
+    /// See [`client::DuneClient::with_column_mapping`](crate::client::DuneClient::with_column_mapping).
+    pub fn with_column_mapping(mut self, mapping: ColumnMapping) -> Self {
+        self.inner = self.inner.with_column_mapping(mapping);
+        self
+    }
+
+    /// See [`client::DuneClient::with_clock_skew_tolerance`](crate::client::DuneClient::with_clock_skew_tolerance).
+    pub fn with_clock_skew_tolerance(mut self, tolerance: Duration) -> Self {
+        self.inner = self.inner.with_clock_skew_tolerance(tolerance);
+        self
+    }
+
+    /// See [`client::DuneClient::with_result_cache`](crate::client::DuneClient::with_result_cache).
+    pub fn with_result_cache(mut self, ttl: Duration) -> Self {
+        self.inner = self.inner.with_result_cache(ttl);
+        self
+    }
+
+    /// See [`client::DuneClient::with_result_cache_backend`](crate::client::DuneClient::with_result_cache_backend).
+    pub fn with_result_cache_backend(
+        mut self,
+        cache: impl ResultCache + 'static,
+        ttl: Duration,
+    ) -> Self {
+        self.inner = self.inner.with_result_cache_backend(cache, ttl);
+        self
+    }
+
+    /// Drops every cached result; see
+    /// [`client::DuneClient::clear_result_cache`](crate::client::DuneClient::clear_result_cache).
+    pub fn clear_result_cache(&self) {
+        self.inner.clear_result_cache()
+    }
+
+    /// The last observed difference between Dune's clock and the local clock.
+    pub fn clock_skew(&self) -> Option<ClockSkew> {
+        self.inner.clock_skew()
+    }
+
+    /// Dune's rate-limit budget as reported by the latest response carrying rate-limit headers.
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    /// Current time according to Dune's clock.
+    pub fn server_now(&self) -> DateTime<Utc> {
+        self.inner.server_now()
+    }
+
+    /// Whether results with these `times` have expired on Dune's servers.
+    pub fn is_expired(&self, times: &ExecutionTimes) -> bool {
+        self.inner.is_expired(times)
+    }
+
+    /// Execution IDs started by this client that are still considered in flight.
+    pub fn tracked_executions(&self) -> Vec<String> {
+        self.inner.tracked_executions()
+    }
+
+    /// Create Query; see [`client::DuneClient::create_query`](crate::client::DuneClient::create_query).
+    pub fn create_query(
+        &self,
+        name: &str,
+        query_sql: &str,
+        parameters: Option<Vec<Parameter>>,
+        is_private: bool,
+    ) -> Result<CreateQueryResponse, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .create_query(name, query_sql, parameters, is_private),
+        )
+    }
+
+    /// Row struct source for a query; see
+    /// [`client::DuneClient::generate_row_struct`](crate::client::DuneClient::generate_row_struct).
+    pub fn generate_row_struct(
+        &self,
+        query: impl Into<QueryRef>,
+        name: &str,
+    ) -> Result<String, DuneRequestError> {
+        self.block_on(self.inner.generate_row_struct(query, name))
+    }
+
+    /// Read Query; see [`client::DuneClient::get_query`](crate::client::DuneClient::get_query).
+    pub fn get_query(&self, query: impl Into<QueryRef>) -> Result<Query, DuneRequestError> {
+        self.block_on(self.inner.get_query(query))
+    }
+
+    /// Checks parameters against the query's definition; see
+    /// [`client::DuneClient::validate_params`](crate::client::DuneClient::validate_params).
+    pub fn validate_params(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: &[Parameter],
+    ) -> Result<(), DuneRequestError> {
+        self.block_on(self.inner.validate_params(query, parameters))
+    }
+
+    /// Update Query; see [`client::DuneClient::update_query`](crate::client::DuneClient::update_query).
+    pub fn update_query(
+        &self,
+        query: impl Into<QueryRef>,
+        update: UpdateQuery,
+    ) -> Result<UpdateQueryResponse, DuneRequestError> {
+        self.block_on(self.inner.update_query(query, update))
+    }
+
+    /// Copies a query into a new private query; see
+    /// [`client::DuneClient::fork_query`](crate::client::DuneClient::fork_query).
+    pub fn fork_query(
+        &self,
+        query: impl Into<QueryRef>,
+    ) -> Result<CreateQueryResponse, DuneRequestError> {
+        self.block_on(self.inner.fork_query(query))
+    }
+
+    /// Archive Query; see [`client::DuneClient::archive_query`](crate::client::DuneClient::archive_query).
+    pub fn archive_query(
+        &self,
+        query: impl Into<QueryRef>,
+    ) -> Result<UpdateQueryResponse, DuneRequestError> {
+        self.block_on(self.inner.archive_query(query))
+    }
+
+    /// Unarchive Query; see [`client::DuneClient::unarchive_query`](crate::client::DuneClient::unarchive_query).
+    pub fn unarchive_query(
+        &self,
+        query: impl Into<QueryRef>,
+    ) -> Result<UpdateQueryResponse, DuneRequestError> {
+        self.block_on(self.inner.unarchive_query(query))
+    }
+
+    /// Private Query; see [`client::DuneClient::make_private`](crate::client::DuneClient::make_private).
+    pub fn make_private(
+        &self,
+        query: impl Into<QueryRef>,
+    ) -> Result<UpdateQueryResponse, DuneRequestError> {
+        self.block_on(self.inner.make_private(query))
+    }
+
+    /// Unprivate Query; see [`client::DuneClient::make_public`](crate::client::DuneClient::make_public).
+    pub fn make_public(
+        &self,
+        query: impl Into<QueryRef>,
+    ) -> Result<UpdateQueryResponse, DuneRequestError> {
+        self.block_on(self.inner.make_public(query))
+    }
+
+    /// Upload CSV; see [`client::DuneClient::upload_csv`](crate::client::DuneClient::upload_csv).
+    pub fn upload_csv(
+        &self,
+        table_name: &str,
+        description: &str,
+        csv_data: &str,
+        is_private: bool,
+    ) -> Result<UploadCsvResponse, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .upload_csv(table_name, description, csv_data, is_private),
+        )
+    }
+
+    /// Create Table; see [`client::DuneClient::create_table`](crate::client::DuneClient::create_table).
+    pub fn create_table(
+        &self,
+        namespace: &str,
+        table_name: &str,
+        schema: Vec<ColumnDef>,
+        is_private: bool,
+    ) -> Result<CreateTableResponse, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .create_table(namespace, table_name, schema, is_private),
+        )
+    }
+
+    /// Insert Table; see [`client::DuneClient::insert_rows`](crate::client::DuneClient::insert_rows).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::blocking::DuneClient;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Transfer {
+    ///     block: u64,
+    ///     amount: f64,
+    /// }
+    ///
+    /// let client = DuneClient::from_env();
+    /// let rows = [Transfer { block: 1, amount: 2.5 }];
+    /// let inserted = client.insert_rows("my_team", "transfers", rows).unwrap();
+    /// println!("{} rows written", inserted.rows_written);
+    /// ```
+    pub fn insert_rows<T: Serialize>(
+        &self,
+        namespace: &str,
+        table_name: &str,
+        rows: impl IntoIterator<Item = T>,
+    ) -> Result<InsertTableResponse, DuneRequestError> {
+        self.block_on(self.inner.insert_rows(namespace, table_name, rows))
+    }
+
+    /// Inserts rows in size-bounded chunks; see
+    /// [`client::DuneClient::insert_rows_chunked`](crate::client::DuneClient::insert_rows_chunked).
+    pub fn insert_rows_chunked<T: Serialize>(
+        &self,
+        namespace: &str,
+        table_name: &str,
+        rows: impl IntoIterator<Item = T>,
+        options: ChunkedInsert,
+        progress: impl FnMut(&InsertProgress),
+    ) -> Result<InsertProgress, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .insert_rows_chunked(namespace, table_name, rows, options, progress),
+        )
+    }
+
+    /// Inserts NDJSON or CSV read from `reader` in size-bounded chunks; see
+    /// [`client::DuneClient::insert_reader`](crate::client::DuneClient::insert_reader).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::blocking::DuneClient;
+    /// use duners::table::{ChunkedInsert, InsertFormat};
+    ///
+    /// let client = DuneClient::from_env();
+    /// let file = std::fs::File::open("transfers.csv").unwrap();
+    /// client
+    ///     .insert_reader("my_team", "transfers", file, InsertFormat::Csv, ChunkedInsert::default(), |_| {})
+    ///     .unwrap();
+    /// ```
+    pub fn insert_reader<R: Read + Unpin>(
+        &self,
+        namespace: &str,
+        table_name: &str,
+        reader: R,
+        format: InsertFormat,
+        options: ChunkedInsert,
+        progress: impl FnMut(&InsertProgress),
+    ) -> Result<InsertProgress, DuneRequestError> {
+        self.block_on(self.inner.insert_reader(
+            namespace,
+            table_name,
+            SyncReader(reader),
+            format,
+            options,
+            progress,
+        ))
+    }
+
+    /// Inserts an Arrow record batch; see
+    /// [`client::DuneClient::insert_record_batch`](crate::client::DuneClient::insert_record_batch).
+    #[cfg(feature = "arrow")]
+    pub fn insert_record_batch(
+        &self,
+        namespace: &str,
+        table_name: &str,
+        batch: &arrow_array::RecordBatch,
+        options: ChunkedInsert,
+    ) -> Result<InsertProgress, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .insert_record_batch(namespace, table_name, batch, options),
+        )
+    }
+
+    /// Inserts a polars data frame; see
+    /// [`client::DuneClient::insert_dataframe`](crate::client::DuneClient::insert_dataframe).
+    #[cfg(feature = "polars")]
+    pub fn insert_dataframe(
+        &self,
+        namespace: &str,
+        table_name: &str,
+        frame: &mut polars::prelude::DataFrame,
+        options: ChunkedInsert,
+    ) -> Result<InsertProgress, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .insert_dataframe(namespace, table_name, frame, options),
+        )
+    }
+
+    /// Clear Table; see [`client::DuneClient::clear_table`](crate::client::DuneClient::clear_table).
+    pub fn clear_table(
+        &self,
+        namespace: &str,
+        table_name: &str,
+    ) -> Result<ClearTableResponse, DuneRequestError> {
+        self.block_on(self.inner.clear_table(namespace, table_name))
+    }
+
+    /// Delete Table; see [`client::DuneClient::delete_table`](crate::client::DuneClient::delete_table).
+    pub fn delete_table(
+        &self,
+        namespace: &str,
+        table_name: &str,
+    ) -> Result<DeleteTableResponse, DuneRequestError> {
+        self.block_on(self.inner.delete_table(namespace, table_name))
+    }
+
+    /// Execute Query; see [`client::DuneClient::execute_query`](crate::client::DuneClient::execute_query).
+    pub fn execute_query(
+        &self,
+        query: impl Into<QueryRef>,
+        params: Option<Vec<Parameter>>,
+    ) -> Result<ExecutionResponse, DuneRequestError> {
+        self.block_on(self.inner.execute_query(query, params))
+    }
+
+    /// Execute Query with a one-off timeout; see
+    /// [`client::DuneClient::execute_query_with_timeout`](crate::client::DuneClient::execute_query_with_timeout).
+    pub fn execute_query_with_timeout(
+        &self,
+        query: impl Into<QueryRef>,
+        params: Option<Vec<Parameter>>,
+        timeout: Duration,
+    ) -> Result<ExecutionResponse, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .execute_query_with_timeout(query, params, timeout),
+        )
+    }
+
+    /// Cancel Execution; see
+    /// [`client::DuneClient::cancel_execution`](crate::client::DuneClient::cancel_execution).
+    pub fn cancel_execution(&self, job_id: &str) -> Result<CancellationResponse, DuneRequestError> {
+        self.block_on(self.inner.cancel_execution(job_id))
+    }
+
+    /// Cancels every execution still tracked; see
+    /// [`client::DuneClient::cancel_all`](crate::client::DuneClient::cancel_all).
+    pub fn cancel_all(&self) -> Vec<(String, Result<CancellationResponse, DuneRequestError>)> {
+        self.block_on(self.inner.cancel_all())
+    }
+
+    /// Get Execution Status; see [`client::DuneClient::get_status`](crate::client::DuneClient::get_status).
+    pub fn get_status(&self, job_id: &str) -> Result<GetStatusResponse, DuneRequestError> {
+        self.block_on(self.inner.get_status(job_id))
+    }
+
+    /// Get Execution Results; see
+    /// [`client::DuneClient::get_results`](crate::client::DuneClient::get_results).
+    pub fn get_results<T: DeserializeOwned>(
+        &self,
+        job_id: &str,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(self.inner.get_results(job_id))
+    }
+
+    /// Get Execution Results with a one-off timeout; see
+    /// [`client::DuneClient::get_results_with_timeout`](crate::client::DuneClient::get_results_with_timeout).
+    pub fn get_results_with_timeout<T: DeserializeOwned>(
+        &self,
+        job_id: &str,
+        timeout: Duration,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(self.inner.get_results_with_timeout(job_id, timeout))
+    }
+
+    /// Execution results, re-executing the query when they expired; see
+    /// [`client::DuneClient::get_results_or_reexecute`](crate::client::DuneClient::get_results_or_reexecute).
+    pub fn get_results_or_reexecute<T: DeserializeOwned>(
+        &self,
+        job_id: &str,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .get_results_or_reexecute(job_id, query, parameters),
+        )
+    }
+
+    /// One page of execution results; see
+    /// [`client::DuneClient::get_results_page`](crate::client::DuneClient::get_results_page).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::blocking::DuneClient;
+    /// use serde_json::Value;
+    ///
+    /// let client = DuneClient::from_env();
+    /// let mut offset = Some(0);
+    /// while let Some(current) = offset {
+    ///     let page = client
+    ///         .get_results_page::<Value>("your-execution-id", 1000, current)
+    ///         .unwrap();
+    ///     offset = page.next_offset;
+    ///     println!("{} rows", page.get_rows().len());
+    /// }
+    /// ```
+    pub fn get_results_page<T: DeserializeOwned>(
+        &self,
+        job_id: &str,
+        limit: u32,
+        offset: u64,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(self.inner.get_results_page(job_id, limit, offset))
+    }
+
+    /// Results of a custom endpoint; see
+    /// [`client::DuneClient::custom_endpoint`](crate::client::DuneClient::custom_endpoint).
+    pub fn custom_endpoint<T: DeserializeOwned>(
+        &self,
+        handle: &str,
+        slug: &str,
+        limit: Option<u32>,
+        filters: Option<&str>,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(self.inner.custom_endpoint(handle, slug, limit, filters))
+    }
+
+    /// Downloads results as CSV; see
+    /// [`client::DuneClient::download_csv`](crate::client::DuneClient::download_csv).
+    pub fn download_csv(
+        &self,
+        job_id: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<CsvDownload, DuneRequestError> {
+        self.block_on(self.inner.download_csv(job_id, path))
+    }
+
+    /// Executes a query and waits for its results; see
+    /// [`client::DuneClient::refresh`](crate::client::DuneClient::refresh).
+    pub fn refresh<T: DeserializeOwned>(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+        ping_frequency: Option<u64>,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(self.inner.refresh(query, parameters, ping_frequency))
+    }
+
+    /// Refreshes a query into a CSV file; see
+    /// [`client::DuneClient::refresh_to_csv`](crate::client::DuneClient::refresh_to_csv).
+    pub fn refresh_to_csv(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+        path: impl AsRef<Path>,
+    ) -> Result<usize, DuneRequestError> {
+        self.block_on(self.inner.refresh_to_csv(query, parameters, path))
+    }
+
+    /// Refreshes a query into a Parquet file; see
+    /// [`client::DuneClient::refresh_to_parquet`](crate::client::DuneClient::refresh_to_parquet).
+    #[cfg(feature = "parquet")]
+    pub fn refresh_to_parquet(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+        path: impl AsRef<Path>,
+        options: crate::columnar::ParquetOptions,
+    ) -> Result<usize, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .refresh_to_parquet(query, parameters, path, options),
+        )
+    }
+
+    /// Refreshes a query into a Feather (Arrow IPC) file; see
+    /// [`client::DuneClient::refresh_to_feather`](crate::client::DuneClient::refresh_to_feather).
+    #[cfg(feature = "ipc")]
+    pub fn refresh_to_feather(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+        path: impl AsRef<Path>,
+    ) -> Result<usize, DuneRequestError> {
+        self.block_on(self.inner.refresh_to_feather(query, parameters, path))
+    }
+
+    /// Refreshes queries into the worksheets of an Excel file; see
+    /// [`client::DuneClient::refresh_to_xlsx`](crate::client::DuneClient::refresh_to_xlsx).
+    #[cfg(feature = "xlsx")]
+    pub fn refresh_to_xlsx<S: AsRef<str>, Q: Into<QueryRef>>(
+        &self,
+        sheets: impl IntoIterator<Item = (S, Q)>,
+        path: impl AsRef<Path>,
+    ) -> Result<usize, DuneRequestError> {
+        self.block_on(self.inner.refresh_to_xlsx(sheets, path))
+    }
+
+    /// Refreshes a query into a polars data frame; see
+    /// [`client::DuneClient::refresh_dataframe`](crate::client::DuneClient::refresh_dataframe).
+    #[cfg(feature = "polars")]
+    pub fn refresh_dataframe(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+    ) -> Result<polars::prelude::DataFrame, DuneRequestError> {
+        self.block_on(self.inner.refresh_dataframe(query, parameters))
+    }
+
+    /// Runs ad-hoc SQL; see [`client::DuneClient::run_sql`](crate::client::DuneClient::run_sql).
+    pub fn run_sql<T: DeserializeOwned>(
+        &self,
+        query_sql: &str,
+        parameters: Option<Vec<Parameter>>,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.block_on(self.inner.run_sql(query_sql, parameters))
+    }
+
+    /// Refreshes two queries and joins their rows; see
+    /// [`client::DuneClient::refresh_join`](crate::client::DuneClient::refresh_join).
+    pub fn refresh_join<L, R, K>(
+        &self,
+        left_query: impl Into<QueryRef>,
+        right_query: impl Into<QueryRef>,
+        left_key: impl Fn(&L) -> K,
+        right_key: impl Fn(&R) -> K,
+        kind: JoinKind,
+    ) -> Result<Vec<(L, Option<R>)>, DuneRequestError>
+    where
+        L: DeserializeOwned + Clone,
+        R: DeserializeOwned + Clone,
+        K: Eq + Hash,
+    {
+        self.block_on(
+            self.inner
+                .refresh_join(left_query, right_query, left_key, right_key, kind),
+        )
+    }
+
+    /// Refreshes two queries and joins their untyped rows on key columns; see
+    /// [`client::DuneClient::refresh_join_rows`](crate::client::DuneClient::refresh_join_rows).
+    pub fn refresh_join_rows(
+        &self,
+        left_query: impl Into<QueryRef>,
+        right_query: impl Into<QueryRef>,
+        key_columns: &[&str],
+        kind: JoinKind,
+    ) -> Result<RowSet, DuneRequestError> {
+        self.block_on(
+            self.inner
+                .refresh_join_rows(left_query, right_query, key_columns, kind),
+        )
+    }
+
+    /// Like [`refresh`](DuneClient::refresh), also returning an [`ExecutionReport`]; see
+    /// [`client::DuneClient::refresh_with_report`](crate::client::DuneClient::refresh_with_report).
+    pub fn refresh_with_report<T: DeserializeOwned>(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+        ping_frequency: Option<u64>,
+    ) -> Result<(GetResultResponse<T>, ExecutionReport), DuneRequestError> {
+        self.block_on(
+            self.inner
+                .refresh_with_report(query, parameters, ping_frequency),
+        )
+    }
+
+    /// Continues waiting for a stored execution; see
+    /// [`client::DuneClient::resume`](crate::client::DuneClient::resume).
+    pub fn resume<T: DeserializeOwned>(
+        &self,
+        query: impl Into<QueryRef>,
+        parameters: Option<Vec<Parameter>>,
+        ping_frequency: Option<u64>,
+    ) -> Result<Option<GetResultResponse<T>>, DuneRequestError> {
+        self.block_on(self.inner.resume(query, parameters, ping_frequency))
+    }
+}
+
+/// Exposes a blocking reader to the async client. Reads block the runtime's only thread, which
+/// has nothing else to do in the meantime.
+struct SyncReader<R>(R);
+
+impl<R: Read + Unpin> AsyncRead for SyncReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let read = self.get_mut().0.read(buf.initialize_unfilled())?;
+        buf.advance(read);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::QueryId;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    #[test]
+    fn zero_query_id_is_rejected_locally() {
+        let dune = DuneClient::new("key");
+        let error = dune.get_query(0).unwrap_err();
+        assert_eq!(error, DuneRequestError::QueryNotFound(QueryId(0)));
+    }
+
+    #[test]
+    fn blocking_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let _ = socket.read(&mut [0; 4096]).unwrap();
+            let body = r#"{"execution_id": "mocked", "state": "QUERY_STATE_PENDING"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).unwrap();
+        });
+        let dune: DuneClient = client::DuneClient::builder()
+            .api_key("key")
+            .base_url(&format!("http://{address}/api/v1"))
+            .build()
+            .unwrap()
+            .into();
+        let execution = dune.execute_query(1, None).unwrap();
+        assert_eq!(execution.execution_id, "mocked");
+        assert_eq!(dune.tracked_executions(), vec!["mocked".to_string()]);
+    }
+}
@@ -0,0 +1,297 @@
+//! Storage for results cached by [`refresh`](crate::client::DuneClient::refresh).
+//!
+//! With [`DuneClient::with_result_cache`](crate::client::DuneClient::with_result_cache) results
+//! are kept in a [`MemoryResultCache`]; pass another [`ResultCache`] to
+//! [`with_result_cache_backend`](crate::client::DuneClient::with_result_cache_backend) to keep
+//! them elsewhere, e.g. in a [`DiskResultCache`] so they survive restarts and are shared by every
+//! process on the host.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// What cached results are looked up by: the query and the parameter values it ran with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Query that was executed.
+    pub query_id: u32,
+    /// Parameter values (name → value) the execution was started with.
+    pub parameters: BTreeMap<String, String>,
+}
+
+/// A results response as the API sent it, with the time it stops being served.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResults {
+    /// Body of the results response (JSON).
+    pub body: Vec<u8>,
+    /// When the entry goes stale: the cache TTL or the results' `expires_at`, whichever is
+    /// first.
+    pub until: DateTime<Utc>,
+}
+
+/// Storage for [`CachedResults`], keyed by [`CacheKey`].
+///
+/// Implementations need not evict stale entries: the client checks
+/// [`until`](CachedResults::until) and invalidates stale entries it comes across.
+///
+/// # Example
+///
+/// A cache that never keeps anything (e.g. to disable caching in tests):
+///
+/// ```rust
+/// use duners::cache::{CacheKey, CachedResults, ResultCache};
+/// use std::io;
+///
+/// struct NoCache;
+///
+/// impl ResultCache for NoCache {
+///     fn get(&self, _key: &CacheKey) -> io::Result<Option<CachedResults>> {
+///         Ok(None)
+///     }
+///     fn put(&self, _key: &CacheKey, _results: &CachedResults) -> io::Result<()> {
+///         Ok(())
+///     }
+///     fn invalidate(&self, _key: &CacheKey) -> io::Result<()> {
+///         Ok(())
+///     }
+///     fn clear(&self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ResultCache: Send + Sync {
+    /// The entry for `key`, if any.
+    fn get(&self, key: &CacheKey) -> io::Result<Option<CachedResults>>;
+    /// Stores `results` under `key`, replacing any previous entry.
+    fn put(&self, key: &CacheKey, results: &CachedResults) -> io::Result<()>;
+    /// Forgets the entry for `key`.
+    fn invalidate(&self, key: &CacheKey) -> io::Result<()>;
+    /// Forgets every entry.
+    fn clear(&self) -> io::Result<()>;
+}
+
+/// [`ResultCache`] in the process's memory, the default.
+#[derive(Debug, Default)]
+pub struct MemoryResultCache {
+    entries: Mutex<HashMap<CacheKey, CachedResults>>,
+}
+
+impl MemoryResultCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ResultCache for MemoryResultCache {
+    fn get(&self, key: &CacheKey) -> io::Result<Option<CachedResults>> {
+        Ok(self.entries.lock().unwrap().get(key).cloned())
+    }
+
+    fn put(&self, key: &CacheKey, results: &CachedResults) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.clone(), results.clone());
+        Ok(())
+    }
+
+    fn invalidate(&self, key: &CacheKey) -> io::Result<()> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        self.entries.lock().unwrap().clear();
+        Ok(())
+    }
+}
+
+/// [`ResultCache`] keeping one file per entry in a directory.
+///
+/// Each file holds a JSON line describing the entry followed by the results body. Writes go to a
+/// temporary file that is then renamed into place, so processes sharing the directory never
+/// read a half-written entry.
+///
+/// # Example
+///
+/// ```no_run
+/// use duners::cache::DiskResultCache;
+/// use duners::DuneClient;
+/// use std::time::Duration;
+///
+/// let client = DuneClient::from_env().with_result_cache_backend(
+///     DiskResultCache::new("/var/cache/my-service/dune"),
+///     Duration::from_secs(3600),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiskResultCache {
+    dir: PathBuf,
+}
+
+/// First line of a [`DiskResultCache`] file.
+#[derive(Serialize, Deserialize)]
+struct EntryHeader {
+    key: CacheKey,
+    until: DateTime<Utc>,
+}
+
+impl DiskResultCache {
+    /// Uses the directory at `dir`, which is created on the first write.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        DiskResultCache {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// File of the entry for `key`: the query ID and a hash of the parameter values.
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        // FNV-1a, which unlike std's hasher is stable across builds and processes.
+        let parameters = serde_json::to_vec(&key.parameters).unwrap_or_default();
+        let hash = parameters
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        self.dir
+            .join(format!("{}-{hash:016x}.results", key.query_id))
+    }
+}
+
+impl ResultCache for DiskResultCache {
+    fn get(&self, key: &CacheKey) -> io::Result<Option<CachedResults>> {
+        let bytes = match fs::read(self.path(key)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let Some(newline) = bytes.iter().position(|&byte| byte == b'\n') else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "cache entry has no header line",
+            ));
+        };
+        let header: EntryHeader = serde_json::from_slice(&bytes[..newline])?;
+        // Different parameters whose hashes collide.
+        if &header.key != key {
+            return Ok(None);
+        }
+        Ok(Some(CachedResults {
+            body: bytes[newline + 1..].to_vec(),
+            until: header.until,
+        }))
+    }
+
+    fn put(&self, key: &CacheKey, results: &CachedResults) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let header = EntryHeader {
+            key: key.clone(),
+            until: results.until,
+        };
+        let mut bytes = serde_json::to_vec(&header)?;
+        bytes.push(b'\n');
+        bytes.extend_from_slice(&results.body);
+        let path = self.path(key);
+        // Unique per process and write, so concurrent writers (other processes, or threads of
+        // this one) do not clobber each other's temporary file.
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let write = WRITES.fetch_add(1, Ordering::Relaxed);
+        let tmp = path.with_extension(format!("tmp{}-{write}", std::process::id()));
+        fs::write(&tmp, bytes)?;
+        fs::rename(tmp, path)
+    }
+
+    fn invalidate(&self, key: &CacheKey) -> io::Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn clear(&self) -> io::Result<()> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "results")
+            {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join("duners-disk-cache-test");
+        let _ = fs::remove_dir_all(&dir);
+        let cache = DiskResultCache::new(&dir);
+        let key = |days: &str| CacheKey {
+            query_id: 1,
+            parameters: BTreeMap::from([("Days".to_string(), days.to_string())]),
+        };
+        assert_eq!(cache.get(&key("7")).unwrap(), None);
+
+        let results = CachedResults {
+            body: b"{\"execution_id\": \"jerb\"}\n".to_vec(),
+            until: "2024-01-01T00:00:00Z".parse().unwrap(),
+        };
+        cache.put(&key("7"), &results).unwrap();
+        cache.put(&key("30"), &results).unwrap();
+        // A fresh handle sees what the previous one wrote.
+        let reopened = DiskResultCache::new(&dir);
+        assert_eq!(reopened.get(&key("7")).unwrap(), Some(results.clone()));
+
+        reopened.invalidate(&key("7")).unwrap();
+        reopened.invalidate(&key("7")).unwrap();
+        assert_eq!(cache.get(&key("7")).unwrap(), None);
+        assert_eq!(cache.get(&key("30")).unwrap(), Some(results));
+        cache.clear().unwrap();
+        assert_eq!(cache.get(&key("30")).unwrap(), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_disk_cache_puts() {
+        let dir = std::env::temp_dir().join("duners-disk-cache-concurrency-test");
+        let _ = fs::remove_dir_all(&dir);
+        let cache = DiskResultCache::new(&dir);
+        let key = CacheKey {
+            query_id: 1,
+            parameters: BTreeMap::new(),
+        };
+        let until = "2024-01-01T00:00:00Z".parse().unwrap();
+        std::thread::scope(|scope| {
+            for thread in 0..8u8 {
+                let (cache, key) = (&cache, &key);
+                scope.spawn(move || {
+                    for _ in 0..50 {
+                        let body = vec![thread; 4096];
+                        cache.put(key, &CachedResults { body, until }).unwrap();
+                    }
+                });
+            }
+        });
+        // Whichever write came last, the entry is one thread's body, whole.
+        let body = cache.get(&key).unwrap().unwrap().body;
+        assert_eq!(body.len(), 4096);
+        assert!(body.iter().all(|&byte| byte == body[0]));
+        // No temporary files are left behind.
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+//! Generating row structs from result metadata.
+//!
+//! [`row_struct`] turns the column names and types of a result into the Rust source of a
+//! `#[derive(Deserialize)]` struct, with the [`parse_utils`](crate::parse_utils) attributes Dune's
+//! string-encoded values need. [`DuneClient::generate_row_struct`](crate::client::DuneClient::generate_row_struct)
+//! does the same for a saved query, from its latest results.
+//!
+//! The generated code is a starting point: every Dune column may contain `null`, so wrap fields
+//! in `Option` where the query can return it, and adjust types as needed.
+
+use crate::response::{DuneColumnType, ResultMetaData};
+use std::fmt::Write;
+
+/// Rust keywords that cannot be used as plain field names.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "typeof", "unsized", "virtual", "yield", "try",
+];
+
+/// Rust source of a struct named `name` with one public field per column of `metadata`.
+///
+/// Field names are the snake_case column names (with `#[serde(rename)]` where they differ);
+/// field types follow [`DuneColumnType::rust_type`]. Timestamps get
+/// [`any_datetime_from_str`](crate::parse_utils::any_datetime_from_str) and 256-bit integers
+/// [`number_as_string`](crate::parse_utils::number_as_string). Columns of unknown type become
+/// `serde_json::Value`.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::codegen::row_struct;
+/// use duners::response::ResultMetaData;
+///
+/// let metadata: ResultMetaData = serde_json::from_value(serde_json::json!({
+///     "column_names": ["symbol", "Max Price", "day"],
+///     "column_types": ["varchar", "double", "timestamp(3) with time zone"],
+///     "result_set_bytes": 0, "total_row_count": 0, "datapoint_count": 0,
+///     "pending_time_millis": null, "execution_time_millis": 1
+/// }))
+/// .unwrap();
+/// let source = row_struct("Price", &metadata);
+/// assert!(source.contains("pub struct Price {"));
+/// assert!(source.contains("#[serde(rename = \"Max Price\")]\n    pub max_price: f64,"));
+/// ```
+pub fn row_struct(name: &str, metadata: &ResultMetaData) -> String {
+    let mut source = String::from("#[derive(Debug, Clone, serde::Deserialize)]\n");
+    let _ = writeln!(source, "pub struct {name} {{");
+    let mut fields: Vec<String> = Vec::new();
+    for (column, column_type) in metadata.columns() {
+        let mut field = field_name(column);
+        let mut suffix = 2;
+        while fields.contains(&field) {
+            field = format!("{}_{suffix}", field_name(column));
+            suffix += 1;
+        }
+        if let Some(column_type) = column_type {
+            let _ = writeln!(source, "    /// `{column_type}`");
+        }
+        let mut attributes = Vec::new();
+        if field.trim_start_matches("r#") != column {
+            attributes.push(format!("rename = {column:?}"));
+        }
+        if let Some(with) = column_type.and_then(deserialize_with) {
+            attributes.push(format!("deserialize_with = \"{with}\""));
+        }
+        if !attributes.is_empty() {
+            let _ = writeln!(source, "    #[serde({})]", attributes.join(", "));
+        }
+        let rust_type = column_type.map_or("serde_json::Value".to_string(), |column_type| {
+            column_type.rust_type()
+        });
+        let _ = writeln!(source, "    pub {field}: {rust_type},");
+        fields.push(field);
+    }
+    source.push_str("}\n");
+    source
+}
+
+/// The `parse_utils` deserializer a field of `column_type` needs, if any.
+fn deserialize_with(column_type: &DuneColumnType) -> Option<&'static str> {
+    match column_type {
+        DuneColumnType::Date | DuneColumnType::Timestamp { .. } => {
+            Some("duners::parse_utils::any_datetime_from_str")
+        }
+        DuneColumnType::Uint256 | DuneColumnType::Int256 => {
+            Some("duners::parse_utils::number_as_string")
+        }
+        _ => None,
+    }
+}
+
+/// A snake_case Rust identifier for `column`.
+fn field_name(column: &str) -> String {
+    let mut name = String::with_capacity(column.len());
+    let mut previous_lower = false;
+    for character in column.chars() {
+        if character.is_ascii_alphanumeric() {
+            if character.is_ascii_uppercase() && previous_lower {
+                name.push('_');
+            }
+            previous_lower = character.is_ascii_lowercase() || character.is_ascii_digit();
+            name.push(character.to_ascii_lowercase());
+        } else {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+            previous_lower = false;
+        }
+    }
+    let name = name.trim_end_matches('_');
+    match name {
+        "" => "column".to_string(),
+        name if name.starts_with(|c: char| c.is_ascii_digit()) => format!("_{name}"),
+        "self" | "super" | "crate" => format!("{name}_"),
+        name if KEYWORDS.contains(&name) => format!("r#{name}"),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_names() {
+        assert_eq!(field_name("symbol"), "symbol");
+        assert_eq!(field_name("Max Price (USD)"), "max_price_usd");
+        assert_eq!(field_name("blockNumber"), "block_number");
+        assert_eq!(field_name("24h_volume"), "_24h_volume");
+        assert_eq!(field_name("type"), "r#type");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(field_name("%"), "column");
+    }
+
+    #[test]
+    fn generated_struct() {
+        let metadata: ResultMetaData = serde_json::from_value(serde_json::json!({
+            "column_names": ["block_time", "type", "amount", "Amount", "tags", "raw"],
+            "column_types": [
+                "timestamp(3) with time zone", "varchar", "uint256", "double",
+                "array(varchar)", "row(a varchar)"
+            ],
+            "result_set_bytes": 0, "total_row_count": 0, "datapoint_count": 0,
+            "pending_time_millis": null, "execution_time_millis": 1
+        }))
+        .unwrap();
+        assert_eq!(
+            row_struct("Transfer", &metadata),
+            r#"#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Transfer {
+    /// `timestamp(3) with time zone`
+    #[serde(deserialize_with = "duners::parse_utils::any_datetime_from_str")]
+    pub block_time: chrono::DateTime<chrono::Utc>,
+    /// `varchar`
+    pub r#type: String,
+    /// `uint256`
+    #[serde(deserialize_with = "duners::parse_utils::number_as_string")]
+    pub amount: String,
+    /// `double`
+    #[serde(rename = "Amount")]
+    pub amount_2: f64,
+    /// `array(varchar)`
+    pub tags: Vec<String>,
+    /// `row(a varchar)`
+    pub raw: serde_json::Value,
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,795 @@
+//! Arrow and polars integration for the Tables API (features `arrow` and `polars`).
+//!
+//! Columnar data is mapped to Dune column types ([`schema_from_arrow`], [`schema_from_polars`])
+//! for [`create_table`](crate::client::DuneClient::create_table), and encoded straight to
+//! newline-delimited JSON for
+//! [`insert_record_batch`](crate::client::DuneClient::insert_record_batch) and
+//! [`insert_dataframe`](crate::client::DuneClient::insert_dataframe), without a detour through
+//! CSV strings.
+//!
+//! | Arrow / polars type                         | [`DuneType`]  |
+//! |---------------------------------------------|---------------|
+//! | strings                                     | `varchar`     |
+//! | `Int8`, `Int16`, `Int32`, `UInt8`, `UInt16` | `integer`     |
+//! | `Int64`, `UInt32`                           | `bigint`      |
+//! | `UInt64`                                    | `uint256`     |
+//! | floats                                      | `double`      |
+//! | `Boolean`                                   | `boolean`     |
+//! | dates                                       | `date`        |
+//! | timestamps / datetimes                      | `timestamp`   |
+//! | binary                                      | `varbinary`   |
+//!
+//! Other types are rejected with [`DuneRequestError::Encode`].
+//!
+//! In the other direction, query results convert to an Arrow `RecordBatch` with
+//! `GetResultResponse::to_record_batch` or a polars `DataFrame` with
+//! `GetResultResponse::to_dataframe`, typed by the result's `column_types` (see `arrow_type` and
+//! `polars_type`). With the `parquet` feature, `GetResultResponse::write_parquet` writes the
+//! record batch as a Parquet file, and with the `ipc` feature `GetResultResponse::write_ipc`
+//! writes it in the Arrow IPC file format (Feather v2). With the `datafusion` feature,
+//! `GetResultResponse::register_with` registers it as a DataFusion table.
+
+use crate::error::DuneRequestError;
+use crate::response::{DuneColumnType, ExecutionResult, ResultRow};
+use crate::table::{ColumnDef, DuneType};
+use serde_json::Value;
+
+/// Dune column type for an Arrow data type, if supported.
+#[cfg(feature = "arrow")]
+pub fn dune_type_from_arrow(data_type: &arrow_schema::DataType) -> Option<DuneType> {
+    use arrow_schema::DataType;
+    Some(match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => DuneType::Varchar,
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::UInt8 | DataType::UInt16 => {
+            DuneType::Integer
+        }
+        DataType::Int64 | DataType::UInt32 => DuneType::Bigint,
+        DataType::UInt64 => DuneType::Uint256,
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => DuneType::Double,
+        DataType::Boolean => DuneType::Boolean,
+        DataType::Date32 | DataType::Date64 => DuneType::Date,
+        DataType::Timestamp(_, _) => DuneType::Timestamp,
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => DuneType::Varbinary,
+        _ => return None,
+    })
+}
+
+/// Table schema matching an Arrow schema.
+///
+/// # Example
+///
+/// ```rust
+/// use arrow_schema::{DataType, Field, Schema};
+/// use duners::columnar::schema_from_arrow;
+/// use duners::table::{ColumnDef, DuneType};
+///
+/// let schema = Schema::new(vec![
+///     Field::new("block", DataType::Int64, false),
+///     Field::new("label", DataType::Utf8, true),
+/// ]);
+/// assert_eq!(
+///     schema_from_arrow(&schema).unwrap(),
+///     vec![
+///         ColumnDef::new("block", DuneType::Bigint),
+///         ColumnDef::new("label", DuneType::Varchar).nullable(),
+///     ]
+/// );
+/// ```
+#[cfg(feature = "arrow")]
+pub fn schema_from_arrow(
+    schema: &arrow_schema::Schema,
+) -> Result<Vec<ColumnDef>, DuneRequestError> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column_type = dune_type_from_arrow(field.data_type())
+                .ok_or_else(|| unsupported(field.name(), field.data_type()))?;
+            let column = ColumnDef::new(field.name(), column_type);
+            Ok(match field.is_nullable() {
+                true => column.nullable(),
+                false => column,
+            })
+        })
+        .collect()
+}
+
+/// Arrow data type a column of `column_type` converts to.
+///
+/// | Dune type                           | Arrow type                    |
+/// |-------------------------------------|-------------------------------|
+/// | `boolean`                           | `Boolean`                     |
+/// | `tinyint` … `bigint`                | `Int8` … `Int64`              |
+/// | `real`                              | `Float32`                     |
+/// | `double`, `decimal`                 | `Float64`                     |
+/// | `date`                              | `Date32`                      |
+/// | `timestamp`                         | `Timestamp(Microsecond, UTC)` |
+/// | others, e.g. `uint256`, `varbinary` | `Utf8`                        |
+///
+/// 256-bit integers stay strings to keep every digit; arrays, maps and JSON become their JSON
+/// text.
+///
+/// # Example
+///
+/// ```rust
+/// use arrow_schema::{DataType, TimeUnit};
+/// use duners::columnar::arrow_type;
+/// use duners::response::DuneColumnType;
+///
+/// assert_eq!(arrow_type(&DuneColumnType::Smallint), DataType::Int16);
+/// let timestamp: DuneColumnType = "timestamp(3) with time zone".parse().unwrap();
+/// assert_eq!(
+///     arrow_type(&timestamp),
+///     DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+/// );
+/// ```
+#[cfg(feature = "arrow")]
+pub fn arrow_type(column_type: &DuneColumnType) -> arrow_schema::DataType {
+    use arrow_schema::{DataType, TimeUnit};
+    match column_type {
+        DuneColumnType::Boolean => DataType::Boolean,
+        DuneColumnType::Tinyint => DataType::Int8,
+        DuneColumnType::Smallint => DataType::Int16,
+        DuneColumnType::Integer => DataType::Int32,
+        DuneColumnType::Bigint => DataType::Int64,
+        DuneColumnType::Real => DataType::Float32,
+        DuneColumnType::Double | DuneColumnType::Decimal { .. } => DataType::Float64,
+        DuneColumnType::Date => DataType::Date32,
+        DuneColumnType::Timestamp { .. } => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        }
+        _ => DataType::Utf8,
+    }
+}
+
+/// Arrow schema of a result: one nullable field per column, typed by [`arrow_type`]. Columns of
+/// unreported type are `Utf8`.
+///
+/// # Example
+///
+/// ```rust
+/// use arrow_schema::DataType;
+/// use duners::columnar::arrow_schema;
+/// use duners::response::ResultMetaData;
+///
+/// let metadata: ResultMetaData = serde_json::from_value(serde_json::json!({
+///     "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+///     "result_set_bytes": 0, "total_row_count": 0, "datapoint_count": 0,
+///     "pending_time_millis": null, "execution_time_millis": 1
+/// }))
+/// .unwrap();
+/// let schema = arrow_schema(&metadata);
+/// assert_eq!(schema.field(1).name(), "max_price");
+/// assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+/// ```
+#[cfg(feature = "arrow")]
+pub fn arrow_schema(metadata: &crate::response::ResultMetaData) -> arrow_schema::Schema {
+    use arrow_schema::{DataType, Field, Schema};
+    let fields: Vec<_> = metadata
+        .columns()
+        .map(|(name, column_type)| {
+            let data_type = column_type.map_or(DataType::Utf8, arrow_type);
+            Field::new(name, data_type, true)
+        })
+        .collect();
+    Schema::new(fields)
+}
+
+/// Converts result rows to a record batch with the [`arrow_schema`] of the result.
+#[cfg(feature = "arrow")]
+pub(crate) fn result_to_record_batch<R: ResultRow>(
+    result: &ExecutionResult<R>,
+) -> Result<arrow_array::RecordBatch, DuneRequestError> {
+    use arrow_array::{
+        ArrayRef, BooleanArray, Date32Array, Float32Array, Float64Array, Int16Array, Int32Array,
+        Int64Array, Int8Array, RecordBatch, RecordBatchOptions, StringArray,
+        TimestampMicrosecondArray,
+    };
+    use arrow_schema::DataType;
+    use std::sync::Arc;
+    let rows = &result.rows;
+    let schema = Arc::new(arrow_schema(&result.metadata));
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        let name = field.name().as_str();
+        let column: ArrayRef = match field.data_type() {
+            DataType::Boolean => {
+                Arc::new(BooleanArray::from(cells(rows, name, "bool", bool_cell)?))
+            }
+            DataType::Int8 => Arc::new(Int8Array::from(cells(rows, name, "i8", int_cell)?)),
+            DataType::Int16 => Arc::new(Int16Array::from(cells(rows, name, "i16", int_cell)?)),
+            DataType::Int32 => Arc::new(Int32Array::from(cells(rows, name, "i32", int_cell)?)),
+            DataType::Int64 => Arc::new(Int64Array::from(cells(rows, name, "i64", int_cell)?)),
+            DataType::Float32 => {
+                let values = cells(rows, name, "f32", float_cell)?;
+                let values: Vec<Option<f32>> = values
+                    .into_iter()
+                    .map(|value| value.map(|value| value as f32))
+                    .collect();
+                Arc::new(Float32Array::from(values))
+            }
+            DataType::Float64 => {
+                Arc::new(Float64Array::from(cells(rows, name, "f64", float_cell)?))
+            }
+            DataType::Date32 => Arc::new(Date32Array::from(cells(rows, name, "date", date_cell)?)),
+            DataType::Timestamp(_, _) => Arc::new(
+                TimestampMicrosecondArray::from(cells(rows, name, "timestamp", micros_cell)?)
+                    .with_timezone("UTC"),
+            ),
+            _ => Arc::new(StringArray::from(cells(rows, name, "string", text_cell)?)),
+        };
+        columns.push(column);
+    }
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(schema, columns, &options)
+        .map_err(|err| DuneRequestError::Encode(err.to_string()))
+}
+
+/// How results are written to Parquet (feature `parquet`).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::columnar::ParquetOptions;
+/// use parquet::basic::Compression;
+///
+/// let options = ParquetOptions {
+///     compression: Compression::UNCOMPRESSED,
+///     ..Default::default()
+/// };
+/// ```
+#[cfg(feature = "parquet")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParquetOptions {
+    /// Compression of the column chunks. Codecs other than ZSTD need the matching `parquet`
+    /// crate feature (e.g. `snap`).
+    pub compression: parquet::basic::Compression,
+    /// Maximum number of rows per row group.
+    pub max_row_group_rows: usize,
+}
+
+#[cfg(feature = "parquet")]
+impl Default for ParquetOptions {
+    /// ZSTD at its default level, row groups of up to 1Mi rows.
+    fn default() -> Self {
+        ParquetOptions {
+            compression: parquet::basic::Compression::ZSTD(Default::default()),
+            max_row_group_rows: parquet::file::properties::DEFAULT_MAX_ROW_GROUP_ROW_COUNT,
+        }
+    }
+}
+
+/// Writes a record batch to `writer` as a Parquet file.
+#[cfg(feature = "parquet")]
+pub(crate) fn write_parquet<W: std::io::Write + Send>(
+    batch: &arrow_array::RecordBatch,
+    writer: W,
+    options: ParquetOptions,
+) -> Result<(), DuneRequestError> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    let properties = WriterProperties::builder()
+        .set_compression(options.compression)
+        .set_max_row_group_row_count(Some(options.max_row_group_rows))
+        .build();
+    let encode = |err: parquet::errors::ParquetError| DuneRequestError::Encode(err.to_string());
+    let mut writer =
+        ArrowWriter::try_new(writer, batch.schema(), Some(properties)).map_err(encode)?;
+    writer.write(batch).map_err(encode)?;
+    writer.close().map_err(encode)?;
+    Ok(())
+}
+
+/// Writes a record batch to `writer` in the Arrow IPC file format.
+#[cfg(feature = "ipc")]
+pub(crate) fn write_ipc<W: std::io::Write>(
+    batch: &arrow_array::RecordBatch,
+    writer: W,
+) -> Result<(), DuneRequestError> {
+    let encode = |err: arrow_schema::ArrowError| DuneRequestError::Encode(err.to_string());
+    let mut writer =
+        arrow_ipc::writer::FileWriter::try_new(writer, &batch.schema()).map_err(encode)?;
+    writer.write(batch).map_err(encode)?;
+    writer.finish().map_err(encode)
+}
+
+/// A record batch as one of DataFusion's own `arrow` version, which may differ from the one
+/// this crate uses. The batch is copied through the (version-independent) IPC format.
+#[cfg(feature = "datafusion")]
+pub(crate) fn to_datafusion_batch(
+    batch: &arrow_array::RecordBatch,
+) -> Result<datafusion::arrow::record_batch::RecordBatch, DuneRequestError> {
+    use datafusion::arrow::error::ArrowError;
+    use datafusion::arrow::ipc::reader::FileReader;
+    let mut file = Vec::new();
+    write_ipc(batch, &mut file)?;
+    let decode = |err: ArrowError| DuneRequestError::Encode(err.to_string());
+    let mut reader = FileReader::try_new(std::io::Cursor::new(file), None).map_err(decode)?;
+    match reader.next() {
+        Some(batch) => batch.map_err(decode),
+        None => Err(DuneRequestError::Encode(
+            "the IPC file holds no record batch".to_string(),
+        )),
+    }
+}
+
+/// Encodes a record batch as newline-delimited JSON.
+#[cfg(feature = "arrow")]
+pub(crate) fn arrow_to_ndjson(
+    batch: &arrow_array::RecordBatch,
+) -> Result<Vec<u8>, DuneRequestError> {
+    schema_from_arrow(&batch.schema())?;
+    let mut writer = arrow_json::LineDelimitedWriter::new(vec![]);
+    writer
+        .write(batch)
+        .and_then(|_| writer.finish())
+        .map_err(|err| DuneRequestError::Encode(err.to_string()))?;
+    Ok(writer.into_inner())
+}
+
+/// Dune column type for a polars data type, if supported.
+#[cfg(feature = "polars")]
+pub fn dune_type_from_polars(data_type: &polars::prelude::DataType) -> Option<DuneType> {
+    use polars::prelude::DataType;
+    Some(match data_type {
+        DataType::String => DuneType::Varchar,
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::UInt8 | DataType::UInt16 => {
+            DuneType::Integer
+        }
+        DataType::Int64 | DataType::UInt32 => DuneType::Bigint,
+        DataType::UInt64 => DuneType::Uint256,
+        DataType::Float32 | DataType::Float64 => DuneType::Double,
+        DataType::Boolean => DuneType::Boolean,
+        DataType::Date => DuneType::Date,
+        DataType::Datetime(_, _) => DuneType::Timestamp,
+        DataType::Binary => DuneType::Varbinary,
+        _ => return None,
+    })
+}
+
+/// Table schema matching a polars schema. All columns are nullable, as polars does not track
+/// nullability.
+#[cfg(feature = "polars")]
+pub fn schema_from_polars(
+    schema: &polars::prelude::Schema,
+) -> Result<Vec<ColumnDef>, DuneRequestError> {
+    schema
+        .iter()
+        .map(|(name, data_type)| {
+            dune_type_from_polars(data_type)
+                .map(|column_type| ColumnDef::new(name, column_type).nullable())
+                .ok_or_else(|| unsupported(name, data_type))
+        })
+        .collect()
+}
+
+/// Encodes a data frame as newline-delimited JSON.
+#[cfg(feature = "polars")]
+pub(crate) fn polars_to_ndjson(
+    frame: &mut polars::prelude::DataFrame,
+) -> Result<Vec<u8>, DuneRequestError> {
+    use polars::prelude::{JsonFormat, JsonWriter, SerWriter};
+    schema_from_polars(frame.schema())?;
+    let mut body = vec![];
+    JsonWriter::new(&mut body)
+        .with_json_format(JsonFormat::JsonLines)
+        .finish(frame)
+        .map_err(|err| DuneRequestError::Encode(err.to_string()))?;
+    Ok(body)
+}
+
+/// Polars data type a column of `column_type` converts to.
+///
+/// | Dune type                              | polars type                      |
+/// |----------------------------------------|----------------------------------|
+/// | `boolean`                              | `Boolean`                        |
+/// | `tinyint`, `smallint`, `integer`       | `Int32`                          |
+/// | `bigint`                               | `Int64`                          |
+/// | `real`                                 | `Float32`                        |
+/// | `double`, `decimal`                    | `Float64`                        |
+/// | `date`                                 | `Date`                           |
+/// | `timestamp`                            | `Datetime(Microseconds)`, in UTC |
+/// | others, e.g. `uint256`, `varbinary`    | `String`                         |
+///
+/// 256-bit integers stay strings to keep every digit; arrays, maps and JSON become their JSON
+/// text.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::columnar::polars_type;
+/// use duners::response::DuneColumnType;
+/// use polars::prelude::DataType;
+///
+/// assert_eq!(polars_type(&DuneColumnType::Bigint), DataType::Int64);
+/// assert_eq!(polars_type(&DuneColumnType::Uint256), DataType::String);
+/// ```
+#[cfg(feature = "polars")]
+pub fn polars_type(column_type: &DuneColumnType) -> polars::prelude::DataType {
+    use polars::prelude::{DataType, TimeUnit};
+    match column_type {
+        DuneColumnType::Boolean => DataType::Boolean,
+        DuneColumnType::Tinyint | DuneColumnType::Smallint | DuneColumnType::Integer => {
+            DataType::Int32
+        }
+        DuneColumnType::Bigint => DataType::Int64,
+        DuneColumnType::Real => DataType::Float32,
+        DuneColumnType::Double | DuneColumnType::Decimal { .. } => DataType::Float64,
+        DuneColumnType::Date => DataType::Date,
+        DuneColumnType::Timestamp { .. } => DataType::Datetime(TimeUnit::Microseconds, None),
+        _ => DataType::String,
+    }
+}
+
+/// Converts result rows to a data frame with one column per result column, typed by
+/// [`polars_type`]. Columns of unreported type become strings.
+#[cfg(feature = "polars")]
+pub(crate) fn result_to_dataframe<R: ResultRow>(
+    result: &ExecutionResult<R>,
+) -> Result<polars::prelude::DataFrame, DuneRequestError> {
+    use polars::prelude::{DataFrame, DataType, NamedFrom, Series};
+    let rows = &result.rows;
+    let mut columns = Vec::with_capacity(result.metadata.column_names.len());
+    for (name, column_type) in result.metadata.columns() {
+        let data_type = column_type.map_or(DataType::String, polars_type);
+        let series = match &data_type {
+            DataType::Boolean => Series::new(name.into(), cells(rows, name, "bool", bool_cell)?),
+            DataType::Int32 => Series::new(name.into(), cells(rows, name, "i32", int_cell::<i32>)?),
+            DataType::Int64 => Series::new(name.into(), cells(rows, name, "i64", int_cell::<i64>)?),
+            DataType::Float32 => {
+                let values = cells(rows, name, "f32", float_cell)?;
+                let values: Vec<Option<f32>> = values
+                    .into_iter()
+                    .map(|value| value.map(|value: f64| value as f32))
+                    .collect();
+                Series::new(name.into(), values)
+            }
+            DataType::Float64 => Series::new(name.into(), cells(rows, name, "f64", float_cell)?),
+            DataType::Date => Series::new(name.into(), cells(rows, name, "date", date_cell)?)
+                .cast(&data_type)
+                .map_err(|err| DuneRequestError::Encode(err.to_string()))?,
+            DataType::Datetime(_, _) => {
+                Series::new(name.into(), cells(rows, name, "timestamp", micros_cell)?)
+                    .cast(&data_type)
+                    .map_err(|err| DuneRequestError::Encode(err.to_string()))?
+            }
+            _ => Series::new(name.into(), cells(rows, name, "string", text_cell)?),
+        };
+        columns.push(series.into());
+    }
+    DataFrame::new(rows.len(), columns).map_err(|err| DuneRequestError::Encode(err.to_string()))
+}
+
+/// The values of `column` in `rows` converted by `convert`, `None` for `null` or missing ones.
+/// Values `convert` rejects fail with [`DuneRequestError::Deserialization`] naming the row.
+fn cells<R: ResultRow, T>(
+    rows: &[R],
+    column: &str,
+    expected: &str,
+    convert: impl Fn(&Value) -> Option<T>,
+) -> Result<Vec<Option<T>>, DuneRequestError> {
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| match row.cell(column) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => match convert(value) {
+                Some(converted) => Ok(Some(converted)),
+                None => Err(DuneRequestError::Deserialization {
+                    row: Some(index),
+                    column: Some(column.to_string()),
+                    value: Some(value.to_string()),
+                    expected: Some(expected.to_string()),
+                    source: format!("{value} is not a valid {expected}"),
+                    context: Box::default(),
+                }),
+            },
+        })
+        .collect()
+}
+
+fn bool_cell(value: &Value) -> Option<bool> {
+    crate::parse_utils::bool_from_value(value).ok()
+}
+
+fn int_cell<T: TryFrom<i64>>(value: &Value) -> Option<T> {
+    let integer = match value {
+        Value::Number(number) => number.as_i64()?,
+        Value::String(text) => text.trim().parse().ok()?,
+        _ => return None,
+    };
+    T::try_from(integer).ok()
+}
+
+fn float_cell(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Days since 1970-01-01.
+fn date_cell(value: &Value) -> Option<i32> {
+    let date = crate::parse_utils::parse_any_date(value.as_str()?).ok()?;
+    let days = date
+        .signed_duration_since(chrono::NaiveDate::default())
+        .num_days();
+    i32::try_from(days).ok()
+}
+
+/// Microseconds since the Unix epoch.
+fn micros_cell(value: &Value) -> Option<i64> {
+    let timestamp = crate::parse_utils::parse_any_datetime(value.as_str()?).ok()?;
+    Some(timestamp.timestamp_micros())
+}
+
+/// Strings as they are, other values as JSON text.
+fn text_cell(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    })
+}
+
+fn unsupported(column: &str, data_type: &dyn std::fmt::Debug) -> DuneRequestError {
+    DuneRequestError::Encode(format!(
+        "column {column:?} has type {data_type:?}, which has no Dune equivalent"
+    ))
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod tests {
+    use super::*;
+    use crate::response::DynamicRow;
+    use arrow_array::{Int64Array, RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn arrow_batch_to_ndjson() {
+        let schema = Schema::new(vec![
+            Field::new("block", DataType::Int64, false),
+            Field::new("label", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(arrow_to_ndjson(&batch).unwrap()).unwrap(),
+            "{\"block\":1,\"label\":\"a\"}\n{\"block\":2}\n"
+        );
+    }
+
+    #[test]
+    fn results_to_record_batch() {
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::{Date32Type, Float64Type, Int16Type, TimestampMicrosecondType};
+        use arrow_array::Array;
+        let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+            "rows": [
+                {"symbol": "ETH", "price": "3000.5", "volume": 12, "day": "2024-01-01 00:00:00.000 UTC",
+                 "date": "2024-01-02", "ok": "true", "tags": ["a"]},
+                {"symbol": null, "price": 61000, "volume": "7", "ok": false, "untyped": 1}
+            ],
+            "metadata": {
+                "column_names": ["symbol", "price", "volume", "day", "date", "ok", "tags", "untyped"],
+                "column_types": ["varchar", "decimal(38,2)", "smallint", "timestamp(3) with time zone",
+                                 "date", "boolean", "array(varchar)"],
+                "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 16,
+                "pending_time_millis": null, "execution_time_millis": 1
+            }
+        }))
+        .unwrap();
+        let batch = result_to_record_batch(&result).unwrap();
+        assert_eq!((batch.num_rows(), batch.num_columns()), (2, 8));
+        let column = |name: &str| batch.column_by_name(name).unwrap();
+        assert!(column("symbol").is_null(1));
+        assert_eq!(column("symbol").as_string::<i32>().value(0), "ETH");
+        assert_eq!(
+            column("price").as_primitive::<Float64Type>().value(0),
+            3000.5
+        );
+        assert_eq!(column("volume").as_primitive::<Int16Type>().value(1), 7);
+        assert_eq!(
+            column("day")
+                .as_primitive::<TimestampMicrosecondType>()
+                .value(0),
+            1_704_067_200_000_000
+        );
+        assert!(column("day").is_null(1));
+        assert_eq!(column("date").as_primitive::<Date32Type>().value(0), 19_724);
+        assert!(column("ok").as_boolean().value(0));
+        assert_eq!(column("tags").as_string::<i32>().value(0), "[\"a\"]");
+        assert_eq!(column("untyped").as_string::<i32>().value(1), "1");
+
+        let mut bad = result;
+        bad.rows[0]["volume"] = 40_000.into();
+        assert!(matches!(
+            result_to_record_batch(&bad),
+            Err(DuneRequestError::Deserialization { row: Some(0), .. })
+        ));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_file() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        let schema = Schema::new(vec![Field::new("block", DataType::Int64, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from(vec![Some(1), None, Some(3)]))],
+        )
+        .unwrap();
+        let path = std::env::temp_dir().join("duners-parquet-test.parquet");
+        let options = ParquetOptions {
+            max_row_group_rows: 2,
+            ..Default::default()
+        };
+        write_parquet(&batch, std::fs::File::create(&path).unwrap(), options).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert_eq!(
+            metadata.row_group(0).column(0).compression(),
+            options.compression
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "ipc")]
+    #[test]
+    fn ipc_file() {
+        use arrow_ipc::reader::FileReader;
+        let schema = Schema::new(vec![Field::new("block", DataType::Int64, true)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from(vec![Some(1), None, Some(3)]))],
+        )
+        .unwrap();
+        let mut file = Vec::new();
+        write_ipc(&batch, &mut file).unwrap();
+        assert!(file.starts_with(b"ARROW1"));
+        let reader = FileReader::try_new(std::io::Cursor::new(file), None).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches, vec![batch]);
+    }
+
+    #[cfg(feature = "datafusion")]
+    #[tokio::test]
+    async fn datafusion_batches() {
+        use datafusion::prelude::SessionContext;
+        let schema = Schema::new(vec![
+            Field::new("block", DataType::Int64, true),
+            Field::new("hash", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), None, Some(3)])),
+                Arc::new(StringArray::from(vec![Some("0xa"), Some("0xb"), None])),
+            ],
+        )
+        .unwrap();
+        let converted = to_datafusion_batch(&batch).unwrap();
+        assert_eq!(converted.num_rows(), 3);
+        assert_eq!(converted.schema().field(1).name(), "hash");
+
+        let ctx = SessionContext::new();
+        ctx.register_batch("blocks", converted).unwrap();
+        let sums = ctx
+            .sql("SELECT sum(block) AS total, count(hash) AS hashes FROM blocks")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let row = datafusion::arrow::util::pretty::pretty_format_batches(&sums)
+            .unwrap()
+            .to_string();
+        assert!(row.contains("| 4     | 2      |"), "{row}");
+    }
+
+    #[test]
+    fn unsupported_arrow_type() {
+        let schema = Schema::new(vec![Field::new(
+            "tags",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            true,
+        )]);
+        assert!(matches!(
+            schema_from_arrow(&schema),
+            Err(DuneRequestError::Encode(_))
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "polars"))]
+mod polars_tests {
+    use super::*;
+    use polars::prelude::{df, DataType};
+
+    #[test]
+    fn polars_frame_to_ndjson() {
+        let mut frame = df!("block" => [1i64, 2], "label" => [Some("a"), None]).unwrap();
+        assert_eq!(
+            schema_from_polars(frame.schema()).unwrap(),
+            vec![
+                ColumnDef::new("block", DuneType::Bigint).nullable(),
+                ColumnDef::new("label", DuneType::Varchar).nullable(),
+            ]
+        );
+        assert_eq!(
+            String::from_utf8(polars_to_ndjson(&mut frame).unwrap()).unwrap(),
+            "{\"block\":1,\"label\":\"a\"}\n{\"block\":2,\"label\":null}\n"
+        );
+        assert_eq!(dune_type_from_polars(&DataType::Null), None);
+    }
+
+    #[test]
+    fn results_to_dataframe() {
+        use polars::prelude::{AnyValue, TimeUnit};
+        let result: ExecutionResult<Value> = serde_json::from_value(serde_json::json!({
+            "rows": [
+                {"symbol": "ETH", "price": "3000.5", "volume": 12, "day": "2024-01-01 00:00:00.000 UTC",
+                 "date": "2024-01-02", "ok": true, "amount": "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                 "tags": ["a"], "untyped": 1},
+                {"symbol": null, "price": 61000, "volume": "7", "ok": "false"}
+            ],
+            "metadata": {
+                "column_names": ["symbol", "price", "volume", "day", "date", "ok", "amount", "tags", "untyped"],
+                "column_types": ["varchar", "double", "integer", "timestamp(3) with time zone", "date",
+                                 "boolean", "uint256", "array(varchar)"],
+                "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 18,
+                "pending_time_millis": null, "execution_time_millis": 1
+            }
+        }))
+        .unwrap();
+        let frame = result_to_dataframe(&result).unwrap();
+        assert_eq!(frame.shape(), (2, 9));
+        let types: Vec<_> = frame.dtypes();
+        assert_eq!(
+            types,
+            vec![
+                DataType::String,
+                DataType::Float64,
+                DataType::Int32,
+                DataType::Datetime(TimeUnit::Microseconds, None),
+                DataType::Date,
+                DataType::Boolean,
+                DataType::String,
+                DataType::String,
+                DataType::String,
+            ]
+        );
+        let value = |column: &str, row: usize| frame.column(column).unwrap().get(row).unwrap();
+        assert_eq!(value("symbol", 1), AnyValue::Null);
+        assert_eq!(value("price", 0), AnyValue::Float64(3000.5));
+        assert_eq!(value("volume", 1), AnyValue::Int32(7));
+        assert_eq!(
+            value("day", 0),
+            AnyValue::Datetime(1_704_067_200_000_000, TimeUnit::Microseconds, None)
+        );
+        assert_eq!(value("date", 0), AnyValue::Date(19_724));
+        assert_eq!(value("ok", 1), AnyValue::Boolean(false));
+        assert_eq!(value("tags", 0), AnyValue::String("[\"a\"]"));
+        assert_eq!(value("untyped", 0), AnyValue::String("1"));
+
+        let mut bad = result;
+        bad.rows[1]["volume"] = "seven".into();
+        match result_to_dataframe(&bad).unwrap_err() {
+            DuneRequestError::Deserialization { row, column, .. } => {
+                assert_eq!((row, column.as_deref()), (Some(1), Some("volume")));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+}
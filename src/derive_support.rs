@@ -0,0 +1,264 @@
+//! Runtime support for `#[derive(DuneRow)]` and `#[derive(DuneParams)]`; not a public API.
+
+use crate::parameters::Parameter;
+use crate::response::{DuneColumnType, DynamicRow};
+use crate::rowset::coerce;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+pub use serde;
+
+/// The columns of one row, removed as fields are filled.
+pub struct Columns(DynamicRow);
+
+impl<'de> Deserialize<'de> for Columns {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DynamicRow::deserialize(deserializer).map(Columns)
+    }
+}
+
+impl Columns {
+    /// Removes the value of the first of `columns` (a name and its aliases) the row has,
+    /// matching names case-insensitively unless `exact`.
+    pub fn take(&mut self, columns: &[&str], exact: bool) -> Option<Value> {
+        if let Some(value) = columns.iter().find_map(|column| self.0.remove(*column)) {
+            return Some(value);
+        }
+        if exact {
+            return None;
+        }
+        let columns: Vec<_> = columns.iter().map(|column| column.to_lowercase()).collect();
+        let key = self
+            .0
+            .keys()
+            .find(|key| columns.contains(&key.to_lowercase()))?
+            .clone();
+        self.0.remove(&key)
+    }
+
+    /// Fails if columns are left that no field read.
+    pub fn deny_remaining(&self) -> Result<(), String> {
+        match self.0.is_empty() {
+            true => Ok(()),
+            false => {
+                let columns: Vec<_> = self.0.keys().map(|key| format!("`{key}`")).collect();
+                Err(format!("unexpected columns {}", columns.join(", ")))
+            }
+        }
+    }
+}
+
+/// How a string value is converted for a field, derived from the field's type.
+pub enum Hint {
+    /// `f32` / `f64`.
+    Float,
+    /// Integer types.
+    Integer,
+    /// `bool`.
+    Bool,
+    /// `DateTime<Utc>`.
+    Timestamp,
+    /// No conversion.
+    Other,
+}
+
+impl Hint {
+    fn column_type(&self) -> Option<DuneColumnType> {
+        match self {
+            Hint::Float => Some(DuneColumnType::Double),
+            Hint::Integer => Some(DuneColumnType::Bigint),
+            Hint::Bool => Some(DuneColumnType::Boolean),
+            Hint::Timestamp => Some(DuneColumnType::Timestamp {
+                precision: None,
+                with_time_zone: true,
+            }),
+            Hint::Other => None,
+        }
+    }
+}
+
+/// Deserializes the value of `column` (`None` if the row does not have it) into `T`, converting
+/// string-encoded values according to `hint`.
+pub fn decode<T: DeserializeOwned>(
+    column: &str,
+    value: Option<Value>,
+    hint: Hint,
+) -> Result<T, String> {
+    let missing = value.is_none();
+    let value = value.unwrap_or(Value::Null);
+    let error = match serde_json::from_value(value.clone()) {
+        Ok(decoded) => return Ok(decoded),
+        Err(err) => err,
+    };
+    if let Some(coerced) = coerce(&value, hint.column_type().as_ref()) {
+        if let Ok(decoded) = serde_json::from_value(coerced) {
+            return Ok(decoded);
+        }
+    }
+    match missing {
+        true => Err(format!("missing column `{column}`")),
+        false => Err(format!("column `{column}`: {error}")),
+    }
+}
+
+/// A field's parameter as a list/enum parameter (`#[dune(list)]`).
+pub fn list(parameter: Option<Parameter>) -> Option<Parameter> {
+    parameter.map(Parameter::into_list)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chrono::{DateTime, Utc};
+    use crate::error::deserialize_row;
+    use crate::schema::{self, FieldKind, FieldSpec, RowSchema};
+    use crate::DuneRow;
+    use serde_json::json;
+
+    #[derive(DuneRow, Debug, PartialEq)]
+    struct Row {
+        symbol: String,
+        #[dune(rename = "Max Price")]
+        max_price: f64,
+        #[dune(alias = "📊 Volume", alias = "vol")]
+        volume: Option<u64>,
+        day: DateTime<Utc>,
+        r#type: Option<String>,
+    }
+
+    #[derive(DuneRow, Debug, PartialEq)]
+    #[dune(strict)]
+    struct StrictRow {
+        symbol: String,
+    }
+
+    #[derive(DuneRow, Debug, PartialEq)]
+    #[dune(lenient)]
+    struct LenientRow {
+        symbol: String,
+        price: Option<f64>,
+    }
+
+    #[test]
+    fn derived_rows() {
+        let value = json!({
+            "Symbol": "ETH", "max price": "3000.5", "volume": "12",
+            "day": "2024-01-01 00:00:00.000 UTC", "extra": true
+        });
+        let row: Row = deserialize_row(0, &value).unwrap();
+        assert_eq!(row.symbol, "ETH");
+        let aliased =
+            json!({"symbol": "ETH", "Max Price": 1, "📊 volume": 3, "day": "2024-01-01 00:00:00"});
+        assert_eq!(deserialize_row::<Row>(0, &aliased).unwrap().volume, Some(3));
+        assert_eq!(row.max_price, 3000.5);
+        assert_eq!(row.volume, Some(12));
+        assert_eq!(row.day.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(row.r#type, None);
+
+        let error = deserialize_row::<Row>(3, &json!({"symbol": "ETH"})).unwrap_err();
+        assert!(error.to_string().contains("missing column `Max Price`"));
+        let error =
+            deserialize_row::<Row>(3, &json!({"symbol": "ETH", "Max Price": "x"})).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("column `Max Price`: invalid type"));
+    }
+
+    #[test]
+    fn strict_and_lenient_rows() {
+        let strict = deserialize_row::<StrictRow>(0, &json!({"symbol": "ETH"})).unwrap();
+        assert_eq!(strict.symbol, "ETH");
+        let error = deserialize_row::<StrictRow>(0, &json!({"SYMBOL": "ETH"})).unwrap_err();
+        assert!(error.to_string().contains("missing column `symbol`"));
+        let error =
+            deserialize_row::<StrictRow>(0, &json!({"symbol": "ETH", "price": 1})).unwrap_err();
+        assert!(error.to_string().contains("unexpected columns `price`"));
+
+        let lenient =
+            deserialize_row::<LenientRow>(0, &json!({"symbol": "ETH", "price": "n/a"})).unwrap();
+        assert_eq!(lenient.price, None);
+        assert!(deserialize_row::<Row>(0, &json!({"symbol": "ETH", "volume": "n/a"})).is_err());
+    }
+
+    #[test]
+    fn derived_schema() {
+        assert_eq!(
+            Row::schema(),
+            vec![
+                FieldSpec::new("symbol", FieldKind::String),
+                FieldSpec::new("Max Price", FieldKind::Float),
+                FieldSpec::new("volume", FieldKind::Integer).optional(),
+                FieldSpec::new("day", FieldKind::Timestamp),
+                FieldSpec::new("type", FieldKind::String).optional(),
+            ]
+        );
+        let metadata = serde_json::from_value(json!({
+            "column_names": ["symbol"], "column_types": ["varchar"],
+            "result_set_bytes": 0, "total_row_count": 0, "datapoint_count": 0,
+            "pending_time_millis": null, "execution_time_millis": 1
+        }))
+        .unwrap();
+        assert_eq!(schema::check::<StrictRow>(&metadata), vec![]);
+    }
+
+    #[test]
+    fn derived_parameters() {
+        use crate::chrono::NaiveDate;
+        use crate::parameters::ToParameters;
+        use crate::{DuneParams, Parameter};
+
+        #[derive(DuneParams)]
+        #[dune(rename_all = "Title Case")]
+        struct Params {
+            wallet_address: String,
+            start_day: NaiveDate,
+            #[dune(rename = "tokens")]
+            symbols: Vec<String>,
+            #[dune(list)]
+            chain: &'static str,
+            limit: Option<u32>,
+            #[dune(skip)]
+            #[allow(dead_code)]
+            label: String,
+            r#type: f64,
+        }
+
+        #[derive(DuneParams)]
+        #[dune(rename_all = "camelCase")]
+        struct Camel {
+            min_amount_usd: u64,
+        }
+
+        let params = Params {
+            wallet_address: "0xabc".into(),
+            start_day: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+            symbols: vec!["ETH".into(), "USDC".into()],
+            chain: "base",
+            limit: Some(10),
+            label: "ignored".into(),
+            r#type: 0.5,
+        };
+        let keys: Vec<_> = params
+            .to_parameters()
+            .into_iter()
+            .map(|parameter| (parameter.key, parameter.value))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("Wallet Address", "0xabc"),
+                ("Start Day", "2024-02-29 00:00:00"),
+                ("tokens", r#"["ETH","USDC"]"#),
+                ("Chain", "base"),
+                ("Limit", "10"),
+                ("Type", "0.5"),
+            ]
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+        );
+        assert_eq!(params.to_parameters()[3], Parameter::list("Chain", "base"));
+        assert_eq!(
+            Camel { min_amount_usd: 1 }.to_parameters(),
+            [Parameter::number("minAmountUsd", 1)]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Comparing two result sets of the same query.
+//!
+//! [`diff`] matches the rows of an earlier and a later execution on key columns and reports the
+//! rows that were added, removed or changed, e.g. to alert when a daily snapshot moves.
+//! Results work as `Value` or [`DynamicRow`] rows, so a snapshot saved with `serde_json` can be
+//! compared with a fresh [`refresh`](crate::client::DuneClient::refresh).
+//! [`DuneClient::watch`](crate::client::DuneClient::watch) does this periodically, yielding each
+//! difference as a [`ChangeEvent`].
+
+use crate::response::{DynamicRow, GetResultResponse, ResultRow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+/// The differences between two result sets, from [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResultDiff {
+    /// Rows whose key only appears in the later result, in its order.
+    pub added: Vec<DynamicRow>,
+    /// Rows whose key only appears in the earlier result, in its order.
+    pub removed: Vec<DynamicRow>,
+    /// Rows whose key appears in both results with different values, in the later result's
+    /// order.
+    pub changed: Vec<ChangedRow>,
+}
+
+impl ResultDiff {
+    /// Whether the results hold the same rows.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The differences as events: added rows, then changed rows, then removed rows.
+    pub fn into_events(self) -> Vec<ChangeEvent> {
+        let added = self.added.into_iter().map(ChangeEvent::Added);
+        let changed = self.changed.into_iter().map(ChangeEvent::Changed);
+        let removed = self.removed.into_iter().map(ChangeEvent::Removed);
+        added.chain(changed).chain(removed).collect()
+    }
+}
+
+/// One difference between two result sets, as yielded by
+/// [`DuneClient::watch`](crate::client::DuneClient::watch).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChangeEvent {
+    /// A row whose key was not in the earlier result.
+    Added(DynamicRow),
+    /// A row whose key is in both results, with different values.
+    Changed(ChangedRow),
+    /// A row whose key is no longer in the result.
+    Removed(DynamicRow),
+}
+
+/// A row present in both results with different values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangedRow {
+    /// Values of the key columns, in the order they were given.
+    pub key: Vec<Value>,
+    /// Columns whose values differ, in result column order.
+    pub columns: Vec<String>,
+    /// The row in the earlier result.
+    pub before: DynamicRow,
+    /// The row in the later result.
+    pub after: DynamicRow,
+}
+
+/// Compares `before` with `after`, matching rows on the values of `key_columns`.
+///
+/// A column missing from a row reads as `null`. Values are compared as JSON, so `"1"` and `1`
+/// differ. With no key columns, whole rows are matched: changes then show as a removed and an
+/// added row. Rows sharing a key are paired in result order, so duplicates are only added or
+/// removed when their count changes.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::diff::diff;
+/// use duners::response::GetResultResponse;
+/// use serde_json::{json, Value};
+///
+/// fn snapshot(rows: Value) -> GetResultResponse<Value> {
+///     serde_json::from_value(json!({
+///         "execution_id": "01HKZJ2683PHF9Q9PHHQ8FW4Q1", "query_id": 971694,
+///         "state": "QUERY_STATE_COMPLETED", "submitted_at": "2024-01-01T00:00:00.000Z",
+///         "result": {
+///             "rows": rows,
+///             "metadata": {
+///                 "column_names": ["symbol", "price"], "result_set_bytes": 0,
+///                 "total_row_count": 2, "datapoint_count": 4,
+///                 "pending_time_millis": null, "execution_time_millis": 1
+///             }
+///         }
+///     }))
+///     .unwrap()
+/// }
+///
+/// let monday = snapshot(json!([{"symbol": "ETH", "price": 3000}, {"symbol": "DAI", "price": 1}]));
+/// let tuesday = snapshot(json!([{"symbol": "ETH", "price": 3100}, {"symbol": "BTC", "price": 61000}]));
+/// let changes = diff(&monday, &tuesday, &["symbol"]);
+/// assert_eq!(changes.added[0]["symbol"], "BTC");
+/// assert_eq!(changes.removed[0]["symbol"], "DAI");
+/// assert_eq!(changes.changed[0].key, vec![json!("ETH")]);
+/// assert_eq!(changes.changed[0].columns, vec!["price"]);
+/// ```
+pub fn diff<T: ResultRow>(
+    before: &GetResultResponse<T>,
+    after: &GetResultResponse<T>,
+    key_columns: &[&str],
+) -> ResultDiff {
+    let mut columns = before.result.metadata.column_names.clone();
+    for column in &after.result.metadata.column_names {
+        if !columns.contains(column) {
+            columns.push(column.clone());
+        }
+    }
+    let key_columns: Vec<&str> = match key_columns {
+        [] => columns.iter().map(String::as_str).collect(),
+        keys => keys.to_vec(),
+    };
+    let key = |row: &T| -> Vec<Value> {
+        key_columns
+            .iter()
+            .map(|column| row.cell(column).cloned().unwrap_or(Value::Null))
+            .collect()
+    };
+
+    // JSON text of the key, since `Value` does not implement `Hash`.
+    let mut earlier: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (index, row) in before.result.rows.iter().enumerate() {
+        let key = Value::Array(key(row)).to_string();
+        earlier.entry(key).or_default().push_back(index);
+    }
+    let mut changes = ResultDiff::default();
+    let mut matched = vec![false; before.result.rows.len()];
+    for row in &after.result.rows {
+        let key = key(row);
+        let previous = earlier
+            .get_mut(&Value::Array(key.clone()).to_string())
+            .and_then(VecDeque::pop_front);
+        let Some(previous) = previous else {
+            changes.added.push(to_dynamic(row, &columns));
+            continue;
+        };
+        matched[previous] = true;
+        let previous = &before.result.rows[previous];
+        let differing: Vec<String> = columns
+            .iter()
+            .filter(|column| previous.cell(column) != row.cell(column))
+            .cloned()
+            .collect();
+        if !differing.is_empty() {
+            changes.changed.push(ChangedRow {
+                key,
+                columns: differing,
+                before: to_dynamic(previous, &columns),
+                after: to_dynamic(row, &columns),
+            });
+        }
+    }
+    changes.removed = before
+        .result
+        .rows
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(row, _)| to_dynamic(row, &columns))
+        .collect();
+    changes
+}
+
+/// The values of `columns` in `row`, skipping columns it does not have.
+fn to_dynamic<T: ResultRow>(row: &T, columns: &[String]) -> DynamicRow {
+    columns
+        .iter()
+        .filter_map(|column| Some((column.clone(), row.cell(column)?.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn results(columns: Value, rows: Value) -> GetResultResponse<Value> {
+        serde_json::from_value(json!({
+            "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "result": {
+                "rows": rows,
+                "metadata": {
+                    "column_names": columns, "result_set_bytes": 0, "total_row_count": 0,
+                    "datapoint_count": 0, "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn keyed_changes() {
+        let before = results(
+            json!(["day", "chain", "volume"]),
+            json!([
+                {"day": "2024-01-01", "chain": "ethereum", "volume": 10},
+                {"day": "2024-01-01", "chain": "base", "volume": 5},
+                {"day": "2024-01-02", "chain": "ethereum", "volume": 7}
+            ]),
+        );
+        let after = results(
+            json!(["day", "chain", "volume", "fees"]),
+            json!([
+                {"day": "2024-01-02", "chain": "ethereum", "volume": 7, "fees": 1},
+                {"day": "2024-01-01", "chain": "ethereum", "volume": 10},
+                {"day": "2024-01-03", "chain": "ethereum", "volume": 3}
+            ]),
+        );
+        let changes = diff(&before, &after, &["day", "chain"]);
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0]["day"], "2024-01-03");
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.removed[0]["chain"], "base");
+        assert_eq!(changes.changed.len(), 1);
+        assert_eq!(
+            changes.changed[0].key,
+            vec![json!("2024-01-02"), json!("ethereum")]
+        );
+        assert_eq!(changes.changed[0].columns, vec!["fees"]);
+        assert!(!changes.is_empty());
+        assert!(diff(&after, &after, &["day", "chain"]).is_empty());
+
+        let events = changes.into_events();
+        assert!(matches!(&events[0], ChangeEvent::Added(row) if row["day"] == "2024-01-03"));
+        assert!(matches!(&events[1], ChangeEvent::Changed(row) if row.columns == ["fees"]));
+        assert!(matches!(&events[2], ChangeEvent::Removed(row) if row["chain"] == "base"));
+    }
+
+    #[test]
+    fn whole_row_and_duplicate_keys() {
+        let before = results(json!(["a"]), json!([{"a": 1}, {"a": 1}, {"a": 2}]));
+        let after = results(json!(["a"]), json!([{"a": 1}, {"a": 3}]));
+        let changes = diff(&before, &after, &[]);
+        assert!(changes.changed.is_empty());
+        assert_eq!(
+            changes.added,
+            vec![json!({"a": 3}).as_object().unwrap().clone()]
+        );
+        assert_eq!(
+            changes.removed,
+            vec![
+                json!({"a": 1}).as_object().unwrap().clone(),
+                json!({"a": 2}).as_object().unwrap().clone()
+            ]
+        );
+    }
+}
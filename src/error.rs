@@ -1,13 +1,126 @@
 //! Error types for Dune API requests and response parsing.
 
+use crate::query::QueryId;
+use crate::response::{ExecutionError, RowCountIssue};
+use crate::schema::SchemaIssue;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Error payload returned by the Dune API when a request fails (e.g. invalid API key, query not found).
 #[derive(Deserialize, Debug)]
 pub struct DuneError {
     /// Human-readable error message from Dune.
     pub error: String,
+    /// Structured details, sent with some errors (e.g. about query parameters).
+    #[serde(default)]
+    pub details: Option<ErrorDetails>,
+}
+
+/// Structured details of a rejected request, telling what to fix.
+///
+/// # Example
+///
+/// ```no_run
+/// use duners::{DuneClient, DuneRequestError, Parameter};
+///
+/// # async fn run() -> Result<(), DuneRequestError> {
+/// let client = DuneClient::from_env();
+/// match client.execute_query(971694, Some(vec![Parameter::number("Days", "7")])).await {
+///     Err(DuneRequestError::InvalidRequest { details, .. }) => {
+///         for name in &details.missing_parameters {
+///             eprintln!("missing parameter {name}");
+///         }
+///         for invalid in &details.invalid_parameters {
+///             eprintln!("bad value for {}: {:?}", invalid.name, invalid.message);
+///         }
+///     }
+///     other => println!("{:?}", other?.execution_id),
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ErrorDetails {
+    /// Query parameters the query requires but the request did not supply.
+    #[serde(default, alias = "missing_params")]
+    pub missing_parameters: Vec<String>,
+    /// Query parameters whose value was rejected.
+    #[serde(default, alias = "invalid_params")]
+    pub invalid_parameters: Vec<InvalidParameter>,
+    /// Any other fields of the details, as sent.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl ErrorDetails {
+    /// Whether no details were sent.
+    pub fn is_empty(&self) -> bool {
+        self.missing_parameters.is_empty()
+            && self.invalid_parameters.is_empty()
+            && self.other.is_empty()
+    }
+}
+
+/// A query parameter rejected by Dune.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InvalidParameter {
+    /// Parameter key.
+    #[serde(alias = "key")]
+    pub name: String,
+    /// Why the value was rejected, if given.
+    #[serde(default, alias = "reason")]
+    pub message: Option<String>,
+}
+
+/// The HTTP exchange an error occurred in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpContext {
+    /// Response status, if a response was received.
+    pub status: Option<u16>,
+    /// Request URL (empty when unknown).
+    pub url: String,
+    /// ID Dune (or its CDN) assigned to the request, if the response named one; quote it
+    /// when contacting Dune support.
+    pub request_id: Option<String>,
+}
+
+impl HttpContext {
+    pub(crate) fn new(status: StatusCode, url: &reqwest::Url, headers: &HeaderMap) -> Self {
+        HttpContext {
+            status: Some(status.as_u16()),
+            url: url.to_string(),
+            request_id: request_id(headers),
+        }
+    }
+}
+
+impl fmt::Display for HttpContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "HTTP {status} from {}", self.url)?,
+            None => write!(f, "{}", self.url)?,
+        }
+        match &self.request_id {
+            Some(id) => write!(f, ", request ID {id}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Response headers identifying a request, most specific first.
+const REQUEST_ID_HEADERS: [&str; 4] = ["x-request-id", "x-amzn-requestid", "x-amz-cf-id", "cf-ray"];
+
+/// The request ID from a response's headers (`X-Request-Id` or a similar proxy header).
+pub(crate) fn request_id(headers: &HeaderMap) -> Option<String> {
+    REQUEST_ID_HEADERS.iter().find_map(|name| {
+        let id = headers.get(*name)?.to_str().ok()?.trim();
+        (!id.is_empty()).then(|| id.to_string())
+    })
 }
 
 /// All errors that can occur when calling the Dune API or parsing responses.
@@ -21,30 +134,516 @@ pub struct DuneError {
 /// use duners::DuneRequestError;
 ///
 /// fn handle_error(e: DuneRequestError) {
-///     eprintln!("{}", e);
+///     match e {
+///         DuneRequestError::RateLimited { retry_after, .. } => {
+///             eprintln!("slow down, retry in {retry_after:?}")
+///         }
+///         DuneRequestError::Unauthorized { .. } => eprintln!("check DUNE_API_KEY"),
+///         other => eprintln!("{other} (HTTP status {:?})", other.status()),
+///     }
 /// }
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DuneRequestError {
-    /// Error returned by the Dune API. Common messages include:
-    /// - `"invalid API Key"`
-    /// - `"Query not found"`
-    /// - `"The requested execution ID (ID: …) is invalid."`
+    /// Error returned by the Dune API with a status not covered by a more specific variant.
     Dune(String),
-    /// Network or HTTP errors from the underlying request (e.g. connection failed, timeout).
-    Request(String),
+    /// The request was rejected as malformed (HTTP 400 / 422). Common messages include:
+    /// - `"Query not found"` (reported as [`QueryNotFound`](DuneRequestError::QueryNotFound)
+    ///   by the query endpoints)
+    /// - `"The requested execution ID (ID: …) is invalid."`
+    InvalidRequest {
+        /// Dune's error message.
+        message: String,
+        /// What to fix, when Dune says (empty otherwise).
+        details: Box<ErrorDetails>,
+        /// Status and URL of the request.
+        context: HttpContext,
+    },
+    /// The API key is missing, invalid or lacks access (HTTP 401 / 403).
+    Unauthorized {
+        /// Dune's error message.
+        message: String,
+        /// Status and URL of the request.
+        context: HttpContext,
+    },
+    /// The requested resource does not exist (HTTP 404).
+    NotFound {
+        /// Dune's error message.
+        message: String,
+        /// Status and URL of the request.
+        context: HttpContext,
+    },
+    /// Too many requests (HTTP 429), once the client's
+    /// [`rate_limit_retries`](crate::client::DuneClientBuilder::rate_limit_retries) are used up.
+    RateLimited {
+        /// How long Dune asked to wait, from the `Retry-After` header.
+        retry_after: Option<Duration>,
+        /// Dune's error message.
+        message: String,
+        /// Status and URL of the request.
+        context: HttpContext,
+    },
+    /// The plan's credits or usage quota are exhausted (HTTP 402).
+    QuotaExceeded {
+        /// Dune's error message.
+        message: String,
+        /// Status and URL of the request.
+        context: HttpContext,
+    },
+    /// Dune failed to handle the request (HTTP 5xx).
+    ServerError {
+        /// Dune's error message, or the response body.
+        message: String,
+        /// Status and URL of the request.
+        context: HttpContext,
+    },
+    /// The request did not complete within the configured timeout.
+    Timeout {
+        /// URL of the request (no status: no response arrived in time).
+        context: HttpContext,
+    },
+    /// A response body could not be deserialized into the expected type.
+    Deserialization {
+        /// Index of the result row that failed, if the error is inside `result.rows`.
+        row: Option<usize>,
+        /// Column (field) of that row that failed, if known.
+        column: Option<String>,
+        /// The offending JSON value (the whole row when a field is missing), shortened to
+        /// [`MAX_VALUE_LEN`] characters.
+        value: Option<String>,
+        /// What the Rust type expected instead, as described by serde (e.g. `f64`).
+        expected: Option<String>,
+        /// The underlying serde error.
+        source: String,
+        /// Status and URL of the request, if the body came from one.
+        context: Box<HttpContext>,
+    },
+    /// The result's columns do not match the row type's [`RowSchema`](crate::schema::RowSchema),
+    /// see [`ResultMetaData::check_schema`](crate::response::ResultMetaData::check_schema).
+    SchemaMismatch(Vec<SchemaIssue>),
+    /// Query parameters were rejected before sending a request: values
+    /// [`Parameter::from_map`](crate::Parameter::from_map) cannot type, or parameters not
+    /// matching the query's definition (see [`validate`](crate::parameters::validate)).
+    InvalidParameters(Vec<InvalidParameter>),
+    /// Results hold fewer (or more) rows than their metadata reports, see
+    /// [`GetResultResponse::check_row_count`](crate::response::GetResultResponse::check_row_count).
+    IncompleteResults(RowCountIssue),
+    /// The execution ended in [`Failed`](crate::response::ExecutionStatus::Failed); the error
+    /// tells SQL errors ([`is_sql_error`](ExecutionError::is_sql_error)) from engine
+    /// timeouts ([`is_timeout`](ExecutionError::is_timeout)).
+    ExecutionFailed(ExecutionError),
+    /// Waiting for an execution failed while polling its status. The execution may still be
+    /// running: pass the ID to [`get_status`](crate::client::DuneClient::get_status) /
+    /// [`get_results`](crate::client::DuneClient::get_results), or use
+    /// [`resume`](crate::client::DuneClient::resume), instead of executing again.
+    PollFailed {
+        /// The execution that was being waited for.
+        execution_id: String,
+        /// The error of the last status poll.
+        source: Box<DuneRequestError>,
+    },
+    /// Network or HTTP errors from the underlying request (e.g. connection failed). The
+    /// original [`reqwest::Error`] is kept as the [`source`](std::error::Error::source).
+    Request(TransportError),
+    /// Local I/O errors (e.g. writing a downloaded file).
+    Io(String),
+    /// The query ID does not refer to a saved query: either `0` (rejected before sending a
+    /// request) or an ID Dune reported as not found.
+    QueryNotFound(QueryId),
+    /// A query ID could not be parsed (not a number, or out of `u32` range).
+    InvalidQueryId(String),
+    /// A SQL template could not be rendered by `template::render_sql` (feature `minijinja`).
+    Template(String),
+    /// Data could not be encoded for upload (e.g. a column type with no Dune equivalent), or
+    /// results could not be converted to a columnar format.
+    Encode(String),
+    /// Invalid client configuration (e.g. a malformed header, or no API key).
+    Config(String),
 }
 
 impl fmt::Display for DuneRequestError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DuneRequestError::Dune(msg) => write!(f, "Dune API error: {}", msg),
-            DuneRequestError::Request(msg) => write!(f, "request error: {}", msg),
+            DuneRequestError::InvalidRequest {
+                message,
+                details,
+                context,
+            } => {
+                write!(f, "invalid request ({context}): {message}")?;
+                if !details.missing_parameters.is_empty() {
+                    write!(
+                        f,
+                        "; missing parameters: {}",
+                        details.missing_parameters.join(", ")
+                    )?;
+                }
+                if !details.invalid_parameters.is_empty() {
+                    let names: Vec<_> = details
+                        .invalid_parameters
+                        .iter()
+                        .map(|invalid| invalid.name.as_str())
+                        .collect();
+                    write!(f, "; invalid parameters: {}", names.join(", "))?;
+                }
+                Ok(())
+            }
+            DuneRequestError::Unauthorized { message, context } => {
+                write!(f, "unauthorized ({context}): {message}")
+            }
+            DuneRequestError::NotFound { message, context } => {
+                write!(f, "not found ({context}): {message}")
+            }
+            DuneRequestError::RateLimited {
+                retry_after,
+                message,
+                context,
+            } => {
+                write!(f, "rate limited ({context}): {message}")?;
+                match retry_after {
+                    Some(delay) => write!(f, "; retry after {}s", delay.as_secs()),
+                    None => Ok(()),
+                }
+            }
+            DuneRequestError::QuotaExceeded { message, context } => {
+                write!(f, "quota exceeded ({context}): {message}")
+            }
+            DuneRequestError::ServerError { message, context } => {
+                write!(f, "server error ({context}): {message}")
+            }
+            DuneRequestError::Timeout { context } => write!(f, "request timed out ({context})"),
+            DuneRequestError::SchemaMismatch(issues) => {
+                let issues: Vec<_> = issues.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "result does not match the row type: {}",
+                    issues.join("; ")
+                )
+            }
+            DuneRequestError::InvalidParameters(invalid) => {
+                let invalid: Vec<_> = invalid
+                    .iter()
+                    .map(|invalid| match &invalid.message {
+                        Some(message) => format!("{} ({message})", invalid.name),
+                        None => invalid.name.clone(),
+                    })
+                    .collect();
+                write!(f, "invalid parameters: {}", invalid.join("; "))
+            }
+            DuneRequestError::IncompleteResults(issue) => write!(f, "incomplete results: {issue}"),
+            DuneRequestError::ExecutionFailed(err) => write!(f, "execution failed: {err}"),
+            DuneRequestError::PollFailed {
+                execution_id,
+                source,
+            } => write!(f, "polling execution {execution_id} failed: {source}"),
+            DuneRequestError::Deserialization {
+                row,
+                column,
+                value,
+                source,
+                context,
+                ..
+            } => {
+                write!(f, "could not deserialize response")?;
+                if !context.url.is_empty() {
+                    write!(f, " ({context})")?;
+                }
+                if let Some(row) = row {
+                    write!(f, " at row {row}")?;
+                }
+                if let Some(column) = column {
+                    write!(f, ", column {column:?}")?;
+                }
+                if let Some(value) = value {
+                    write!(f, ", value {value}")?;
+                }
+                write!(f, ": {source}")
+            }
+            DuneRequestError::Request(err) => write!(f, "request error: {}", err),
+            DuneRequestError::Io(msg) => write!(f, "I/O error: {}", msg),
+            DuneRequestError::QueryNotFound(id) => write!(f, "query {} not found", id),
+            DuneRequestError::InvalidQueryId(msg) => write!(f, "invalid query ID {}", msg),
+            DuneRequestError::Template(msg) => write!(f, "template error: {}", msg),
+            DuneRequestError::Encode(msg) => write!(f, "encoding error: {}", msg),
+            DuneRequestError::Config(msg) => write!(f, "configuration error: {}", msg),
         }
     }
 }
 
-impl std::error::Error for DuneRequestError {}
+impl std::error::Error for DuneRequestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DuneRequestError::Request(err) => Some(err),
+            DuneRequestError::PollFailed { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// A [`reqwest::Error`] that prevented a request from completing (connection refused, TLS
+/// failure, invalid URL, …), shared so [`DuneRequestError`] stays cheap to clone.
+///
+/// Two transport errors are equal when their messages are.
+#[derive(Debug, Clone)]
+pub struct TransportError(Arc<reqwest::Error>);
+
+impl TransportError {
+    /// The underlying reqwest error.
+    pub fn get_ref(&self) -> &reqwest::Error {
+        &self.0
+    }
+
+    /// Whether the connection could not be established.
+    pub fn is_connect(&self) -> bool {
+        self.0.is_connect()
+    }
+
+    /// Whether the request timed out.
+    pub fn is_timeout(&self) -> bool {
+        self.0.is_timeout()
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for TransportError {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl DuneRequestError {
+    /// Status and URL of the request, for errors tied to one.
+    pub fn context(&self) -> Option<&HttpContext> {
+        match self {
+            DuneRequestError::Unauthorized { context, .. }
+            | DuneRequestError::InvalidRequest { context, .. }
+            | DuneRequestError::NotFound { context, .. }
+            | DuneRequestError::RateLimited { context, .. }
+            | DuneRequestError::QuotaExceeded { context, .. }
+            | DuneRequestError::ServerError { context, .. }
+            | DuneRequestError::Timeout { context } => Some(context),
+            DuneRequestError::Deserialization { context, .. } => Some(context),
+            DuneRequestError::PollFailed { source, .. } => source.context(),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response the error came from, if known.
+    pub fn status(&self) -> Option<u16> {
+        self.context().and_then(|context| context.status)
+    }
+
+    /// Whether retrying the request may succeed: network failures, timeouts, rate limits and
+    /// server errors. Requests that could not be built (e.g. an invalid URL) or whose response
+    /// body could not be decoded fail the same way again, so they are not retryable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let status = match client.get_status("your-execution-id").await {
+    ///     Err(err) if err.is_retryable() => client.get_status("your-execution-id").await?,
+    ///     other => other?,
+    /// };
+    /// # Ok(()) }
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DuneRequestError::Request(transport) => {
+                let err = transport.get_ref();
+                !err.is_builder() && !err.is_decode()
+            }
+            DuneRequestError::Timeout { .. }
+            | DuneRequestError::RateLimited { .. }
+            | DuneRequestError::ServerError { .. } => true,
+            DuneRequestError::PollFailed { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Whether the request (or, for [`PollFailed`](DuneRequestError::PollFailed), the last
+    /// status poll) timed out.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde_json::Value;
+    /// use std::time::Duration;
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = match client.get_results::<Value>("your-execution-id").await {
+    ///     Err(err) if err.is_timeout() => {
+    ///         let patient = Duration::from_secs(300);
+    ///         client.get_results_with_timeout::<Value>("your-execution-id", patient).await?
+    ///     }
+    ///     other => other?,
+    /// };
+    /// # Ok(()) }
+    /// ```
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            DuneRequestError::Timeout { .. } => true,
+            DuneRequestError::Request(err) => err.is_timeout(),
+            DuneRequestError::PollFailed { source, .. } => source.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Classifies an unsuccessful response by its status.
+    pub(crate) fn from_status(
+        status: StatusCode,
+        url: &reqwest::Url,
+        headers: &HeaderMap,
+        payload: DuneError,
+    ) -> Self {
+        let context = HttpContext::new(status, url, headers);
+        let message = payload.error;
+        match status.as_u16() {
+            400 | 422 => DuneRequestError::InvalidRequest {
+                message,
+                details: Box::new(payload.details.unwrap_or_default()),
+                context,
+            },
+            401 | 403 => DuneRequestError::Unauthorized { message, context },
+            402 => DuneRequestError::QuotaExceeded { message, context },
+            404 => DuneRequestError::NotFound { message, context },
+            429 => DuneRequestError::RateLimited {
+                retry_after: retry_after(headers),
+                message,
+                context,
+            },
+            500..=599 => DuneRequestError::ServerError { message, context },
+            _ => DuneRequestError::Dune(message),
+        }
+    }
+}
+
+/// The delay requested by a `Retry-After` header, given in seconds or as an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Longest offending value quoted in a [`DuneRequestError::Deserialization`], in characters.
+pub const MAX_VALUE_LEN: usize = 200;
+
+/// Deserializes a response body, locating failures inside `result.rows` by row and column and
+/// quoting the offending value.
+pub(crate) fn deserialize_body<T: DeserializeOwned>(
+    body: &[u8],
+    context: HttpContext,
+) -> Result<T, DuneRequestError> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let (mut row, mut column) = (None, None);
+        let mut in_rows = false;
+        let mut pointer = String::new();
+        for segment in err.path().iter() {
+            match segment {
+                serde_path_to_error::Segment::Seq { index } => {
+                    pointer.push_str(&format!("/{index}"));
+                }
+                serde_path_to_error::Segment::Map { key } => {
+                    pointer.push_str(&format!("/{}", key.replace('~', "~0").replace('/', "~1")));
+                }
+                _ => {}
+            }
+            match segment {
+                serde_path_to_error::Segment::Map { key } if key == "rows" => in_rows = true,
+                serde_path_to_error::Segment::Seq { index } if in_rows && row.is_none() => {
+                    row = Some(*index)
+                }
+                serde_path_to_error::Segment::Map { key } if row.is_some() && column.is_none() => {
+                    column = Some(key.clone())
+                }
+                _ => {}
+            }
+        }
+        let value = (!pointer.is_empty())
+            .then(|| serde_json::from_slice::<serde_json::Value>(body).ok())
+            .flatten()
+            .and_then(|json| {
+                json.pointer(&pointer)
+                    .map(|value| shorten(&value.to_string()))
+            });
+        let source = err.into_inner().to_string();
+        DuneRequestError::Deserialization {
+            row,
+            column,
+            value,
+            expected: expected_type(&source),
+            source,
+            context: Box::new(context),
+        }
+    })
+}
+
+/// Deserializes result row `index` on its own, locating a failure by column like
+/// [`deserialize_body`].
+pub(crate) fn deserialize_row<T: DeserializeOwned>(
+    index: usize,
+    row: &serde_json::Value,
+) -> Result<T, DuneRequestError> {
+    serde_path_to_error::deserialize(row).map_err(|err| {
+        let column = err.path().iter().find_map(|segment| match segment {
+            serde_path_to_error::Segment::Map { key } => Some(key.clone()),
+            _ => None,
+        });
+        let value = match &column {
+            Some(column) => row.get(column),
+            None => Some(row),
+        };
+        let source = err.into_inner().to_string();
+        DuneRequestError::Deserialization {
+            row: Some(index),
+            column,
+            value: value.map(|value| shorten(&value.to_string())),
+            expected: expected_type(&source),
+            source,
+            context: Box::default(),
+        }
+    })
+}
+
+/// The expectation in a serde error message, e.g. `f64` from
+/// `invalid type: string "n/a", expected f64 at line 1 column 42`.
+fn expected_type(message: &str) -> Option<String> {
+    let (_, expected) = message.split_once(", expected ")?;
+    let expected = expected.split(" at line ").next().unwrap_or(expected);
+    Some(expected.to_string())
+}
+
+/// Cuts `value` down to [`MAX_VALUE_LEN`] characters.
+fn shorten(value: &str) -> String {
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value.to_string(),
+    }
+}
 
 impl From<DuneError> for DuneRequestError {
     fn from(value: DuneError) -> Self {
@@ -54,7 +653,44 @@ impl From<DuneError> for DuneRequestError {
 
 impl From<reqwest::Error> for DuneRequestError {
     fn from(value: reqwest::Error) -> Self {
-        DuneRequestError::Request(value.to_string())
+        let context = HttpContext {
+            status: value.status().map(|status| status.as_u16()),
+            url: value.url().map(ToString::to_string).unwrap_or_default(),
+            request_id: None,
+        };
+        if value.is_timeout() {
+            DuneRequestError::Timeout { context }
+        } else if value.is_decode() {
+            DuneRequestError::Deserialization {
+                row: None,
+                column: None,
+                value: None,
+                expected: None,
+                source: value.to_string(),
+                context: Box::new(context),
+            }
+        } else {
+            DuneRequestError::Request(TransportError(Arc::new(value)))
+        }
+    }
+}
+
+impl From<serde_json::Error> for DuneRequestError {
+    fn from(value: serde_json::Error) -> Self {
+        DuneRequestError::Deserialization {
+            row: None,
+            column: None,
+            value: None,
+            expected: expected_type(&value.to_string()),
+            source: value.to_string(),
+            context: Box::default(),
+        }
+    }
+}
+
+impl From<std::io::Error> for DuneRequestError {
+    fn from(value: std::io::Error) -> Self {
+        DuneRequestError::Io(value.to_string())
     }
 }
 
@@ -64,29 +700,178 @@ mod tests {
 
     #[tokio::test]
     async fn error_parsing() {
-        let err = reqwest::get("invalid-url").await.unwrap_err();
-        assert_eq!(
-            DuneRequestError::from(err),
-            DuneRequestError::Request("builder error".to_string())
-        );
+        let err = DuneRequestError::from(reqwest::get("invalid-url").await.unwrap_err());
+        assert_eq!(err.to_string(), "request error: builder error");
+        match &err {
+            DuneRequestError::Request(transport) => assert!(transport.get_ref().is_builder()),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(!err.is_retryable());
+        assert!(!err.is_timeout());
         assert_eq!(
             DuneRequestError::from(DuneError {
-                error: "broken".to_string()
+                error: "broken".to_string(),
+                details: None,
             }),
             DuneRequestError::Dune("broken".to_string())
         )
     }
 
+    #[test]
+    fn status_taxonomy() {
+        let url = reqwest::Url::parse("https://api.dune.com/api/v1/query/1/execute").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "30".parse().unwrap());
+        headers.insert("x-request-id", "req-42".parse().unwrap());
+        let error = DuneRequestError::from_status(
+            StatusCode::TOO_MANY_REQUESTS,
+            &url,
+            &headers,
+            DuneError {
+                error: "slow down".to_string(),
+                details: None,
+            },
+        );
+        assert_eq!(
+            error,
+            DuneRequestError::RateLimited {
+                retry_after: Some(Duration::from_secs(30)),
+                message: "slow down".to_string(),
+                context: HttpContext {
+                    status: Some(429),
+                    url: url.to_string(),
+                    request_id: Some("req-42".to_string()),
+                },
+            }
+        );
+        assert_eq!(error.status(), Some(429));
+        assert_eq!(
+            error.to_string(),
+            "rate limited (HTTP 429 from https://api.dune.com/api/v1/query/1/execute, request ID req-42): slow down; retry after 30s"
+        );
+
+        let headers = HeaderMap::new();
+        let classify = |status| {
+            let payload = DuneError {
+                error: "oops".to_string(),
+                details: None,
+            };
+            DuneRequestError::from_status(status, &url, &headers, payload)
+        };
+        assert!(matches!(
+            classify(StatusCode::UNAUTHORIZED),
+            DuneRequestError::Unauthorized { .. }
+        ));
+        assert!(matches!(
+            classify(StatusCode::PAYMENT_REQUIRED),
+            DuneRequestError::QuotaExceeded { .. }
+        ));
+        assert!(matches!(
+            classify(StatusCode::NOT_FOUND),
+            DuneRequestError::NotFound { .. }
+        ));
+        assert!(matches!(
+            classify(StatusCode::BAD_GATEWAY),
+            DuneRequestError::ServerError { .. }
+        ));
+        assert!(matches!(
+            classify(StatusCode::BAD_REQUEST),
+            DuneRequestError::InvalidRequest { .. }
+        ));
+        assert_eq!(
+            classify(StatusCode::CONFLICT),
+            DuneRequestError::Dune("oops".to_string())
+        );
+    }
+
+    #[test]
+    fn structured_error_details() {
+        let body = r#"{
+            "error": "Invalid query parameters",
+            "details": {
+                "missing_parameters": ["Days"],
+                "invalid_parameters": [{"name": "Token", "reason": "not a valid enum value"}],
+                "hint": "see the query page"
+            }
+        }"#;
+        let payload: DuneError = serde_json::from_str(body).unwrap();
+        let url = reqwest::Url::parse("https://api.dune.com/api/v1/query/1/execute").unwrap();
+        let error = DuneRequestError::from_status(
+            StatusCode::BAD_REQUEST,
+            &url,
+            &HeaderMap::new(),
+            payload,
+        );
+        let DuneRequestError::InvalidRequest { details, .. } = &error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(details.missing_parameters, vec!["Days"]);
+        assert_eq!(
+            details.invalid_parameters,
+            vec![InvalidParameter {
+                name: "Token".to_string(),
+                message: Some("not a valid enum value".to_string()),
+            }]
+        );
+        assert_eq!(details.other["hint"], "see the query page");
+        assert!(error
+            .to_string()
+            .ends_with("missing parameters: Days; invalid parameters: Token"));
+
+        let payload: DuneError = serde_json::from_str(r#"{"error": "bad"}"#).unwrap();
+        assert!(payload.details.is_none());
+    }
+
+    #[test]
+    fn deserialization_names_row_and_column() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Row {
+            price: f64,
+        }
+        let body = br#"{"rows": [{"price": 1.5}, {"price": "n/a"}]}"#;
+        let error =
+            deserialize_body::<crate::response::ExecutionResult<Row>>(body, HttpContext::default())
+                .err();
+        match error.unwrap() {
+            DuneRequestError::Deserialization {
+                row,
+                column,
+                value,
+                expected,
+                ..
+            } => {
+                assert_eq!(row, Some(1));
+                assert_eq!(column.as_deref(), Some("price"));
+                assert_eq!(value.as_deref(), Some("\"n/a\""));
+                assert_eq!(expected.as_deref(), Some("f64"));
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        let body = br#"{"rows": [{"cost": 1.5}]}"#;
+        let error =
+            deserialize_body::<crate::response::ExecutionResult<Row>>(body, HttpContext::default())
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not deserialize response at row 0, value {\"cost\":1.5}: missing field `price` at line 1 column 23"
+        );
+        assert_eq!(shorten(&"x".repeat(500)).chars().count(), MAX_VALUE_LEN + 1);
+    }
+
     #[test]
     fn derive_debug() {
         assert_eq!(
             format!(
                 "{:?}",
                 DuneError {
-                    error: "broken".to_string()
+                    error: "broken".to_string(),
+                    details: None,
                 }
             ),
-            "DuneError { error: \"broken\" }"
+            "DuneError { error: \"broken\", details: None }"
         );
     }
 }
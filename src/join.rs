@@ -0,0 +1,267 @@
+//! In-memory joins of result rows from different queries.
+//!
+//! Useful when two result sets cannot be joined on Dune (engine limits, credit cost) but fit in
+//! memory. [`join`] performs a hash join of two row vectors on keys extracted by closures;
+//! [`DuneClient::refresh_join`](crate::client::DuneClient::refresh_join) fetches both queries and
+//! joins them in one call. [`join_row_sets`] joins untyped [`RowSet`]s on columns they share
+//! instead, producing a combined row set.
+
+use crate::response::{DuneColumnType, DynamicRow};
+use crate::rowset::{coerce, RowSet};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Which rows a [`join`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only left rows with at least one matching right row.
+    Inner,
+    /// Every left row; unmatched ones are paired with `None`.
+    Left,
+}
+
+/// Hash-joins `left` and `right` on the keys returned by `left_key` and `right_key`.
+///
+/// Produces one `(left, Some(right))` pair per matching combination (left rows are cloned when
+/// they match several right rows), in the order of `left`. With [`JoinKind::Left`], left rows
+/// without a match are kept as `(left, None)`.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::join::{join, JoinKind};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Volume { token: String, volume: f64 }
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Price { token: String, price: f64 }
+///
+/// let volumes = vec![
+///     Volume { token: "WETH".into(), volume: 10.0 },
+///     Volume { token: "DAI".into(), volume: 5.0 },
+/// ];
+/// let prices = vec![Price { token: "WETH".into(), price: 3000.0 }];
+///
+/// let joined = join(volumes, prices, |v| v.token.clone(), |p| p.token.clone(), JoinKind::Left);
+/// assert_eq!(joined.len(), 2);
+/// assert_eq!(joined[0].1.as_ref().unwrap().price, 3000.0);
+/// assert!(joined[1].1.is_none());
+/// ```
+pub fn join<L, R, K>(
+    left: Vec<L>,
+    right: Vec<R>,
+    left_key: impl Fn(&L) -> K,
+    right_key: impl Fn(&R) -> K,
+    kind: JoinKind,
+) -> Vec<(L, Option<R>)>
+where
+    L: Clone,
+    R: Clone,
+    K: Eq + Hash,
+{
+    let mut index: HashMap<K, Vec<R>> = HashMap::new();
+    for row in right {
+        index.entry(right_key(&row)).or_default().push(row);
+    }
+    let mut joined = Vec::with_capacity(left.len());
+    for row in left {
+        match index.get(&left_key(&row)) {
+            Some(matches) => {
+                for other in matches {
+                    joined.push((row.clone(), Some(other.clone())));
+                }
+            }
+            None if kind == JoinKind::Left => joined.push((row, None)),
+            None => {}
+        }
+    }
+    joined
+}
+
+/// Hash-joins two row sets on the columns named in `key_columns`, which both must have.
+///
+/// The result has the columns of `left`, then those of `right` except the keys; right columns
+/// whose name `left` already uses get a `_right` suffix. Key values are compared after
+/// converting them by column type, so `"42"` in a `bigint` column matches `42`; rows with a
+/// `null` key match nothing. Rows come in the order of `left`, one per matching right row, and
+/// with [`JoinKind::Left`] unmatched left rows are kept without right columns.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::join::{join_row_sets, JoinKind};
+/// use duners::response::{DynamicRow, ExecutionResult};
+/// use duners::rowset::RowSet;
+/// use serde_json::json;
+///
+/// let rows = |columns: serde_json::Value, types: serde_json::Value, rows: serde_json::Value| {
+///     let result: ExecutionResult<DynamicRow> = serde_json::from_value(json!({
+///         "rows": rows,
+///         "metadata": {
+///             "column_names": columns, "column_types": types, "result_set_bytes": 0,
+///             "total_row_count": 0, "datapoint_count": 0,
+///             "pending_time_millis": null, "execution_time_millis": 1
+///         }
+///     }))
+///     .unwrap();
+///     RowSet::new(result)
+/// };
+/// let volumes = rows(
+///     json!(["token", "volume"]),
+///     json!(["varchar", "double"]),
+///     json!([{"token": "WETH", "volume": 10.0}, {"token": "DAI", "volume": 5.0}]),
+/// );
+/// let prices = rows(
+///     json!(["token", "price"]),
+///     json!(["varchar", "double"]),
+///     json!([{"token": "WETH", "price": "3000"}]),
+/// );
+/// let joined = join_row_sets(&volumes, &prices, &["token"], JoinKind::Inner);
+/// assert_eq!(joined.column_names(), ["token", "volume", "price"]);
+/// assert_eq!(joined.len(), 1);
+/// assert_eq!(joined.row(0).unwrap().get::<f64>("price").unwrap(), 3000.0);
+/// ```
+pub fn join_row_sets(
+    left: &RowSet,
+    right: &RowSet,
+    key_columns: &[&str],
+    kind: JoinKind,
+) -> RowSet {
+    let mut columns = left.column_names().to_vec();
+    let mut types: Vec<Option<DuneColumnType>> = columns
+        .iter()
+        .map(|column| left.column_type(column).cloned())
+        .collect();
+    // Right columns as (name in the right set, name in the result).
+    let mut right_columns = Vec::new();
+    for column in right.column_names() {
+        if key_columns.contains(&column.as_str()) {
+            continue;
+        }
+        let mut name = column.clone();
+        while columns.contains(&name) {
+            name.push_str("_right");
+        }
+        columns.push(name.clone());
+        types.push(right.column_type(column).cloned());
+        right_columns.push((column.as_str(), name));
+    }
+
+    let key = |set: &RowSet, row: &DynamicRow| -> Option<String> {
+        let mut values = Vec::with_capacity(key_columns.len());
+        for column in key_columns {
+            let value = row.get(*column).filter(|value| !value.is_null())?;
+            values.push(coerce(value, set.column_type(column)).unwrap_or_else(|| value.clone()));
+        }
+        Some(Value::Array(values).to_string())
+    };
+    let mut index: HashMap<String, Vec<&DynamicRow>> = HashMap::new();
+    for row in right.rows() {
+        if let Some(key) = key(right, row) {
+            index.entry(key).or_default().push(row);
+        }
+    }
+    let mut rows = Vec::with_capacity(left.len());
+    for row in left.rows() {
+        let matches = key(left, row).and_then(|key| index.get(&key));
+        match matches {
+            Some(matches) => {
+                for other in matches {
+                    let mut joined = row.clone();
+                    for (column, name) in &right_columns {
+                        if let Some(value) = other.get(*column) {
+                            joined.insert(name.clone(), value.clone());
+                        }
+                    }
+                    rows.push(joined);
+                }
+            }
+            None if kind == JoinKind::Left => rows.push(row.clone()),
+            None => {}
+        }
+    }
+    RowSet::from_parts(columns, types, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn inner_and_left_joins() {
+        let left = vec![(1, "a"), (2, "b"), (3, "c")];
+        let right = vec![(1, 10), (1, 11), (3, 30), (4, 40)];
+        let inner = join(
+            left.clone(),
+            right.clone(),
+            |l| l.0,
+            |r| r.0,
+            JoinKind::Inner,
+        );
+        assert_eq!(
+            inner,
+            vec![
+                ((1, "a"), Some((1, 10))),
+                ((1, "a"), Some((1, 11))),
+                ((3, "c"), Some((3, 30))),
+            ]
+        );
+        let outer = join(left, right, |l| l.0, |r| r.0, JoinKind::Left);
+        assert_eq!(outer.len(), 4);
+        assert_eq!(outer[2], ((2, "b"), None));
+    }
+
+    fn row_set(columns: Value, types: Value, rows: Value) -> RowSet {
+        RowSet::new(
+            serde_json::from_value(json!({
+                "rows": rows,
+                "metadata": {
+                    "column_names": columns, "column_types": types, "result_set_bytes": 0,
+                    "total_row_count": 0, "datapoint_count": 0,
+                    "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn row_set_joins() {
+        let left = row_set(
+            json!(["id", "chain", "name"]),
+            json!(["bigint", "varchar", "varchar"]),
+            json!([
+                {"id": 1, "chain": "ethereum", "name": "a"},
+                {"id": "2", "chain": "base", "name": "b"},
+                {"id": null, "chain": "base", "name": "c"}
+            ]),
+        );
+        let right = row_set(
+            json!(["chain", "id", "name", "volume"]),
+            json!(["varchar", "bigint", "varchar", "double"]),
+            json!([
+                {"chain": "ethereum", "id": "1", "name": "x", "volume": 1.5},
+                {"chain": "base", "id": 2, "name": "y", "volume": 2},
+                {"chain": "base", "id": 2, "name": "z", "volume": 3},
+                {"chain": "base", "id": null, "name": "w", "volume": 4}
+            ]),
+        );
+        let inner = join_row_sets(&left, &right, &["id", "chain"], JoinKind::Inner);
+        assert_eq!(
+            inner.column_names(),
+            ["id", "chain", "name", "name_right", "volume"]
+        );
+        assert_eq!(inner.column_type("volume"), Some(&DuneColumnType::Double));
+        let names: Vec<String> = inner.column("name_right").unwrap();
+        assert_eq!(names, ["x", "y", "z"]);
+        assert_eq!(inner.row(0).unwrap().raw("id"), Some(&json!(1)));
+
+        let outer = join_row_sets(&left, &right, &["id", "chain"], JoinKind::Left);
+        assert_eq!(outer.len(), 4);
+        let unmatched = outer.row(3).unwrap();
+        assert_eq!(unmatched.raw("name"), Some(&json!("c")));
+        assert_eq!(unmatched.raw("volume"), None);
+    }
+}
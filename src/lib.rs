@@ -33,23 +33,146 @@
 //!
 //! ## What’s in this crate
 //!
-//! - **[`DuneClient`](client::DuneClient)** — Main entry point. Create with [`DuneClient::new`](client::DuneClient::new) or [`DuneClient::from_env`](client::DuneClient::from_env).
+//! - **[`DuneClient`](client::DuneClient)** — Main entry point. Create with [`DuneClient::new`](client::DuneClient::new) or [`DuneClient::from_env`](client::DuneClient::from_env), or configure base URL, timeouts, headers and more with [`DuneClient::builder`](client::DuneClient::builder).
 //! - **[`refresh`](client::DuneClient::refresh)** — Run a query and wait for results (execute → poll status → return rows).
 //! - **Lower-level API** — [`execute_query`](client::DuneClient::execute_query), [`get_status`](client::DuneClient::get_status), [`get_results`](client::DuneClient::get_results), [`cancel_execution`](client::DuneClient::cancel_execution) for full control.
+//! - **[`PollStrategy`](poll::PollStrategy)** — Controls how often `refresh` polls; the default backs off while a query is queued.
+//! - **[`ExponentialBackoff`](retry::ExponentialBackoff)** — Retries of idempotent requests after transient failures (5xx, connection errors, timeouts).
+//! - **[`RateLimiter`](throttle::RateLimiter)** — Client-side requests-per-minute budget shared by all calls (and optionally several clients).
 //! - **[`Parameter`](parameters::Parameter)** — Query parameters (text, number, date, list) for parameterized queries.
 //! - **[`parse_utils`](parse_utils)** — Helpers for deserializing Dune’s JSON (e.g. dates and numbers that come as strings): [`datetime_from_str`](parse_utils::datetime_from_str), [`f64_from_str`](parse_utils::f64_from_str).
+//! - **[`QueryId`](query::QueryId)** / **[`QueryRef`](query::QueryRef)** — Validated query IDs; every method taking a query also accepts a `https://dune.com/queries/…` URL.
+//! - **[`ExecutionStore`](store::ExecutionStore)** — Persist in-flight executions so [`resume`](client::DuneClient::resume) can continue polling after a restart.
+//! - **[`ResultCache`](cache::ResultCache)** — Where [`with_result_cache`](client::DuneClient::with_result_cache) keeps refreshed results: in memory or on disk ([`DiskResultCache`](cache::DiskResultCache)).
+//! - **[`table`]** — Schema types for the Tables API ([`upload_csv`](client::DuneClient::upload_csv), [`create_table`](client::DuneClient::create_table)).
+//! - **`template`** — With the `minijinja` feature, `template::render_sql` renders loops and conditionals in SQL locally, keeping Dune's `{{name}}` placeholders.
+//! - **[`QuerySync`](sync::QuerySync)** — Push and pull a directory of `.sql` files to and from saved Dune queries.
+//! - **[`RowSet`](rowset::RowSet)** — Untyped result rows with typed accessors (`row.get::<f64>("price")`), converting by column type, and per-column statistics.
+//! - **[`ColumnMapping`](mapping::ColumnMapping)** — Alias and case-insensitive matching of result columns to struct fields.
+//! - **[`RowSchema`](schema::RowSchema)** — Declare the columns a row type reads to check results against it before deserializing.
+//! - **`DuneParams`** — With the `derive` feature, `#[derive(DuneParams)]` turns a struct into typed query parameters ([`ToParameters`](parameters::ToParameters)).
+//! - **`DuneRow`** — With the `derive` feature, `#[derive(DuneRow)]` deserializes row structs with case-insensitive column matching and automatic string-to-number/timestamp conversion.
+//! - **[`codegen`]** — Generate the Rust source of a row struct from a query's result columns.
+//! - **`xlsx`** — With the `xlsx` feature, `xlsx::Workbook` writes results to Excel, one typed worksheet per query.
+//! - **`archive`** — With the `archive` feature, `results.save(path)` / `GetResultResponse::load(path)` keep whole responses in zstd-compressed files.
+//! - **[`diff`](diff::diff)** — Added, removed and changed rows between two executions of a query, matched on key columns.
+//! - **[`join`](join::join)** — In-memory inner/left joins of typed rows or untyped row sets from different queries.
+//! - **[`SlaTracker`](metrics::SlaTracker)** — Per-query latency/failure tracking with an alert hook when SLAs are breached.
+//! - **`blocking`** — With the `blocking` feature, `duners::blocking::DuneClient` offers the same methods without `async`.
 //! - **[`DuneRequestError`](error::DuneRequestError)** — All request and parsing errors.
+//! - **[`prelude`]** — `use duners::prelude::*;` imports the client, its options, parameters and the row deserializers.
 //!
 //! See the [README](https://github.com/bh2smith/duners) for more examples and details.
 
+// Lets `#[derive(DuneRow)]` code refer to `::duners` inside this crate too.
+extern crate self as duners;
+
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
 pub mod client;
+pub mod codegen;
+#[cfg(any(feature = "arrow", feature = "polars"))]
+pub mod columnar;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod derive_support;
+pub mod diff;
 pub mod error;
+pub mod join;
+pub mod mapping;
+pub mod metrics;
 pub mod parameters;
 pub mod parse_utils;
+pub mod poll;
+pub mod prelude;
+pub mod query;
 pub mod response;
+pub mod retry;
+pub mod rowset;
+pub mod schema;
+pub mod store;
+pub mod sync;
+pub mod table;
+#[cfg(feature = "minijinja")]
+pub mod template;
+pub mod throttle;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
 
 // Re-export commonly used types for convenience and clearer docs.
+/// The `chrono` version used by [`parse_utils`], so row structs can name its types without a
+/// separate (possibly mismatched) dependency.
+pub use chrono;
 pub use client::DuneClient;
+/// Derives [`ToParameters`](parameters::ToParameters) for a struct of query parameters (feature
+/// `derive`), typing each parameter by its field type.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::chrono::NaiveDate;
+/// use duners::parameters::ToParameters;
+/// use duners::{DuneParams, Parameter};
+///
+/// #[derive(DuneParams)]
+/// #[dune(rename_all = "PascalCase")]
+/// struct Params {
+///     wallet: String,
+///     #[dune(rename = "Start Date")]
+///     start: NaiveDate,
+///     min_amount: u64,
+///     #[dune(list)]
+///     chain: String,
+///     limit: Option<u32>,
+/// }
+///
+/// let params = Params {
+///     wallet: "0xd8da6bf26964af9d7eed9e03e53415d37aa96045".into(),
+///     start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+///     min_amount: 100,
+///     chain: "ethereum".into(),
+///     limit: None,
+/// };
+/// let parameters = params.to_parameters();
+/// assert_eq!(parameters.len(), 4);
+/// assert_eq!(parameters[1].key, "Start Date");
+/// assert_eq!(parameters[1].value, "2024-01-01 00:00:00");
+/// assert_eq!(parameters[2], Parameter::number("MinAmount", 100));
+/// assert_eq!(parameters[3], Parameter::list("Chain", "ethereum"));
+/// ```
+#[cfg(feature = "derive")]
+pub use duners_derive::DuneParams;
+/// Derives `Deserialize` and [`RowSchema`](schema::RowSchema) for a result row struct (feature
+/// `derive`), matching columns case-insensitively and converting string-encoded numbers,
+/// booleans and timestamps by field type.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::chrono::{DateTime, Utc};
+/// use duners::DuneRow;
+///
+/// #[derive(DuneRow, Debug)]
+/// struct Row {
+///     symbol: String,
+///     #[dune(rename = "Max Price")]
+///     max_price: f64,
+///     day: DateTime<Utc>,
+///     volume: Option<u64>,
+/// }
+///
+/// let row: Row = serde_json::from_str(
+///     r#"{"SYMBOL": "ETH", "max price": "3000.5", "day": "2024-01-01 00:00:00.000 UTC"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(row.max_price, 3000.5);
+/// assert_eq!(row.volume, None);
+/// ```
+#[cfg(feature = "derive")]
+pub use duners_derive::DuneRow;
 pub use error::DuneRequestError;
 pub use parameters::Parameter;
 pub use response::{ExecutionStatus, GetResultResponse};
@@ -0,0 +1,200 @@
+//! Matching result columns to struct fields by more than their exact names.
+//!
+//! Dune column names may contain spaces, capitals or emoji that no Rust identifier can hold. A
+//! [`ColumnMapping`] renames columns before rows are deserialized, from an explicit alias map
+//! and, optionally, by matching the row type's field names case-insensitively. Set it on a client
+//! with [`DuneClient::with_column_mapping`](crate::client::DuneClient::with_column_mapping) to
+//! apply it to every result.
+
+use crate::error::{deserialize_row, DuneRequestError};
+use crate::response::DynamicRow;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Renames result columns to the field names of a row type.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::mapping::ColumnMapping;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     symbol: String,
+///     volume: f64,
+/// }
+///
+/// let mapping = ColumnMapping::new()
+///     .alias("🔥 Volume (24h)", "volume")
+///     .case_insensitive();
+/// let row: Row = mapping
+///     .deserialize_row(0, &serde_json::json!({"Symbol": "ETH", "🔥 Volume (24h)": 12.5}))
+///     .unwrap();
+/// assert_eq!(row.symbol, "ETH");
+/// assert_eq!(row.volume, 12.5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnMapping {
+    /// Field name for each aliased column name.
+    aliases: HashMap<String, String>,
+    /// Whether columns are matched to field names ignoring case.
+    case_insensitive: bool,
+}
+
+impl ColumnMapping {
+    /// A mapping that leaves columns as they are.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads column `column` into field `field` (its name after serde renames).
+    pub fn alias(mut self, column: &str, field: &str) -> Self {
+        self.aliases.insert(column.to_string(), field.to_string());
+        self
+    }
+
+    /// Matches columns to the row type's field names ignoring case, e.g. `Symbol` to `symbol`.
+    ///
+    /// Field names are read from the type's `Deserialize` implementation, so this works for
+    /// structs with `#[derive(Deserialize)]`; other row types only get the aliases.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
+    /// Renames the columns of `row` for deserializing into `T`. Columns already named like a
+    /// field keep their value.
+    pub fn apply<T: DeserializeOwned>(&self, row: DynamicRow) -> DynamicRow {
+        let fields = match self.case_insensitive {
+            true => field_names::<T>(),
+            false => None,
+        };
+        let mut mapped = DynamicRow::new();
+        let mut renamed = Vec::new();
+        for (column, value) in row {
+            match self.target(&column, fields) {
+                Some(field) => renamed.push((field, value)),
+                None => {
+                    mapped.insert(column, value);
+                }
+            }
+        }
+        // A column already named like the field wins over renamed ones.
+        for (field, value) in renamed {
+            mapped.entry(field).or_insert(value);
+        }
+        mapped
+    }
+
+    /// Deserializes result row number `index` into `T` after renaming its columns.
+    pub fn deserialize_row<T: DeserializeOwned>(
+        &self,
+        index: usize,
+        row: &Value,
+    ) -> Result<T, DuneRequestError> {
+        match row {
+            Value::Object(columns) => {
+                let mapped = Value::Object(self.apply::<T>(columns.clone()));
+                deserialize_row(index, &mapped)
+            }
+            other => deserialize_row(index, other),
+        }
+    }
+
+    /// The field `column` is read into, if it must be renamed.
+    fn target(&self, column: &str, fields: Option<&[&str]>) -> Option<String> {
+        if let Some(field) = self.aliases.get(column) {
+            return Some(field.clone());
+        }
+        let fields = fields?;
+        if fields.contains(&column) {
+            return None;
+        }
+        let column = column.to_lowercase();
+        fields
+            .iter()
+            .find(|field| field.to_lowercase() == column)
+            .map(|field| field.to_string())
+    }
+}
+
+/// Field names of `T`, if it deserializes from a struct.
+pub(crate) fn field_names<T: DeserializeOwned>() -> Option<&'static [&'static str]> {
+    let mut capture = FieldCapture(None);
+    let _ = T::deserialize(&mut capture);
+    capture.0
+}
+
+/// A deserializer that only records the field names a struct asks for, then gives up.
+struct FieldCapture(Option<&'static [&'static str]>);
+
+impl<'de> Deserializer<'de> for &mut FieldCapture {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0 = Some(fields);
+        Err(de::Error::custom("fields captured"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Row {
+        symbol: String,
+        #[serde(rename = "maxPrice")]
+        max_price: f64,
+    }
+
+    #[test]
+    fn struct_field_names() {
+        assert_eq!(field_names::<Row>(), Some(&["symbol", "maxPrice"][..]));
+        assert_eq!(field_names::<Value>(), None);
+    }
+
+    #[test]
+    fn mapped_rows() {
+        let row = json!({"SYMBOL": "ETH", "Max Price 💰": 3000.5, "symbol_2": "x"});
+        assert!(ColumnMapping::new()
+            .deserialize_row::<Row>(0, &row)
+            .is_err());
+
+        let mapping = ColumnMapping::new()
+            .alias("Max Price 💰", "maxPrice")
+            .case_insensitive();
+        assert_eq!(
+            mapping.deserialize_row::<Row>(0, &row).unwrap(),
+            Row {
+                symbol: "ETH".to_string(),
+                max_price: 3000.5
+            }
+        );
+        // Exact matches win over case-insensitive ones.
+        let row = json!({"symbol": "ETH", "Symbol": "BTC", "maxprice": 1.0});
+        let mapped = mapping.apply::<Row>(row.as_object().unwrap().clone());
+        assert_eq!(mapped["symbol"], "ETH");
+        assert_eq!(mapped["maxPrice"], 1.0);
+    }
+}
@@ -0,0 +1,316 @@
+//! Client-side metrics for query executions.
+//!
+//! [`ExecutionReport`] summarizes a single refresh (see
+//! [`refresh_with_report`](crate::client::DuneClient::refresh_with_report)).
+//! [`SlaTracker`] keeps a rolling window of [`refresh`](crate::client::DuneClient::refresh)
+//! outcomes per query and calls a hook when a query's failure rate or p95 latency breaches the
+//! configured [`SlaThresholds`]. Attach one with
+//! [`DuneClient::with_sla_tracker`](crate::client::DuneClient::with_sla_tracker).
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Summary of one [`refresh_with_report`](crate::client::DuneClient::refresh_with_report) call,
+/// meant to be logged once per pipeline run.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::metrics::ExecutionReport;
+///
+/// fn log_run(report: &ExecutionReport) {
+///     println!(
+///         "query {} ({}) polled {} times, waited {:?}, downloaded {} bytes",
+///         report.query_id, report.execution_id, report.poll_count, report.total_wait, report.bytes_downloaded
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionReport {
+    /// Query that was refreshed.
+    pub query_id: u32,
+    /// Execution whose results were returned.
+    pub execution_id: String,
+    /// Number of executions started (more than one if an execution had to be re-run).
+    pub attempts: u32,
+    /// Number of status polls.
+    pub poll_count: u32,
+    /// Total time spent sleeping between status polls.
+    pub total_wait: Duration,
+    /// Wall-clock time of the whole refresh.
+    pub elapsed: Duration,
+    /// Size of the downloaded result bodies.
+    pub bytes_downloaded: u64,
+    /// Number of result pages fetched.
+    pub pages_fetched: u32,
+    /// Number of retries: requests sent again after a transient failure or rate limit, and
+    /// status polls repeated after a tolerated failure.
+    pub retries: u32,
+    /// Credits charged for the execution, when the API reports them.
+    pub credits: Option<f64>,
+}
+
+/// Limits a query is expected to stay within, evaluated over a rolling time window.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::metrics::SlaThresholds;
+/// use std::time::Duration;
+///
+/// // Dashboards refreshed every 5 minutes: alert on 2 failures in 10 or a p95 above 2 minutes.
+/// let thresholds = SlaThresholds {
+///     window: Duration::from_secs(50 * 60),
+///     min_samples: 10,
+///     max_failure_rate: 0.1,
+///     max_p95: Duration::from_secs(120),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlaThresholds {
+    /// How far back outcomes are considered.
+    pub window: Duration,
+    /// Minimum number of outcomes in the window before thresholds are evaluated.
+    pub min_samples: usize,
+    /// Highest acceptable share of failed refreshes (`0.0..=1.0`).
+    pub max_failure_rate: f64,
+    /// Highest acceptable 95th percentile of refresh latency.
+    pub max_p95: Duration,
+}
+
+impl Default for SlaThresholds {
+    /// One hour window, at least 5 samples, 10% failures, 10 minute p95.
+    fn default() -> Self {
+        SlaThresholds {
+            window: Duration::from_secs(60 * 60),
+            min_samples: 5,
+            max_failure_rate: 0.1,
+            max_p95: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// Aggregates over the current window for a single query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryStats {
+    /// Number of refreshes in the window.
+    pub executions: usize,
+    /// Share of those refreshes that failed.
+    pub failure_rate: f64,
+    /// 95th percentile latency of those refreshes.
+    pub p95: Duration,
+}
+
+/// A threshold violation reported to the breach hook.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlaBreach {
+    /// Too many refreshes of `query_id` failed within the window.
+    FailureRate {
+        /// Query whose refreshes are failing.
+        query_id: u32,
+        /// Observed failure rate.
+        failure_rate: f64,
+    },
+    /// Refreshes of `query_id` are slower than allowed.
+    Latency {
+        /// Query whose refreshes are slow.
+        query_id: u32,
+        /// Observed 95th percentile latency.
+        p95: Duration,
+    },
+}
+
+struct Sample {
+    at: Instant,
+    latency: Duration,
+    success: bool,
+}
+
+#[derive(Default)]
+struct QueryWindow {
+    samples: VecDeque<Sample>,
+    failing: bool,
+    slow: bool,
+}
+
+/// Callback invoked when a query breaches its SLA.
+type BreachHook = Box<dyn Fn(&SlaBreach) + Send + Sync>;
+
+/// Tracks refresh latency and failures per query and reports SLA breaches.
+///
+/// The hook fires when a query *enters* a breached state, not on every refresh while it stays
+/// breached; it fires again after the query has recovered and breaches anew.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::metrics::{SlaThresholds, SlaTracker};
+/// use std::time::Duration;
+///
+/// let tracker = SlaTracker::new(SlaThresholds {
+///     min_samples: 1,
+///     ..Default::default()
+/// })
+/// .on_breach(|breach| eprintln!("SLA breach: {breach:?}"));
+///
+/// tracker.record(971694, Duration::from_secs(12), false); // prints a failure-rate breach
+/// assert_eq!(tracker.stats(971694).unwrap().failure_rate, 1.0);
+/// ```
+pub struct SlaTracker {
+    thresholds: SlaThresholds,
+    windows: Mutex<HashMap<u32, QueryWindow>>,
+    hook: Option<BreachHook>,
+}
+
+impl SlaTracker {
+    /// Creates a tracker evaluating every query against `thresholds`.
+    pub fn new(thresholds: SlaThresholds) -> Self {
+        SlaTracker {
+            thresholds,
+            windows: Mutex::new(HashMap::new()),
+            hook: None,
+        }
+    }
+
+    /// Sets the callback invoked on breaches.
+    pub fn on_breach(mut self, hook: impl Fn(&SlaBreach) + Send + Sync + 'static) -> Self {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Records the outcome of one refresh of `query_id` and evaluates its thresholds.
+    pub fn record(&self, query_id: u32, latency: Duration, success: bool) {
+        let now = Instant::now();
+        let mut breaches = vec![];
+        {
+            let mut windows = self.windows.lock().unwrap();
+            let window = windows.entry(query_id).or_default();
+            window.samples.push_back(Sample {
+                at: now,
+                latency,
+                success,
+            });
+            self.prune(window, now);
+            let Some(stats) = self.compute(window) else {
+                return;
+            };
+            let failing = stats.failure_rate > self.thresholds.max_failure_rate;
+            if failing && !window.failing {
+                breaches.push(SlaBreach::FailureRate {
+                    query_id,
+                    failure_rate: stats.failure_rate,
+                });
+            }
+            let slow = stats.p95 > self.thresholds.max_p95;
+            if slow && !window.slow {
+                breaches.push(SlaBreach::Latency {
+                    query_id,
+                    p95: stats.p95,
+                });
+            }
+            window.failing = failing;
+            window.slow = slow;
+        }
+        if let Some(hook) = &self.hook {
+            breaches.iter().for_each(hook);
+        }
+    }
+
+    /// Current statistics for `query_id`, or `None` if it has no outcomes in the window.
+    pub fn stats(&self, query_id: u32) -> Option<QueryStats> {
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.get_mut(&query_id)?;
+        self.prune(window, Instant::now());
+        if window.samples.is_empty() {
+            return None;
+        }
+        Some(Self::aggregate(&window.samples))
+    }
+
+    fn prune(&self, window: &mut QueryWindow, now: Instant) {
+        while window
+            .samples
+            .front()
+            .is_some_and(|sample| now.duration_since(sample.at) > self.thresholds.window)
+        {
+            window.samples.pop_front();
+        }
+    }
+
+    /// Statistics if the window holds enough samples to be evaluated.
+    fn compute(&self, window: &QueryWindow) -> Option<QueryStats> {
+        (window.samples.len() >= self.thresholds.min_samples.max(1))
+            .then(|| Self::aggregate(&window.samples))
+    }
+
+    fn aggregate(samples: &VecDeque<Sample>) -> QueryStats {
+        let failures = samples.iter().filter(|sample| !sample.success).count();
+        let mut latencies: Vec<_> = samples.iter().map(|sample| sample.latency).collect();
+        latencies.sort();
+        // Nearest-rank percentile.
+        let rank = (latencies.len() * 95).div_ceil(100).max(1);
+        QueryStats {
+            executions: samples.len(),
+            failure_rate: failures as f64 / samples.len() as f64,
+            p95: latencies[rank - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn tracker(breaches: Arc<Mutex<Vec<SlaBreach>>>) -> SlaTracker {
+        SlaTracker::new(SlaThresholds {
+            window: Duration::from_secs(60),
+            min_samples: 2,
+            max_failure_rate: 0.5,
+            max_p95: Duration::from_secs(10),
+        })
+        .on_breach(move |breach| breaches.lock().unwrap().push(breach.clone()))
+    }
+
+    #[test]
+    fn failure_rate_breach_fires_once() {
+        let breaches = Arc::new(Mutex::new(vec![]));
+        let tracker = tracker(breaches.clone());
+        tracker.record(1, Duration::from_secs(1), false);
+        assert!(breaches.lock().unwrap().is_empty(), "below min_samples");
+        tracker.record(1, Duration::from_secs(1), false);
+        tracker.record(1, Duration::from_secs(1), false);
+        assert_eq!(
+            *breaches.lock().unwrap(),
+            vec![SlaBreach::FailureRate {
+                query_id: 1,
+                failure_rate: 1.0
+            }]
+        );
+        assert!(tracker.stats(2).is_none());
+    }
+
+    #[test]
+    fn latency_breach() {
+        let breaches = Arc::new(Mutex::new(vec![]));
+        let tracker = tracker(breaches.clone());
+        tracker.record(7, Duration::from_secs(1), true);
+        tracker.record(7, Duration::from_secs(30), true);
+        assert_eq!(
+            *breaches.lock().unwrap(),
+            vec![SlaBreach::Latency {
+                query_id: 7,
+                p95: Duration::from_secs(30)
+            }]
+        );
+        assert_eq!(
+            tracker.stats(7),
+            Some(QueryStats {
+                executions: 2,
+                failure_rate: 0.0,
+                p95: Duration::from_secs(30)
+            })
+        );
+    }
+}
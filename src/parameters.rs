@@ -3,22 +3,106 @@
 //! Use [`Parameter`] and its constructors ([`Parameter::text`], [`Parameter::number`], etc.)
 //! when calling [`execute_query`](crate::client::DuneClient::execute_query) or [`refresh`](crate::client::DuneClient::refresh).
 
-use chrono::{DateTime, Utc};
+use crate::error::{DuneRequestError, InvalidParameter};
+use crate::response::QueryParameter;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde_json::{json, Value};
 
 /// Dune supports four parameter types; all are sent to the API as JSON strings.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 enum ParameterType {
     Text,
     Number,
     Enum,
+    /// A multi-select list with the selected values.
+    MultiEnum(Vec<String>),
     Date,
 }
 
+impl ParameterType {
+    /// Name of the type in the Query API's parameter definitions.
+    fn api_name(&self) -> &'static str {
+        match self {
+            ParameterType::Text => "text",
+            ParameterType::Number => "number",
+            ParameterType::Enum | ParameterType::MultiEnum(_) => "enum",
+            ParameterType::Date => "datetime",
+        }
+    }
+}
+
+/// The value of a **number** parameter, formatted for Dune.
+///
+/// Integers are written in full and floats in their shortest exact decimal form, never in
+/// exponent notation (`1e21` becomes `1000000000000000000000`). Strings are sent as they are.
+/// With the `rust_decimal` / `bigdecimal` features, `Decimal` and `BigDecimal` convert too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number(String);
+
+impl Number {
+    /// The text sent to the API.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! number_from_display {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for Number {
+                fn from(value: $number) -> Self {
+                    Number(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+number_from_display!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl From<&str> for Number {
+    fn from(value: &str) -> Self {
+        Number(value.to_string())
+    }
+}
+
+impl From<String> for Number {
+    fn from(value: String) -> Self {
+        Number(value)
+    }
+}
+
+impl From<&String> for Number {
+    fn from(value: &String) -> Self {
+        Number(value.clone())
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl From<rust_decimal::Decimal> for Number {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Number(value.to_string())
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl From<bigdecimal::BigDecimal> for Number {
+    fn from(value: bigdecimal::BigDecimal) -> Self {
+        Number(value.to_plain_string())
+    }
+}
+
 /// A single query parameter for a [parameterized Dune query](https://dune.com/docs/api/api-reference/execute-queries/execute-query-id/).
 ///
 /// The parameter **name** must match the name defined in the query on Dune (e.g. in the query editor).
-/// Use the constructors [`Parameter::text`], [`Parameter::number`], [`Parameter::date`], and
-/// [`Parameter::list`] to build parameters of the correct type.
+/// Use the constructors [`Parameter::text`], [`Parameter::number`], [`Parameter::date`],
+/// [`Parameter::list`] and [`Parameter::list_multi`] to build parameters of the correct type.
 ///
 /// # Example
 ///
@@ -33,7 +117,7 @@ enum ParameterType {
 ///     Parameter::date("StartDate", Utc::now()),
 /// ];
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Parameter {
     /// Parameter name (must match the query’s parameter name on Dune).
     pub key: String,
@@ -63,6 +147,74 @@ impl Parameter {
         }
     }
 
+    /// Builds a **date** parameter from a [`time::OffsetDateTime`] (feature `time`). The value is
+    /// converted to UTC and sent as `YYYY-MM-DD HH:MM:SS`, like [`Parameter::date`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::Parameter;
+    /// use time::{OffsetDateTime, UtcOffset};
+    ///
+    /// let start = OffsetDateTime::from_unix_timestamp(1_704_069_000)
+    ///     .unwrap()
+    ///     .to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    /// let p = Parameter::offset_date_time("StartDate", start);
+    /// assert_eq!(p.value, "2024-01-01 00:30:00");
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn offset_date_time(name: &str, value: time::OffsetDateTime) -> Self {
+        let value = value.to_offset(time::UtcOffset::UTC);
+        Parameter {
+            key: String::from(name),
+            ptype: ParameterType::Date,
+            value: format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                value.year(),
+                u8::from(value.month()),
+                value.day(),
+                value.hour(),
+                value.minute(),
+                value.second()
+            ),
+        }
+    }
+
+    /// Builds a **date** parameter from a wall-clock time in time zone `tz` (feature `chrono-tz`),
+    /// e.g. midnight of a business day in New York. Dune evaluates date parameters in UTC, so the
+    /// value is converted to UTC and sent as `YYYY-MM-DD HH:MM:SS`.
+    ///
+    /// Ambiguous times (when clocks go back) use the earlier instant; times skipped when clocks go
+    /// forward are moved past the gap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono::NaiveDate;
+    /// use chrono_tz::Tz;
+    /// use duners::Parameter;
+    ///
+    /// let midnight = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    /// let p = Parameter::date_in_tz("StartDate", midnight, Tz::America__New_York);
+    /// assert_eq!(p.value, "2024-01-02 05:00:00");
+    /// ```
+    #[cfg(feature = "chrono-tz")]
+    pub fn date_in_tz(name: &str, local: chrono::NaiveDateTime, tz: chrono_tz::Tz) -> Self {
+        use chrono::TimeZone;
+
+        let instant = tz
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                // Skipped local times only exist within a DST gap of at most an hour.
+                tz.from_local_datetime(&(local + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|date| date.with_timezone(&Utc))
+            .unwrap_or_else(|| local.and_utc());
+        Parameter::date(name, instant)
+    }
+
     /// Builds a **text** parameter (e.g. addresses, hashes, plain strings).
     pub fn text(name: &str, value: &str) -> Self {
         Parameter {
@@ -72,12 +224,26 @@ impl Parameter {
         }
     }
 
-    /// Builds a **number** parameter. Pass the value as a string (e.g. `"42"` or `"3.14"`).
-    pub fn number(name: &str, value: &str) -> Self {
+    /// Builds a **number** parameter from an integer, a float, or a string for values no Rust
+    /// number type holds (e.g. `"42"` or `"3.14"`); see [`Number`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::Parameter;
+    ///
+    /// assert_eq!(Parameter::number("Limit", 100u32).value, "100");
+    /// assert_eq!(Parameter::number("Threshold", 0.1).value, "0.1");
+    /// assert_eq!(Parameter::number("Amount", "1e18").value, "1e18");
+    /// ```
+    ///
+    /// NaN and infinite floats have no SQL literal: executing a query with one fails with
+    /// [`DuneRequestError::InvalidParameters`] before any request is sent.
+    pub fn number(name: &str, value: impl Into<Number>) -> Self {
         Parameter {
             key: String::from(name),
             ptype: ParameterType::Number,
-            value: String::from(value),
+            value: value.into().0,
         }
     }
 
@@ -89,6 +255,517 @@ impl Parameter {
             value: String::from(value),
         }
     }
+
+    /// Builds a **multi-select list** parameter with several selected values, for dropdowns that
+    /// allow more than one choice. [`value`](Parameter::value) holds the values as a JSON array;
+    /// the API receives the array itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::Parameter;
+    ///
+    /// let p = Parameter::list_multi("Tokens", ["ETH", "USDC"]);
+    /// assert_eq!(p.value, r#"["ETH","USDC"]"#);
+    /// ```
+    pub fn list_multi<I, S>(name: &str, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let values: Vec<String> = values.into_iter().map(Into::into).collect();
+        Parameter {
+            key: String::from(name),
+            value: json!(values).to_string(),
+            ptype: ParameterType::MultiEnum(values),
+        }
+    }
+
+    /// Builds parameters from names and JSON values, e.g. a parameter set read from a config
+    /// file or an HTTP request, inferring each type: strings and booleans become **text**,
+    /// numbers **number**, and arrays of them multi-select **lists**. `null` values are left out,
+    /// so the query's default applies.
+    ///
+    /// Accepts a `HashMap<String, Value>`, a `BTreeMap` or a `serde_json::Map`. Parameters are
+    /// sorted by name. Objects and nested arrays have no Dune parameter type and fail with
+    /// [`DuneRequestError::InvalidParameters`] naming every such parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::Parameter;
+    /// use serde_json::json;
+    /// use std::collections::HashMap;
+    ///
+    /// let values: HashMap<String, serde_json::Value> = serde_json::from_value(json!({
+    ///     "Wallet": "0xabc", "Days": 7, "Tokens": ["ETH", "USDC"], "Limit": null
+    /// }))
+    /// .unwrap();
+    /// let params = Parameter::from_map(values).unwrap();
+    /// assert_eq!(
+    ///     params,
+    ///     vec![
+    ///         Parameter::number("Days", 7),
+    ///         Parameter::list_multi("Tokens", ["ETH", "USDC"]),
+    ///         Parameter::text("Wallet", "0xabc"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn from_map<I, K>(values: I) -> Result<Vec<Self>, DuneRequestError>
+    where
+        I: IntoIterator<Item = (K, Value)>,
+        K: Into<String>,
+    {
+        let mut parameters = Vec::new();
+        let mut invalid = Vec::new();
+        for (name, value) in values {
+            let name = name.into();
+            match Parameter::from_value(&name, &value) {
+                Ok(Some(parameter)) => parameters.push(parameter),
+                Ok(None) => {}
+                Err(message) => invalid.push(InvalidParameter {
+                    name,
+                    message: Some(message),
+                }),
+            }
+        }
+        if !invalid.is_empty() {
+            invalid.sort_by(|a, b| a.name.cmp(&b.name));
+            return Err(DuneRequestError::InvalidParameters(invalid));
+        }
+        parameters.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(parameters)
+    }
+
+    /// The parameter `name` for a JSON value, `None` for `null`.
+    fn from_value(name: &str, value: &Value) -> Result<Option<Self>, String> {
+        let scalar = |value: &Value| match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(number) => Some(number.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        };
+        Ok(Some(match value {
+            Value::Null => return Ok(None),
+            Value::String(s) => Parameter::text(name, s),
+            Value::Bool(b) => Parameter::text(name, &b.to_string()),
+            Value::Number(number) => Parameter::number(name, number.to_string()),
+            Value::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(scalar)
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or("list values must be strings, numbers or booleans")?;
+                Parameter::list_multi(name, values)
+            }
+            Value::Object(_) => return Err("objects are not a parameter type".to_string()),
+        }))
+    }
+
+    /// The value as sent in an execution's `query_parameters`: a string, or an array of strings
+    /// for multi-select lists.
+    pub(crate) fn api_value(&self) -> Value {
+        match &self.ptype {
+            ParameterType::MultiEnum(values) => json!(values),
+            _ => json!(self.value),
+        }
+    }
+
+    /// Parameter definition as expected by the Query API when creating or updating a query,
+    /// with `value` as the default.
+    pub(crate) fn definition(&self) -> Value {
+        let mut definition = json!({
+            "key": self.key,
+            "type": self.ptype.api_name(),
+            "value": self.api_value(),
+        });
+        match &self.ptype {
+            ParameterType::Enum => definition["enumOptions"] = json!([self.value]),
+            ParameterType::MultiEnum(values) => {
+                definition["enumOptions"] = json!(values);
+                definition["isMultiselect"] = json!(true);
+            }
+            _ => {}
+        }
+        definition
+    }
+
+    /// The parameter as a **list/enum** parameter, keeping its value; multi-select lists stay
+    /// as they are.
+    #[cfg(feature = "derive")]
+    pub(crate) fn into_list(self) -> Self {
+        match self.ptype {
+            ParameterType::MultiEnum(_) => self,
+            _ => Parameter {
+                ptype: ParameterType::Enum,
+                ..self
+            },
+        }
+    }
+}
+
+/// The names of the `{{name}}` placeholders in `sql`, in order of first use and without
+/// duplicates.
+///
+/// Whitespace around the name (`{{ name }}`) is ignored, like Dune does. Placeholders inside
+/// string literals and comments count too, since Dune substitutes them there as well.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parameters::extract_placeholders;
+///
+/// let sql = "SELECT * FROM dex.trades WHERE token = '{{Token}}' \
+///            AND block_time > now() - interval '{{ Days }}' day LIMIT {{Limit}} -- {{Token}}";
+/// assert_eq!(extract_placeholders(sql), vec!["Token", "Days", "Limit"]);
+/// ```
+pub fn extract_placeholders(sql: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = sql;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let name = rest[..end].trim();
+        if name.is_empty() || name.contains(['{', '}', '\n']) {
+            // Not a placeholder, e.g. a stray `{{`; look for the next one inside it.
+            continue;
+        }
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    names
+}
+
+/// Checks that every placeholder of `sql` (see [`extract_placeholders`]) has one of
+/// `parameters`, reporting all that are missing.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parameters::check_placeholders;
+/// use duners::Parameter;
+///
+/// let sql = "SELECT {{Value}} * {{Factor}} AS value";
+/// let error = check_placeholders(sql, &[Parameter::number("Value", 1)]).unwrap_err();
+/// assert_eq!(error.to_string(), "invalid parameters: Factor (no parameter given for {{Factor}})");
+/// ```
+pub fn check_placeholders(sql: &str, parameters: &[Parameter]) -> Result<(), DuneRequestError> {
+    let missing: Vec<_> = extract_placeholders(sql)
+        .into_iter()
+        .filter(|name| !parameters.iter().any(|parameter| parameter.key == *name))
+        .map(|name| InvalidParameter {
+            message: Some(format!("no parameter given for {{{{{name}}}}}")),
+            name,
+        })
+        .collect();
+    match missing.is_empty() {
+        true => Ok(()),
+        false => Err(DuneRequestError::InvalidParameters(missing)),
+    }
+}
+
+/// Checks `parameters` against the parameter `definitions` of a saved query (see
+/// [`Query::parameters`](crate::response::Query::parameters)), reporting every parameter the
+/// query does not declare, whose type differs from the declared one, whose value is not among
+/// the options of a list parameter, or whose number value is not a number.
+///
+/// Parameters the query declares but `parameters` leaves out are fine: Dune uses their
+/// defaults. [`DuneClient::validate_params`](crate::client::DuneClient::validate_params) fetches
+/// the definitions and calls this.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parameters::validate;
+/// use duners::response::QueryParameter;
+/// use duners::Parameter;
+///
+/// let definitions: Vec<QueryParameter> = serde_json::from_value(serde_json::json!([
+///     {"key": "Token", "type": "enum", "value": "ETH", "enumOptions": ["ETH", "USDC"]},
+///     {"key": "Days", "type": "number", "value": "7"}
+/// ]))
+/// .unwrap();
+/// assert!(validate(&definitions, &[Parameter::number("Days", 30)]).is_ok());
+/// let error = validate(&definitions, &[Parameter::list("Token", "DAI")]).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "invalid parameters: Token (`DAI` is not one of its options: ETH, USDC)"
+/// );
+/// ```
+pub fn validate(
+    definitions: &[QueryParameter],
+    parameters: &[Parameter],
+) -> Result<(), DuneRequestError> {
+    let invalid: Vec<_> = parameters
+        .iter()
+        .filter_map(|parameter| {
+            let message = mismatch(definitions, parameter)?;
+            Some(InvalidParameter {
+                name: parameter.key.clone(),
+                message: Some(message),
+            })
+        })
+        .collect();
+    match invalid.is_empty() {
+        true => Ok(()),
+        false => Err(DuneRequestError::InvalidParameters(invalid)),
+    }
+}
+
+/// Checks that no **number** parameter is NaN or infinite, which Dune cannot substitute into SQL,
+/// reporting all that are.
+pub(crate) fn check_finite(parameters: &[Parameter]) -> Result<(), DuneRequestError> {
+    let invalid: Vec<_> = parameters
+        .iter()
+        .filter(|parameter| {
+            parameter.ptype == ParameterType::Number && is_non_finite(&parameter.value)
+        })
+        .map(|parameter| InvalidParameter {
+            name: parameter.key.clone(),
+            message: Some(format!("`{}` is not a finite number", parameter.value)),
+        })
+        .collect();
+    match invalid.is_empty() {
+        true => Ok(()),
+        false => Err(DuneRequestError::InvalidParameters(invalid)),
+    }
+}
+
+/// Whether `value` spells NaN or infinity (as `f64`'s `Display` and `FromStr` do), rather than a
+/// number too large for `f64`.
+fn is_non_finite(value: &str) -> bool {
+    let value = value.trim().trim_start_matches(['+', '-']);
+    ["nan", "inf", "infinity"]
+        .iter()
+        .any(|name| value.eq_ignore_ascii_case(name))
+}
+
+/// Why `parameter` does not fit `definitions`, if it does not.
+fn mismatch(definitions: &[QueryParameter], parameter: &Parameter) -> Option<String> {
+    let Some(definition) = definitions.iter().find(|d| d.key == parameter.key) else {
+        let keys: Vec<_> = definitions.iter().map(|d| d.key.as_str()).collect();
+        return Some(match keys.is_empty() {
+            true => "the query has no parameters".to_string(),
+            false => format!(
+                "not a parameter of the query, which has: {}",
+                keys.join(", ")
+            ),
+        });
+    };
+    let api_name = parameter.ptype.api_name();
+    if !definition.parameter_type.eq_ignore_ascii_case(api_name) {
+        return Some(format!(
+            "the query declares a {} parameter, got {api_name}",
+            definition.parameter_type
+        ));
+    }
+    let values = match &parameter.ptype {
+        ParameterType::Number
+            if parameter.value.trim().parse::<f64>().is_err()
+                || is_non_finite(&parameter.value) =>
+        {
+            return Some(format!("`{}` is not a number", parameter.value));
+        }
+        ParameterType::Enum => vec![parameter.value.clone()],
+        ParameterType::MultiEnum(values) => values.clone(),
+        _ => return None,
+    };
+    let options = definition.enum_options.as_ref()?;
+    let unknown: Vec<_> = values
+        .iter()
+        .filter(|value| !options.contains(value))
+        .map(|value| format!("`{value}`"))
+        .collect();
+    match unknown.len() {
+        0 => None,
+        1 => Some(format!(
+            "{} is not one of its options: {}",
+            unknown[0],
+            options.join(", ")
+        )),
+        _ => Some(format!(
+            "{} are not among its options: {}",
+            unknown.join(", "),
+            options.join(", ")
+        )),
+    }
+}
+
+/// A set of query parameters, usually a struct with `#[derive(DuneParams)]` (feature `derive`).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parameters::{ParameterValue, ToParameters};
+/// use duners::Parameter;
+///
+/// struct Params {
+///     wallet: String,
+///     min_amount: u64,
+/// }
+///
+/// impl ToParameters for Params {
+///     fn to_parameters(&self) -> Vec<Parameter> {
+///         [
+///             self.wallet.to_parameter("Wallet"),
+///             self.min_amount.to_parameter("MinAmount"),
+///         ]
+///         .into_iter()
+///         .flatten()
+///         .collect()
+///     }
+/// }
+///
+/// let params = Params { wallet: "0xabc".into(), min_amount: 5 }.to_parameters();
+/// assert_eq!(params[1], Parameter::number("MinAmount", 5));
+/// ```
+pub trait ToParameters {
+    /// The parameters to execute a query with.
+    fn to_parameters(&self) -> Vec<Parameter>;
+}
+
+/// A value that can be sent as a query parameter of the matching Dune type.
+///
+/// Strings, booleans, UUIDs and addresses become **text**, numbers **number**, timestamps and
+/// dates **date** (in UTC), and `Vec`s multi-select **lists**. `None` yields no parameter, so the
+/// query's default applies.
+pub trait ParameterValue {
+    /// The parameter named `name` holding this value, if any.
+    fn to_parameter(&self, name: &str) -> Option<Parameter>;
+}
+
+impl<T: ParameterValue + ?Sized> ParameterValue for &T {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        (**self).to_parameter(name)
+    }
+}
+
+impl<T: ParameterValue> ParameterValue for Option<T> {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        self.as_ref()?.to_parameter(name)
+    }
+}
+
+impl<T: ParameterValue> ParameterValue for [T] {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        let values = self
+            .iter()
+            .filter_map(|value| value.to_parameter(name))
+            .map(|parameter| parameter.value);
+        Some(Parameter::list_multi(name, values))
+    }
+}
+
+impl<T: ParameterValue> ParameterValue for Vec<T> {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        self.as_slice().to_parameter(name)
+    }
+}
+
+impl ParameterValue for str {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::text(name, self))
+    }
+}
+
+impl ParameterValue for String {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::text(name, self))
+    }
+}
+
+impl ParameterValue for bool {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::text(name, &self.to_string()))
+    }
+}
+
+macro_rules! number_parameter_value {
+    ($($number:ty),*) => {
+        $(
+            impl ParameterValue for $number {
+                fn to_parameter(&self, name: &str) -> Option<Parameter> {
+                    Some(Parameter::number(name, *self))
+                }
+            }
+        )*
+    };
+}
+
+number_parameter_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl<Tz: TimeZone> ParameterValue for DateTime<Tz> {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::date(name, self.with_timezone(&Utc)))
+    }
+}
+
+impl ParameterValue for NaiveDateTime {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::date(name, self.and_utc()))
+    }
+}
+
+impl ParameterValue for NaiveDate {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::date(
+            name,
+            self.and_time(NaiveTime::MIN).and_utc(),
+        ))
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl ParameterValue for rust_decimal::Decimal {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::number(name, *self))
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl ParameterValue for bigdecimal::BigDecimal {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::number(name, self.clone()))
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl ParameterValue for alloy_primitives::Address {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::text(name, &format!("{self:#x}")))
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl ParameterValue for alloy_primitives::U256 {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::number(name, self.to_string()))
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+impl ParameterValue for primitive_types::U256 {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::number(name, self.to_string()))
+    }
+}
+
+#[cfg(feature = "time")]
+impl ParameterValue for time::OffsetDateTime {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::offset_date_time(name, *self))
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl ParameterValue for uuid::Uuid {
+    fn to_parameter(&self, name: &str) -> Option<Parameter> {
+        Some(Parameter::text(name, &self.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -96,6 +773,181 @@ mod tests {
     use super::*;
     use crate::parse_utils::date_parse;
 
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn zoned_date_parameters() {
+        use chrono::NaiveDate;
+        use chrono_tz::Tz;
+
+        let local = |day, hour, minute| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+        };
+        let berlin = |local| Parameter::date_in_tz("Start", local, Tz::Europe__Berlin).value;
+        assert_eq!(berlin(local(1, 0, 0)), "2024-02-29 23:00:00");
+        // 02:30 does not exist on 2024-03-31 in Berlin; it moves to 03:30 CEST.
+        assert_eq!(berlin(local(31, 2, 30)), "2024-03-31 01:30:00");
+        assert_eq!(
+            Parameter::date_in_tz("Start", local(1, 12, 0), Tz::UTC).ptype,
+            ParameterType::Date
+        );
+    }
+
+    #[test]
+    fn number_values() {
+        let value = |number: Number| number.to_string();
+        assert_eq!(value((-42i64).into()), "-42");
+        assert_eq!(value(u64::MAX.into()), "18446744073709551615");
+        assert_eq!(value(u128::MAX.into()), u128::MAX.to_string());
+        assert_eq!(value(2.5f64.into()), "2.5");
+        assert_eq!(value(1e21f64.into()), "1000000000000000000000");
+        assert_eq!(value(0.1f32.into()), "0.1");
+        assert_eq!(value(String::from("7").into()), "7");
+        assert_eq!(Number::from(&String::from("8")).as_str(), "8");
+    }
+
+    #[test]
+    fn non_finite_numbers() {
+        let parameters = [
+            Parameter::number("Ratio", f64::NAN),
+            Parameter::number("Cap", f64::NEG_INFINITY),
+            Parameter::number("Huge", "1e400"),
+            Parameter::text("Label", "inf"),
+        ];
+        assert_eq!(
+            check_finite(&parameters).unwrap_err().to_string(),
+            "invalid parameters: Ratio (`NaN` is not a finite number); \
+             Cap (`-inf` is not a finite number)"
+        );
+        assert_eq!(check_finite(&parameters[2..]), Ok(()));
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimal_numbers() {
+        let price: rust_decimal::Decimal = "1234.5000".parse().unwrap();
+        assert_eq!(Parameter::number("Price", price).value, "1234.5000");
+    }
+
+    #[test]
+    fn parameter_values() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        assert_eq!(
+            day.to_parameter("Day"),
+            Some(Parameter::date(
+                "Day",
+                date_parse("2024-01-02T00:00:00.0Z").unwrap()
+            ))
+        );
+        assert_eq!("x".to_parameter("T"), Some(Parameter::text("T", "x")));
+        assert_eq!(true.to_parameter("T").unwrap().value, "true");
+        assert_eq!(
+            2.5f32.to_parameter("N"),
+            Some(Parameter::number("N", "2.5"))
+        );
+        assert_eq!(None::<u64>.to_parameter("N"), None);
+        assert_eq!(
+            vec!["ETH", "USDC"].to_parameter("Tokens"),
+            Some(Parameter::list_multi("Tokens", ["ETH", "USDC"]))
+        );
+        #[cfg(feature = "derive")]
+        assert_eq!(
+            Parameter::number("N", 1).into_list(),
+            Parameter::list("N", "1")
+        );
+    }
+
+    #[test]
+    fn parameters_from_map() {
+        let values = json!({
+            "b": true, "n": 1.5, "big": 18446744073709551615u64, "mixed": [1, "x", false],
+            "empty": [], "skip": null
+        });
+        let params = Parameter::from_map(values.as_object().unwrap().clone()).unwrap();
+        assert_eq!(
+            params,
+            vec![
+                Parameter::text("b", "true"),
+                Parameter::number("big", "18446744073709551615"),
+                Parameter::list_multi("empty", Vec::<String>::new()),
+                Parameter::list_multi("mixed", ["1", "x", "false"]),
+                Parameter::number("n", "1.5"),
+            ]
+        );
+
+        let values = json!({"ok": 1, "obj": {}, "nested": [[1]]});
+        let error = Parameter::from_map(values.as_object().unwrap().clone()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid parameters: nested (list values must be strings, numbers or booleans); \
+             obj (objects are not a parameter type)"
+        );
+        let map: std::collections::HashMap<&str, Value> = [("Days", json!("7"))].into();
+        assert_eq!(
+            Parameter::from_map(map).unwrap(),
+            vec![Parameter::text("Days", "7")]
+        );
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(extract_placeholders("SELECT 1"), Vec::<String>::new());
+        assert_eq!(
+            extract_placeholders("{{a}}{{ b }}{{}} {{a}} {{ {{c}} {{x\ny}} {{d"),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            extract_placeholders("SELECT '{{ Wallet Address }}'"),
+            vec!["Wallet Address"]
+        );
+        let parameters = [Parameter::text("a", "1"), Parameter::text("unused", "2")];
+        assert_eq!(check_placeholders("{{a}}", &parameters), Ok(()));
+        assert_eq!(
+            check_placeholders("{{b}} {{a}} {{c}}", &parameters)
+                .unwrap_err()
+                .to_string(),
+            "invalid parameters: b (no parameter given for {{b}}); c (no parameter given for {{c}})"
+        );
+    }
+
+    #[test]
+    fn validated_parameters() {
+        let definitions: Vec<QueryParameter> = serde_json::from_value(json!([
+            {"key": "Token", "type": "enum", "value": "ETH", "enumOptions": ["ETH", "USDC"]},
+            {"key": "Days", "type": "number", "value": "7"},
+            {"key": "Start", "type": "datetime", "value": "2024-01-01 00:00:00"}
+        ]))
+        .unwrap();
+        let valid = [
+            Parameter::list_multi("Token", ["ETH", "USDC"]),
+            Parameter::number("Days", "1e3"),
+        ];
+        assert_eq!(validate(&definitions, &valid), Ok(()));
+        assert_eq!(validate(&[], &[]), Ok(()));
+
+        let invalid = [
+            Parameter::list_multi("Token", ["ETH", "DAI", "WBTC"]),
+            Parameter::number("Days", "seven"),
+            Parameter::text("Start", "2024-01-01"),
+            Parameter::text("End", "2024-02-01"),
+        ];
+        assert_eq!(
+            validate(&definitions, &invalid).unwrap_err().to_string(),
+            "invalid parameters: Token (`DAI`, `WBTC` are not among its options: ETH, USDC); \
+             Days (`seven` is not a number); \
+             Start (the query declares a datetime parameter, got text); \
+             End (not a parameter of the query, which has: Token, Days, Start)"
+        );
+        assert_eq!(
+            validate(&[], &[Parameter::text("End", "x")])
+                .unwrap_err()
+                .to_string(),
+            "invalid parameters: End (the query has no parameters)"
+        );
+    }
+
     #[test]
     fn new_parameter() {
         assert_eq!(
@@ -133,6 +985,40 @@ mod tests {
         )
     }
 
+    #[test]
+    fn definition() {
+        assert_eq!(
+            Parameter::number("MyNumber", "3").definition(),
+            json!({"key": "MyNumber", "type": "number", "value": "3"})
+        );
+        assert_eq!(
+            Parameter::list("MyEnum", "Item 1").definition(),
+            json!({"key": "MyEnum", "type": "enum", "value": "Item 1", "enumOptions": ["Item 1"]})
+        );
+        let date = Parameter::date("MyDate", date_parse("2022-01-01T01:02:03.123Z").unwrap());
+        assert_eq!(date.definition()["type"], "datetime");
+        assert_eq!(
+            Parameter::list_multi("Tokens", vec!["ETH".to_string(), "USDC".to_string()])
+                .definition(),
+            json!({
+                "key": "Tokens", "type": "enum", "value": ["ETH", "USDC"],
+                "enumOptions": ["ETH", "USDC"], "isMultiselect": true
+            })
+        );
+    }
+
+    #[test]
+    fn multi_select_values() {
+        let tokens = Parameter::list_multi("Tokens", ["ETH", "US\"DC"]);
+        assert_eq!(tokens.value, r#"["ETH","US\"DC"]"#);
+        assert_eq!(tokens.api_value(), json!(["ETH", "US\"DC"]));
+        assert_eq!(Parameter::list("Token", "ETH").api_value(), json!("ETH"));
+        assert_ne!(
+            Parameter::list_multi("Tokens", ["a,b"]),
+            Parameter::list_multi("Tokens", ["a", "b"])
+        );
+    }
+
     #[test]
     fn derived_debug() {
         assert_eq!(format!("{:?}", ParameterType::Date), "Date");
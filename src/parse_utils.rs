@@ -3,9 +3,20 @@
 //! Dune often returns numbers and dates as **strings** in JSON. Use the deserializer helpers here
 //! with `#[serde(deserialize_with = "...")]` so your structs can use `f64` or `DateTime<Utc>`.
 
-use chrono::{DateTime, NaiveDateTime, ParseError, Utc};
-use serde::{de, Deserialize, Deserializer};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, ParseError, Utc};
+use serde::de::DeserializeOwned;
+use serde::{de, Deserialize, Deserializer, Serializer};
 use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Date format used by the API for execution metadata (`submitted_at`, `expires_at`, …).
+///
+/// `%.f` reads the fraction as such (`.123` is 123ms) and accepts timestamps without one.
+const API_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.fZ";
+
+/// [`API_DATE_FORMAT`] as the API writes it, with milliseconds (RFC 3339).
+const API_DATE_OUTPUT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
 
 fn date_string_parser(date_str: &str, format: &str) -> Result<DateTime<Utc>, ParseError> {
     let native = NaiveDateTime::parse_from_str(date_str, format);
@@ -14,7 +25,7 @@ fn date_string_parser(date_str: &str, format: &str) -> Result<DateTime<Utc>, Par
 
 /// Parses API metadata date strings (e.g. `submitted_at`, `execution_ended_at`).
 ///
-/// Format: `%Y-%m-%dT%H:%M:%S.%fZ` (ISO 8601 with optional subseconds).
+/// Format: `%Y-%m-%dT%H:%M:%S%.fZ` (ISO 8601 with optional subseconds).
 ///
 /// # Example
 ///
@@ -25,16 +36,358 @@ fn date_string_parser(date_str: &str, format: &str) -> Result<DateTime<Utc>, Par
 /// assert_eq!(dt.format("%Y-%m-%d").to_string(), "2022-01-01");
 /// ```
 pub fn date_parse(date_str: &str) -> Result<DateTime<Utc>, ParseError> {
-    date_string_parser(date_str, "%Y-%m-%dT%H:%M:%S.%fZ")
+    date_string_parser(date_str, API_DATE_FORMAT)
 }
 
 /// Parses timestamp strings returned in **query result** columns (Dune timestamp type).
 ///
 /// Accepts `YYYY-MM-DD HH:MM:SS` or `YYYY-MM-DD HH:MM:SS.ffffff`.
 pub fn dune_date(date_str: &str) -> Result<DateTime<Utc>, ParseError> {
-    // Try with microseconds first
-    date_string_parser(date_str, "%Y-%m-%d %H:%M:%S.%f")
-        .or_else(|_| date_string_parser(date_str, "%Y-%m-%d %H:%M:%S"))
+    date_string_parser(date_str, "%Y-%m-%d %H:%M:%S%.f")
+}
+
+/// Parses a timestamp in any format Dune emits: API metadata (`2022-01-01T00:00:00.000Z`), query
+/// results with or without a ` UTC` suffix (`2022-01-01 00:00:00.000 UTC`), RFC 3339 with any
+/// offset (`2022-01-01T02:00:00+02:00`), ISO 8601 without offset, and date-only (`2022-01-01`, as
+/// midnight UTC).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::parse_any_datetime;
+///
+/// let expected = parse_any_datetime("2022-01-01T00:00:00Z").unwrap();
+/// for text in [
+///     "2022-01-01 00:00:00.000 UTC",
+///     "2022-01-01T02:00:00+02:00",
+///     "2022-01-01T00:00:00",
+///     "2022-01-01",
+/// ] {
+///     assert_eq!(parse_any_datetime(text).unwrap(), expected);
+/// }
+/// ```
+pub fn parse_any_datetime(date_str: &str) -> Result<DateTime<Utc>, ParseError> {
+    let text = date_str.trim();
+    parse_timestamp(text).or_else(|_| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+    })
+}
+
+/// Parses a date: date-only (`2022-01-01`) or the date of any timestamp
+/// [`parse_any_datetime`] accepts, in UTC.
+pub fn parse_any_date(date_str: &str) -> Result<NaiveDate, ParseError> {
+    let text = date_str.trim();
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .or_else(|_| parse_timestamp(text).map(|date| date.date_naive()))
+}
+
+/// Parses the timestamp (with time of day) formats of [`parse_any_datetime`].
+fn parse_timestamp(text: &str) -> Result<DateTime<Utc>, ParseError> {
+    let text = text
+        .strip_suffix(" UTC")
+        .or_else(|| text.strip_suffix(" Z"))
+        .unwrap_or(text);
+    // `%.f` reads fractions as such (`.25` is 250ms).
+    DateTime::parse_from_rfc3339(text)
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|_| date_string_parser(text, "%Y-%m-%dT%H:%M:%S%.fZ"))
+        .or_else(|_| date_string_parser(text, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| date_string_parser(text, "%Y-%m-%dT%H:%M:%S%.f"))
+}
+
+/// Serde deserializer for timestamp columns in any format Dune emits; see [`parse_any_datetime`].
+///
+/// # Example
+///
+/// ```rust
+/// use duners::chrono::{DateTime, NaiveDate, Utc};
+/// use duners::parse_utils::{any_datetime_from_str, date_from_str};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "any_datetime_from_str")]
+///     block_time: DateTime<Utc>,
+///     #[serde(deserialize_with = "date_from_str")]
+///     day: NaiveDate,
+/// }
+///
+/// let row: Row = serde_json::from_str(
+///     r#"{"block_time": "2024-01-01 12:00:00.000 UTC", "day": "2024-01-01 00:00:00.000 UTC"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(row.block_time.to_rfc3339(), "2024-01-01T12:00:00+00:00");
+/// assert_eq!(row.day, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+/// ```
+pub fn any_datetime_from_str<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    parse_any_datetime(&s).map_err(|err| de::Error::custom(format!("{s:?}: {err}")))
+}
+
+/// Serde deserializer for optional timestamp columns in any format; `null` is `None`.
+pub fn optional_any_datetime_from_str<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    s.map(|s| parse_any_datetime(&s).map_err(|err| de::Error::custom(format!("{s:?}: {err}"))))
+        .transpose()
+}
+
+/// Serde deserializer for `date` columns (or the date of a timestamp) into `NaiveDate`; see
+/// [`parse_any_date`].
+pub fn date_from_str<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    parse_any_date(&s).map_err(|err| de::Error::custom(format!("{s:?}: {err}")))
+}
+
+/// Serde deserializer for optional `date` columns; `null` is `None`.
+pub fn optional_date_from_str<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    s.map(|s| parse_any_date(&s).map_err(|err| de::Error::custom(format!("{s:?}: {err}"))))
+        .transpose()
+}
+
+/// Deserializers for [`time`](::time) types (feature `time`), for codebases standardized on
+/// `time` rather than `chrono`.
+///
+/// Timestamps are accepted in every format of [`parse_any_datetime`](super::parse_any_datetime)
+/// and produced in UTC.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use time::{Date, OffsetDateTime};
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "duners::parse_utils::time::offset_datetime_from_str")]
+///     block_time: OffsetDateTime,
+///     #[serde(deserialize_with = "duners::parse_utils::time::date_from_str")]
+///     day: Date,
+/// }
+///
+/// let row: Row = serde_json::from_str(
+///     r#"{"block_time": "2024-01-01 12:00:00.250 UTC", "day": "2024-01-01"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(row.block_time.unix_timestamp(), 1_704_110_400);
+/// assert_eq!(row.block_time.millisecond(), 250);
+/// assert_eq!(row.day.to_string(), "2024-01-01");
+/// ```
+#[cfg(feature = "time")]
+pub mod time {
+    use super::{parse_any_date, parse_any_datetime};
+    use ::time::{Date, Month, OffsetDateTime};
+    use chrono::{Datelike, Timelike};
+    use serde::{de, Deserialize, Deserializer};
+
+    /// Parses a timestamp in any format Dune emits into an `OffsetDateTime` in UTC.
+    pub fn parse_offset_datetime(date_str: &str) -> Result<OffsetDateTime, String> {
+        let date = parse_any_datetime(date_str).map_err(|err| format!("{date_str:?}: {err}"))?;
+        OffsetDateTime::from_unix_timestamp(date.timestamp())
+            .and_then(|time| time.replace_nanosecond(date.nanosecond()))
+            .map_err(|err| format!("{date_str:?}: {err}"))
+    }
+
+    /// Parses a date, or the date of a timestamp, into a `Date`.
+    pub fn parse_date(date_str: &str) -> Result<Date, String> {
+        let date = parse_any_date(date_str).map_err(|err| format!("{date_str:?}: {err}"))?;
+        Month::try_from(date.month() as u8)
+            .and_then(|month| Date::from_calendar_date(date.year(), month, date.day() as u8))
+            .map_err(|err| format!("{date_str:?}: {err}"))
+    }
+
+    /// Deserializes a timestamp string into an `OffsetDateTime` in UTC.
+    pub fn offset_datetime_from_str<'de, D>(deserializer: D) -> Result<OffsetDateTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        parse_offset_datetime(&s).map_err(de::Error::custom)
+    }
+
+    /// [`offset_datetime_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_offset_datetime_from_str<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<OffsetDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: Option<String> = Deserialize::deserialize(deserializer)?;
+        s.map(|s| parse_offset_datetime(&s))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+
+    /// Deserializes a date (or timestamp) string into a `Date`.
+    pub fn date_from_str<'de, D>(deserializer: D) -> Result<Date, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        parse_date(&s).map_err(de::Error::custom)
+    }
+
+    /// [`date_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_date_from_str<'de, D>(deserializer: D) -> Result<Option<Date>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: Option<String> = Deserialize::deserialize(deserializer)?;
+        s.map(|s| parse_date(&s))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Timestamps in a [`chrono_tz::Tz`](::chrono_tz::Tz) time zone (feature `chrono-tz`), for
+/// reports in a business time zone rather than UTC.
+///
+/// Dune timestamps are UTC; they are parsed with [`parse_any_datetime`](super::parse_any_datetime)
+/// and converted to the zone. For row fields, name the zone with a [`Zone`] type and use
+/// [`InZone`].
+///
+/// # Example
+///
+/// ```rust
+/// use chrono::DateTime;
+/// use chrono_tz::Tz;
+/// use duners::parse_utils::chrono_tz::{InZone, Zone};
+/// use serde::Deserialize;
+///
+/// struct NewYork;
+///
+/// impl Zone for NewYork {
+///     const TZ: Tz = Tz::America__New_York;
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(with = "InZone::<NewYork>")]
+///     block_time: DateTime<Tz>,
+///     #[serde(with = "InZone::<NewYork>")]
+///     settled: Option<DateTime<Tz>>,
+/// }
+///
+/// let row: Row = serde_json::from_str(
+///     r#"{"block_time": "2024-01-01 15:00:00.000 UTC", "settled": null}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(row.block_time.to_rfc3339(), "2024-01-01T10:00:00-05:00");
+/// ```
+#[cfg(feature = "chrono-tz")]
+pub mod chrono_tz {
+    use super::parse_any_datetime;
+    use ::chrono_tz::Tz;
+    use chrono::{DateTime, ParseError};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::marker::PhantomData;
+
+    /// Parses a Dune timestamp and converts it to `tz`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chrono_tz::Tz;
+    /// use duners::parse_utils::chrono_tz::parse_in_tz;
+    ///
+    /// let time = parse_in_tz("2024-07-01 12:00:00.000 UTC", Tz::Europe__Berlin).unwrap();
+    /// assert_eq!(time.to_rfc3339(), "2024-07-01T14:00:00+02:00");
+    /// ```
+    pub fn parse_in_tz(date_str: &str, tz: Tz) -> Result<DateTime<Tz>, ParseError> {
+        parse_any_datetime(date_str).map(|date| date.with_timezone(&tz))
+    }
+
+    /// A time zone known at compile time, for [`InZone`].
+    pub trait Zone {
+        /// The time zone timestamps are converted to.
+        const TZ: Tz;
+    }
+
+    /// `#[serde(with = "InZone::<Z>")]` for `DateTime<Tz>` and `Option<DateTime<Tz>>` fields,
+    /// converting timestamps to the zone `Z`. Serializes RFC 3339 with the zone's offset.
+    #[derive(Debug, Clone, Copy)]
+    pub struct InZone<Z>(PhantomData<Z>);
+
+    impl<Z: Zone> InZone<Z> {
+        /// Deserializes a timestamp string (or `null` for `Option` fields) in zone `Z`.
+        pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+        where
+            D: Deserializer<'de>,
+            T: ZonedValue,
+        {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(s) => parse_in_tz(&s, Z::TZ)
+                    .map(T::from_datetime)
+                    .map_err(|err| de::Error::custom(format!("{s:?}: {err}"))),
+                None => T::from_null().ok_or_else(|| de::Error::custom("expected a timestamp")),
+            }
+        }
+
+        /// Serializes as RFC 3339, or `null`.
+        pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            T: ZonedValue,
+        {
+            match value.datetime() {
+                Some(date) => serializer.serialize_str(&date.to_rfc3339()),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+
+    /// A field type [`InZone`] can fill.
+    pub trait ZonedValue: Sized {
+        /// Wraps a converted timestamp.
+        fn from_datetime(date: DateTime<Tz>) -> Self;
+
+        /// The timestamp, or `None` for an absent value.
+        fn datetime(&self) -> Option<&DateTime<Tz>>;
+
+        /// The value of a `null` column, if the type allows one.
+        fn from_null() -> Option<Self> {
+            None
+        }
+    }
+
+    impl ZonedValue for DateTime<Tz> {
+        fn from_datetime(date: DateTime<Tz>) -> Self {
+            date
+        }
+
+        fn datetime(&self) -> Option<&DateTime<Tz>> {
+            Some(self)
+        }
+    }
+
+    impl ZonedValue for Option<DateTime<Tz>> {
+        fn from_datetime(date: DateTime<Tz>) -> Self {
+            Some(date)
+        }
+
+        fn datetime(&self) -> Option<&DateTime<Tz>> {
+            self.as_ref()
+        }
+
+        fn from_null() -> Option<Self> {
+            Some(None)
+        }
+    }
 }
 
 /// Serde deserializer for date/time fields that Dune returns as strings.
@@ -83,6 +436,28 @@ where
     }
 }
 
+/// Serde serializer writing a `DateTime<Utc>` in the API metadata format understood by [`date_parse`].
+pub fn datetime_to_str<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&date.format(API_DATE_OUTPUT_FORMAT).to_string())
+}
+
+/// Serde serializer for optional date/time fields; the counterpart of [`optional_datetime_from_str`].
+pub fn optional_datetime_to_str<S>(
+    date: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match date {
+        Some(date) => datetime_to_str(date, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// Serde deserializer for numeric fields that Dune returns as strings.
 ///
 /// Use with `#[serde(deserialize_with = "duners::parse_utils::f64_from_str")]` on `f64` fields
@@ -114,25 +489,1910 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Serde deserializer keeping a numeric column as its exact decimal text.
+///
+/// Accepts JSON numbers as well as numeric strings and returns the digits unchanged, so values such
+/// as 18-decimal token amounts can be handed to a decimal/big-number type without passing through
+/// `f64`. Digits of JSON *numbers* are only preserved exactly with the `arbitrary-precision`
+/// feature enabled (which also makes `serde_json::Value` rows lossless).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::number_as_string;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Transfer {
+///     #[serde(deserialize_with = "number_as_string")]
+///     amount: String,
+/// }
+///
+/// let transfer: Transfer = serde_json::from_str(r#"{"amount": "1000000000000000001"}"#).unwrap();
+/// assert_eq!(transfer.amount, "1000000000000000001");
+/// ```
+pub fn number_as_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(number) => Ok(number.to_string()),
+        Value::String(s) => Ok(s),
+        other => Err(de::Error::custom(format!("expected a number, got {other}"))),
+    }
+}
 
-    #[test]
-    fn date_parse_works() {
-        let date_str = "2022-01-01T01:02:03.123Z";
-        assert_eq!(
-            date_parse(date_str).unwrap().to_string(),
-            "2022-01-01 01:02:03.000000123 UTC"
-        )
+/// Serde deserializer for columns holding JSON encoded as a string, e.g. `"{\"a\": 1}"`.
+///
+/// Parses the string into any `Deserialize` type, such as `serde_json::Value` or a nested struct.
+/// Columns that already hold JSON objects or arrays are accepted as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::{json_from_str, optional_json_from_str};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Metadata {
+///     name: String,
+///     decimals: u8,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Token {
+///     #[serde(deserialize_with = "json_from_str")]
+///     metadata: Metadata,
+///     #[serde(deserialize_with = "optional_json_from_str")]
+///     extra: Option<serde_json::Value>,
+/// }
+///
+/// let token: Token = serde_json::from_str(
+///     r#"{"metadata": "{\"name\": \"Wrapped Ether\", \"decimals\": 18}", "extra": null}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(token.metadata.name, "Wrapped Ether");
+/// assert_eq!(token.metadata.decimals, 18);
+/// assert!(token.extra.is_none());
+/// ```
+pub fn json_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    json_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional JSON-encoded columns; `null` is `None`.
+pub fn optional_json_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .map(json_from_value)
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+/// Parses a JSON-encoded string, or converts an already decoded value, into `T`.
+fn json_from_value<T: DeserializeOwned>(value: Value) -> Result<T, String> {
+    match value {
+        Value::String(s) => serde_json::from_str(&s).map_err(|err| format!("{s:?}: {err}")),
+        value => serde_json::from_value(value).map_err(|err| err.to_string()),
     }
+}
 
-    #[test]
-    fn new_dune_date() {
-        let date_str = "2022-05-04 00:00:00.000";
-        assert_eq!(
-            dune_date(date_str).unwrap().to_string(),
-            "2022-05-04 00:00:00 UTC"
+/// Serde deserializer for array columns (e.g. from `array_agg`) into `Vec<T>`.
+///
+/// Accepts JSON arrays as well as arrays rendered as strings, either JSON (`"[1, 2, 3]"`) or
+/// Trino-style with bare elements (`"[a, b, c]"`, `"{1,2,3}"`). String elements are parsed as JSON
+/// scalars when `T` needs it, so `Vec<f64>` reads both `[1.5]` and `["1.5"]`; `NULL` elements
+/// are `null`.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::array_from_str;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "array_from_str")]
+///     prices: Vec<f64>,
+///     #[serde(deserialize_with = "array_from_str")]
+///     symbols: Vec<String>,
+///     #[serde(deserialize_with = "array_from_str")]
+///     fees: Vec<Option<u64>>,
+/// }
+///
+/// let row: Row = serde_json::from_str(
+///     r#"{"prices": ["1.5", 2], "symbols": "[ETH, USDC]", "fees": "{1,NULL,3}"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(row.prices, [1.5, 2.0]);
+/// assert_eq!(row.symbols, ["ETH", "USDC"]);
+/// assert_eq!(row.fees, [Some(1), None, Some(3)]);
+/// ```
+pub fn array_from_str<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    array_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional array columns; `null` is `None`.
+pub fn optional_array_from_str<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .map(array_from_value)
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+/// Reads a JSON array or an array rendered as a string into `Vec<T>`.
+fn array_from_value<T: DeserializeOwned>(value: Value) -> Result<Vec<T>, String> {
+    let elements = match value {
+        Value::Array(elements) => elements,
+        Value::String(s) => match serde_json::from_str::<Vec<Value>>(&s) {
+            Ok(elements) => elements,
+            Err(_) => rendered_array(&s).ok_or_else(|| format!("{s:?} is not an array"))?,
+        },
+        other => return Err(format!("expected an array, got {other}")),
+    };
+    elements
+        .into_iter()
+        .enumerate()
+        .map(|(index, element)| {
+            array_element(element).map_err(|err| format!("array element {index}: {err}"))
+        })
+        .collect()
+}
+
+/// Converts one array element, parsing string elements as JSON scalars if `T` needs it.
+fn array_element<T: DeserializeOwned>(element: Value) -> Result<T, String> {
+    match serde_json::from_value(element.clone()) {
+        Ok(value) => Ok(value),
+        Err(err) => match element {
+            Value::String(s) => serde_json::from_str(&s).map_err(|_| err.to_string()),
+            _ => Err(err.to_string()),
+        },
+    }
+}
+
+/// Elements of a Trino-style array string such as `[a, b]` or `{1,NULL}`, as JSON values:
+/// nested arrays and JSON scalars are parsed, other elements are kept as strings.
+fn rendered_array(s: &str) -> Option<Vec<Value>> {
+    let s = s.trim();
+    let inner = s
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .or_else(|| s.strip_prefix('{').and_then(|rest| rest.strip_suffix('}')))?;
+    if inner.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    let mut elements = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+    for (position, character) in inner.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            '[' | '{' | '(' if !quoted => depth += 1,
+            ']' | '}' | ')' if !quoted => depth = depth.checked_sub(1)?,
+            ',' if !quoted && depth == 0 => {
+                elements.push(&inner[start..position]);
+                start = position + 1;
+            }
+            _ => {}
+        }
+    }
+    elements.push(&inner[start..]);
+    Some(
+        elements
+            .into_iter()
+            .map(|element| {
+                let element = element.trim();
+                if element.eq_ignore_ascii_case("null") {
+                    return Value::Null;
+                }
+                serde_json::from_str(element)
+                    .ok()
+                    .or_else(|| rendered_array(element).map(Value::Array))
+                    .unwrap_or_else(|| Value::String(element.to_string()))
+            })
+            .collect(),
+    )
+}
+
+/// Serde deserializer for `u64` columns that Dune returns as strings (e.g. block numbers or
+/// token amounts), without the precision loss of going through [`f64_from_str`].
+///
+/// JSON integers are accepted as well.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::{i128_from_str, optional_u64_from_str, u64_from_str};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Block {
+///     #[serde(deserialize_with = "u64_from_str")]
+///     number: u64,
+///     #[serde(deserialize_with = "i128_from_str")]
+///     balance_change: i128,
+///     #[serde(deserialize_with = "optional_u64_from_str")]
+///     base_fee: Option<u64>,
+/// }
+///
+/// let block: Block = serde_json::from_str(
+///     r#"{"number": "18446744073709551615", "balance_change": "-170141183460469231731687303715884105728", "base_fee": null}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(block.number, u64::MAX);
+/// assert_eq!(block.balance_change, i128::MIN);
+/// assert_eq!(block.base_fee, None);
+/// ```
+pub fn u64_from_str<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    integer_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for `i64` columns that Dune returns as strings; see [`u64_from_str`].
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::i64_from_str;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "i64_from_str")]
+///     delta: i64,
+/// }
+///
+/// let row: Row = serde_json::from_str(r#"{"delta": "-42"}"#).unwrap();
+/// assert_eq!(row.delta, -42);
+/// ```
+pub fn i64_from_str<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    integer_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for `i128` columns that Dune returns as strings; see [`u64_from_str`].
+pub fn i128_from_str<'de, D>(deserializer: D) -> Result<i128, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    integer_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional `u64` columns; `null` is `None`.
+pub fn optional_u64_from_str<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    optional_integer_from_value(Deserialize::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional `i64` columns; `null` is `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::optional_i64_from_str;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "optional_i64_from_str")]
+///     delta: Option<i64>,
+/// }
+///
+/// let row: Row = serde_json::from_str(r#"{"delta": "7"}"#).unwrap();
+/// assert_eq!(row.delta, Some(7));
+/// ```
+pub fn optional_i64_from_str<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    optional_integer_from_value(Deserialize::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional `i128` columns; `null` is `None`.
+pub fn optional_i128_from_str<'de, D>(deserializer: D) -> Result<Option<i128>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    optional_integer_from_value(Deserialize::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Parses a JSON integer or integer string as `T`.
+fn integer_from_value<T>(value: Value) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match value {
+        Value::Number(number) => number
+            .to_string()
+            .parse()
+            .map_err(|err| format!("{number}: {err}")),
+        Value::String(s) => s.trim().parse().map_err(|err| format!("{s:?}: {err}")),
+        other => Err(format!("expected an integer, got {other}")),
+    }
+}
+
+fn optional_integer_from_value<T>(value: Option<Value>) -> Result<Option<T>, String>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value.map(integer_from_value).transpose()
+}
+
+/// Serde deserializer for boolean columns Dune returns as strings or numbers.
+///
+/// Accepts JSON booleans, `"true"`/`"false"`, `"t"`/`"f"` (any case) and `0`/`1` as numbers or
+/// strings.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::{bool_from_str, optional_bool_from_str};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Pool {
+///     #[serde(deserialize_with = "bool_from_str")]
+///     active: bool,
+///     #[serde(deserialize_with = "bool_from_str")]
+///     verified: bool,
+///     #[serde(deserialize_with = "optional_bool_from_str")]
+///     deprecated: Option<bool>,
+/// }
+///
+/// let pool: Pool =
+///     serde_json::from_str(r#"{"active": "t", "verified": 0, "deprecated": null}"#).unwrap();
+/// assert!(pool.active);
+/// assert!(!pool.verified);
+/// assert_eq!(pool.deprecated, None);
+/// ```
+pub fn bool_from_str<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    bool_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional boolean columns; `null` is `None`.
+pub fn optional_bool_from_str<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .map(|value| bool_from_value(&value))
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+/// Reads the spellings of a boolean accepted by [`bool_from_str`].
+pub(crate) fn bool_from_value(value: &Value) -> Result<bool, String> {
+    let parsed = match value {
+        Value::Bool(value) => Some(*value),
+        Value::Number(number) => match number.as_u64() {
+            Some(0) => Some(false),
+            Some(1) => Some(true),
+            _ => None,
+        },
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "t" | "1" => Some(true),
+            "false" | "f" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    };
+    parsed.ok_or_else(|| format!("expected a boolean, got {value}"))
+}
+
+/// Serde deserializer for UUID columns (feature `uuid`), in any form [`uuid::Uuid::parse_str`]
+/// accepts (hyphenated, simple, braced or URN).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::uuid_from_str;
+/// use serde::Deserialize;
+/// use uuid::Uuid;
+///
+/// #[derive(Deserialize)]
+/// struct Order {
+///     #[serde(deserialize_with = "uuid_from_str")]
+///     id: Uuid,
+/// }
+///
+/// let order: Order =
+///     serde_json::from_str(r#"{"id": "67E55044-10B1-426F-9247-BB680E5FE0C8"}"#).unwrap();
+/// assert_eq!(order.id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// ```
+#[cfg(feature = "uuid")]
+pub fn uuid_from_str<'de, D>(deserializer: D) -> Result<uuid::Uuid, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    uuid::Uuid::parse_str(s.trim()).map_err(|err| de::Error::custom(format!("{s:?}: {err}")))
+}
+
+/// Serde deserializer for optional UUID columns (feature `uuid`); `null` is `None`.
+#[cfg(feature = "uuid")]
+pub fn optional_uuid_from_str<'de, D>(deserializer: D) -> Result<Option<uuid::Uuid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    s.map(|s| {
+        uuid::Uuid::parse_str(s.trim()).map_err(|err| de::Error::custom(format!("{s:?}: {err}")))
+    })
+    .transpose()
+}
+
+/// Serde deserializer for `varbinary` columns holding 20-byte addresses (`0x…` hex).
+///
+/// With the `alloy` feature, `alloy::address_from_str` reads into `alloy_primitives::Address`
+/// instead.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::{address_from_str, bytes_from_str};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Transfer {
+///     #[serde(deserialize_with = "address_from_str")]
+///     to: [u8; 20],
+///     #[serde(deserialize_with = "bytes_from_str")]
+///     data: Vec<u8>,
+/// }
+///
+/// let transfer: Transfer = serde_json::from_str(
+///     r#"{"to": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045", "data": "0xa9059cbb"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(transfer.to[0], 0xd8);
+/// assert_eq!(transfer.data, [0xa9, 0x05, 0x9c, 0xbb]);
+/// ```
+pub fn address_from_str<'de, D>(deserializer: D) -> Result<[u8; 20], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    address_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional address columns; `null` is `None`.
+pub fn optional_address_from_str<'de, D>(deserializer: D) -> Result<Option<[u8; 20]>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .map(|value| address_from_value(&value))
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+/// Serde deserializer for `varbinary` columns (`0x…` hex) of any length.
+pub fn bytes_from_str<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    bytes_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Serde deserializer for optional `varbinary` columns; `null` is `None`.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::optional_bytes_from_str;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "optional_bytes_from_str")]
+///     input: Option<Vec<u8>>,
+/// }
+///
+/// let row: Row = serde_json::from_str(r#"{"input": "0x"}"#).unwrap();
+/// assert_eq!(row.input, Some(vec![]));
+/// ```
+pub fn optional_bytes_from_str<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Value>::deserialize(deserializer)?
+        .map(|value| bytes_from_value(&value))
+        .transpose()
+        .map_err(de::Error::custom)
+}
+
+/// Decodes a hex string, with or without `0x` prefix.
+fn bytes_from_value(value: &Value) -> Result<Vec<u8>, String> {
+    let s = match value {
+        Value::String(s) => s.trim(),
+        other => return Err(format!("expected a hex string, got {other}")),
+    };
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    if hex.len() % 2 != 0 {
+        return Err(format!("{s:?}: odd number of hex digits"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("{s:?}: invalid hex digit"))
+        })
+        .collect()
+}
+
+/// Decodes a 20-byte hex string.
+fn address_from_value(value: &Value) -> Result<[u8; 20], String> {
+    let bytes = bytes_from_value(value)?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("{value}: expected 20 bytes, got {}", bytes.len()))
+}
+
+/// The digits and radix of a 256-bit integer: a JSON number, a decimal string or a `0x` hex
+/// string.
+#[cfg(any(feature = "alloy", feature = "primitive-types"))]
+fn uint_digits(value: &Value) -> Result<(String, u32), String> {
+    let s = match value {
+        Value::Number(number) => return Ok((number.to_string(), 10)),
+        Value::String(s) => s.trim(),
+        other => return Err(format!("expected an unsigned integer, got {other}")),
+    };
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => Ok((hex.to_string(), 16)),
+        None => Ok((s.to_string(), 10)),
+    }
+}
+
+/// Deserializers for [`alloy_primitives`] types (feature `alloy`): [`U256`](alloy_primitives::U256)
+/// for `uint256` columns and token amounts, [`Address`](alloy_primitives::Address) and
+/// [`Bytes`](alloy_primitives::Bytes) for `varbinary` columns.
+///
+/// Integers may be decimal strings, `0x` hex strings or JSON numbers; addresses and bytes are
+/// `0x` hex strings.
+///
+/// # Example
+///
+/// ```rust
+/// use alloy_primitives::U256;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Transfer {
+///     #[serde(deserialize_with = "duners::parse_utils::alloy::u256_from_str")]
+///     amount: U256,
+///     #[serde(deserialize_with = "duners::parse_utils::alloy::optional_u256_from_str")]
+///     fee: Option<U256>,
+/// }
+///
+/// let transfer: Transfer =
+///     serde_json::from_str(r#"{"amount": "1000000000000000000000", "fee": "0xff"}"#).unwrap();
+/// assert_eq!(transfer.amount, U256::from(10u64).pow(U256::from(21u64)));
+/// assert_eq!(transfer.fee, Some(U256::from(255u64)));
+/// ```
+#[cfg(feature = "alloy")]
+pub mod alloy {
+    use super::{address_from_value, bytes_from_value, uint_digits};
+    use alloy_primitives::{Address, Bytes, U256};
+    use serde::{de, Deserialize, Deserializer};
+    use serde_json::Value;
+
+    fn u256_from_value(value: &Value) -> Result<U256, String> {
+        let (digits, radix) = uint_digits(value)?;
+        U256::from_str_radix(&digits, radix.into()).map_err(|err| format!("{value}: {err}"))
+    }
+
+    /// Deserializes a decimal string, `0x` hex string or JSON number.
+    pub fn u256_from_str<'de, D>(deserializer: D) -> Result<U256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u256_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+
+    /// [`u256_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_u256_from_str<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Value>::deserialize(deserializer)?
+            .map(|value| u256_from_value(&value))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+
+    /// Deserializes a 20-byte `0x` hex string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use alloy_primitives::{address, Address, Bytes};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Call {
+    ///     #[serde(deserialize_with = "duners::parse_utils::alloy::address_from_str")]
+    ///     to: Address,
+    ///     #[serde(deserialize_with = "duners::parse_utils::alloy::bytes_from_str")]
+    ///     input: Bytes,
+    /// }
+    ///
+    /// let call: Call = serde_json::from_str(
+    ///     r#"{"to": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045", "input": "0xa9059cbb"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(call.to, address!("d8da6bf26964af9d7eed9e03e53415d37aa96045"));
+    /// assert_eq!(call.input.len(), 4);
+    /// ```
+    pub fn address_from_str<'de, D>(deserializer: D) -> Result<Address, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        address_from_value(&Value::deserialize(deserializer)?)
+            .map(Address::from)
+            .map_err(de::Error::custom)
+    }
+
+    /// [`address_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_address_from_str<'de, D>(deserializer: D) -> Result<Option<Address>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Value>::deserialize(deserializer)?
+            .map(|value| address_from_value(&value).map(Address::from))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+
+    /// Deserializes a `0x` hex string of any length.
+    pub fn bytes_from_str<'de, D>(deserializer: D) -> Result<Bytes, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        bytes_from_value(&Value::deserialize(deserializer)?)
+            .map(Bytes::from)
+            .map_err(de::Error::custom)
+    }
+
+    /// [`bytes_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_bytes_from_str<'de, D>(deserializer: D) -> Result<Option<Bytes>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Value>::deserialize(deserializer)?
+            .map(|value| bytes_from_value(&value).map(Bytes::from))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Deserializers for [`primitive_types::U256`](::primitive_types::U256) fields (feature
+/// `primitive-types`), e.g. `uint256` columns and token amounts.
+///
+/// Values may be decimal strings, `0x` hex strings or JSON numbers.
+///
+/// # Example
+///
+/// ```rust
+/// use primitive_types::U256;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Transfer {
+///     #[serde(deserialize_with = "duners::parse_utils::primitive_types::u256_from_str")]
+///     amount: U256,
+///     #[serde(deserialize_with = "duners::parse_utils::primitive_types::optional_u256_from_str")]
+///     fee: Option<U256>,
+/// }
+///
+/// let transfer: Transfer =
+///     serde_json::from_str(r#"{"amount": "1000000000000000000000", "fee": null}"#).unwrap();
+/// assert_eq!(transfer.amount, U256::exp10(21));
+/// assert_eq!(transfer.fee, None);
+/// ```
+#[cfg(feature = "primitive-types")]
+pub mod primitive_types {
+    use super::uint_digits;
+    use ::primitive_types::U256;
+    use serde::{de, Deserialize, Deserializer};
+    use serde_json::Value;
+
+    fn u256_from_value(value: &Value) -> Result<U256, String> {
+        let (digits, radix) = uint_digits(value)?;
+        let parsed = match radix {
+            10 => U256::from_dec_str(&digits).map_err(|err| err.to_string()),
+            _ => U256::from_str_radix(&digits, radix).map_err(|err| err.to_string()),
+        };
+        parsed.map_err(|err| format!("{value}: {err}"))
+    }
+
+    /// Deserializes a decimal string, `0x` hex string or JSON number.
+    pub fn u256_from_str<'de, D>(deserializer: D) -> Result<U256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u256_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+
+    /// [`u256_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_u256_from_str<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Value>::deserialize(deserializer)?
+            .map(|value| u256_from_value(&value))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The text of a decimal number: a JSON number or a numeric string.
+fn decimal_text(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Number(number) => Ok(number.to_string()),
+        Value::String(s) => Ok(s.trim().to_string()),
+        other => Err(format!("expected a number, got {other}")),
+    }
+}
+
+/// Deserializers for [`rust_decimal::Decimal`] fields (feature `rust_decimal`), for prices and
+/// amounts that must not be rounded through `f64`.
+///
+/// Values may be numeric strings (including scientific notation) or JSON numbers. Digits of JSON
+/// *numbers* are only exact with the `arbitrary-precision` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use rust_decimal::Decimal;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Fill {
+///     #[serde(deserialize_with = "duners::parse_utils::rust_decimal::decimal_from_str")]
+///     price: Decimal,
+///     #[serde(deserialize_with = "duners::parse_utils::rust_decimal::optional_decimal_from_str")]
+///     fee: Option<Decimal>,
+/// }
+///
+/// let fill: Fill = serde_json::from_str(r#"{"price": "0.1", "fee": "2.5e-3"}"#).unwrap();
+/// assert_eq!(fill.price * Decimal::from(3), "0.3".parse::<Decimal>().unwrap());
+/// assert_eq!(fill.fee, Some("0.0025".parse().unwrap()));
+/// ```
+#[cfg(feature = "rust_decimal")]
+pub mod rust_decimal {
+    use super::decimal_text;
+    use ::rust_decimal::Decimal;
+    use serde::{de, Deserialize, Deserializer};
+    use serde_json::Value;
+    use std::str::FromStr;
+
+    fn decimal_from_value(value: &Value) -> Result<Decimal, String> {
+        let text = decimal_text(value)?;
+        let parsed = match text.contains(['e', 'E']) {
+            true => Decimal::from_scientific(&text),
+            false => Decimal::from_str(&text),
+        };
+        parsed.map_err(|err| format!("{value}: {err}"))
+    }
+
+    /// Deserializes a numeric string or JSON number.
+    pub fn decimal_from_str<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        decimal_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+
+    /// [`decimal_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_decimal_from_str<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Value>::deserialize(deserializer)?
+            .map(|value| decimal_from_value(&value))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Deserializers for [`bigdecimal::BigDecimal`](::bigdecimal::BigDecimal) fields (feature
+/// `bigdecimal`), for amounts beyond the 28 digits of `rust_decimal`, e.g. 18-decimal token
+/// balances.
+///
+/// Values may be numeric strings (including scientific notation) or JSON numbers. Digits of JSON
+/// *numbers* are only exact with the `arbitrary-precision` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use bigdecimal::BigDecimal;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Balance {
+///     #[serde(deserialize_with = "duners::parse_utils::bigdecimal::decimal_from_str")]
+///     amount: BigDecimal,
+/// }
+///
+/// let balance: Balance =
+///     serde_json::from_str(r#"{"amount": "123456789012345678901234.000000000000000001"}"#).unwrap();
+/// assert_eq!(balance.amount.to_string(), "123456789012345678901234.000000000000000001");
+/// ```
+#[cfg(feature = "bigdecimal")]
+pub mod bigdecimal {
+    use super::decimal_text;
+    use ::bigdecimal::BigDecimal;
+    use serde::{de, Deserialize, Deserializer};
+    use serde_json::Value;
+    use std::str::FromStr;
+
+    fn decimal_from_value(value: &Value) -> Result<BigDecimal, String> {
+        BigDecimal::from_str(&decimal_text(value)?).map_err(|err| format!("{value}: {err}"))
+    }
+
+    /// Deserializes a numeric string or JSON number.
+    pub fn decimal_from_str<'de, D>(deserializer: D) -> Result<BigDecimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        decimal_from_value(&Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+
+    /// [`decimal_from_str`] for optional fields; `null` is `None`.
+    pub fn optional_decimal_from_str<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<BigDecimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Value>::deserialize(deserializer)?
+            .map(|value| decimal_from_value(&value))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// `#[serde(with = "duners::parse_utils::Scaled::<18>")]` for fixed-point integer columns, e.g.
+/// raw wei amounts read as ether.
+///
+/// The column (an integer string or JSON number) is divided by `10^DECIMALS` by moving its decimal
+/// point, so the only rounding is the final conversion into the field type: `f64`, or
+/// `rust_decimal::Decimal` / `bigdecimal::BigDecimal` with their features, and `Option`s of them.
+/// Serializing multiplies back into the raw integer string.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::parse_utils::Scaled;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Transfer {
+///     #[serde(with = "Scaled::<18>")]
+///     value: f64,
+///     #[serde(with = "Scaled::<6>")]
+///     usdc: Option<f64>,
+/// }
+///
+/// let transfer: Transfer =
+///     serde_json::from_str(r#"{"value": "1500000000000000000", "usdc": "2500000"}"#).unwrap();
+/// assert_eq!(transfer.value, 1.5);
+/// assert_eq!(transfer.usdc, Some(2.5));
+/// assert_eq!(
+///     serde_json::to_string(&transfer).unwrap(),
+///     r#"{"value":"1500000000000000000","usdc":"2500000"}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Scaled<const DECIMALS: u32>;
+
+impl<const DECIMALS: u32> Scaled<DECIMALS> {
+    /// Deserializes a raw integer and divides it by `10^DECIMALS`.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: ScaledValue,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::Null => T::from_null().ok_or_else(|| de::Error::custom("expected a number")),
+            value => decimal_text(&value)
+                .and_then(|text| shift_decimal(&text, -i64::from(DECIMALS)))
+                .and_then(|text| T::parse_decimal(&text))
+                .map_err(de::Error::custom),
+        }
+    }
+
+    /// Serializes the value multiplied by `10^DECIMALS`, as a string.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: ScaledValue,
+    {
+        match value.to_decimal() {
+            Some(text) => {
+                let raw =
+                    shift_decimal(&text, i64::from(DECIMALS)).map_err(serde::ser::Error::custom)?;
+                serializer.serialize_str(&raw)
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// A field type [`Scaled`] can fill.
+pub trait ScaledValue: Sized {
+    /// Parses a plain decimal string such as `-1.25`.
+    fn parse_decimal(text: &str) -> Result<Self, String>;
+
+    /// Decimal text of the value, or `None` for an absent value.
+    fn to_decimal(&self) -> Option<String>;
+
+    /// The value of a `null` column, if the type allows one.
+    fn from_null() -> Option<Self> {
+        None
+    }
+}
+
+impl ScaledValue for f64 {
+    fn parse_decimal(text: &str) -> Result<Self, String> {
+        text.parse().map_err(|err| format!("{text:?}: {err}"))
+    }
+
+    fn to_decimal(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl ScaledValue for ::rust_decimal::Decimal {
+    fn parse_decimal(text: &str) -> Result<Self, String> {
+        text.parse().map_err(|err| format!("{text:?}: {err}"))
+    }
+
+    fn to_decimal(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl ScaledValue for ::bigdecimal::BigDecimal {
+    fn parse_decimal(text: &str) -> Result<Self, String> {
+        text.parse().map_err(|err| format!("{text:?}: {err}"))
+    }
+
+    fn to_decimal(&self) -> Option<String> {
+        Some(self.to_plain_string())
+    }
+}
+
+impl<T: ScaledValue> ScaledValue for Option<T> {
+    fn parse_decimal(text: &str) -> Result<Self, String> {
+        T::parse_decimal(text).map(Some)
+    }
+
+    fn to_decimal(&self) -> Option<String> {
+        self.as_ref().and_then(T::to_decimal)
+    }
+
+    fn from_null() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// Multiplies the decimal number `text` (optionally signed, with fraction and exponent) by
+/// `10^shift`, returning plain decimal text without exponent or redundant zeros.
+fn shift_decimal(text: &str, shift: i64) -> Result<String, String> {
+    let invalid = || format!("{text:?} is not a decimal number");
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().map_err(|_| invalid())?),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let digits = format!("{integer}{fraction}");
+    // Position of the decimal point within `digits` after shifting.
+    let point = integer.len() as i64 + exponent + shift;
+    let (integer, fraction) = if point <= 0 {
+        let zeros = "0".repeat(point.unsigned_abs() as usize);
+        (String::new(), format!("{zeros}{digits}"))
+    } else if point as usize >= digits.len() {
+        let zeros = "0".repeat(point as usize - digits.len());
+        (format!("{digits}{zeros}"), String::new())
+    } else {
+        let (integer, fraction) = digits.split_at(point as usize);
+        (integer.to_string(), fraction.to_string())
+    };
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+    let fraction = fraction.trim_end_matches('0');
+    let sign = match negative && (integer != "0" || !fraction.is_empty()) {
+        true => "-",
+        false => "",
+    };
+    Ok(match fraction {
+        "" => format!("{sign}{integer}"),
+        fraction => format!("{sign}{integer}.{fraction}"),
+    })
+}
+
+/// Parses a JSON number or numeric string as `f64`.
+fn f64_from_value(value: Value) -> Result<f64, String> {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .ok_or_else(|| format!("{number} does not fit an f64")),
+        Value::String(s) => s.trim().parse().map_err(|err| format!("{s:?}: {err}")),
+        other => Err(format!("expected a number, got {other}")),
+    }
+}
+
+/// Parses a date string in the API metadata or query result format.
+fn datetime_from_value(value: Value) -> Result<DateTime<Utc>, String> {
+    match value {
+        Value::String(s) => date_parse(&s)
+            .or_else(|_| dune_date(&s))
+            .map_err(|err| format!("{s:?}: {err}")),
+        other => Err(format!("expected a date string, got {other}")),
+    }
+}
+
+/// `#[serde(with = "duners::parse_utils::f64_str")]` for `f64` fields Dune sends as strings.
+///
+/// Unlike [`f64_from_str`], plain JSON numbers are accepted too. Serializes the value as a
+/// string, so rows round-trip.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Row {
+///     #[serde(with = "duners::parse_utils::f64_str")]
+///     price: f64,
+///     #[serde(with = "duners::parse_utils::option_f64_str")]
+///     volume: Option<f64>,
+///     #[serde(with = "duners::parse_utils::vec_f64_str")]
+///     ticks: Vec<f64>,
+/// }
+///
+/// let row: Row =
+///     serde_json::from_str(r#"{"price": "3.5", "volume": null, "ticks": ["1.5", 2]}"#).unwrap();
+/// assert_eq!((row.price, row.volume, row.ticks), (3.5, None, vec![1.5, 2.0]));
+/// ```
+pub mod f64_str {
+    use super::f64_from_value;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Deserializes a number or numeric string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        f64_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+
+    /// Serializes the value as a string.
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+}
+
+/// [`f64_str`] for `Option<f64>` fields; `null` is `None`.
+///
+/// # Example
+///
+/// ```rust
+/// #[derive(serde::Serialize)]
+/// struct Row {
+///     #[serde(with = "duners::parse_utils::option_f64_str")]
+///     volume: Option<f64>,
+/// }
+///
+/// let json = serde_json::to_string(&Row { volume: Some(1.5) }).unwrap();
+/// assert_eq!(json, r#"{"volume":"1.5"}"#);
+/// ```
+pub mod option_f64_str {
+    use super::f64_from_value;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Deserializes `null`, a number or a numeric string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f64>, D::Error> {
+        Option::<Value>::deserialize(deserializer)?
+            .map(f64_from_value)
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+
+    /// Serializes the value as a string, or `null`.
+    pub fn serialize<S: Serializer>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// [`f64_str`] for `Vec<f64>` fields (array columns).
+pub mod vec_f64_str {
+    use super::f64_from_value;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Deserializes an array of numbers or numeric strings.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        Vec::<Value>::deserialize(deserializer)?
+            .into_iter()
+            .map(f64_from_value)
+            .collect::<Result<_, _>>()
+            .map_err(de::Error::custom)
+    }
+
+    /// Serializes the values as strings.
+    pub fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(f64::to_string))
+    }
+}
+
+/// `#[serde(with = "duners::parse_utils::datetime_str")]` for `DateTime<Utc>` fields; the
+/// module form of [`datetime_from_str`] and [`datetime_to_str`].
+///
+/// # Example
+///
+/// ```rust
+/// use duners::chrono::{DateTime, Utc};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Row {
+///     #[serde(with = "duners::parse_utils::option_datetime_str")]
+///     last_trade: Option<DateTime<Utc>>,
+///     #[serde(with = "duners::parse_utils::vec_datetime_str")]
+///     blocks: Vec<DateTime<Utc>>,
+/// }
+///
+/// let row: Row = serde_json::from_str(
+///     r#"{"last_trade": "2024-01-01 12:00:00.000", "blocks": ["2024-01-01 00:00:00"]}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(row.last_trade.unwrap().to_rfc3339(), "2024-01-01T12:00:00+00:00");
+/// assert_eq!(row.blocks.len(), 1);
+/// ```
+pub mod datetime_str {
+    use super::datetime_from_value;
+    use chrono::{DateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Deserializes a date string in the API metadata or query result format.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        datetime_from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+
+    /// Serializes in the API metadata format.
+    pub fn serialize<S: Serializer>(
+        value: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::datetime_to_str(value, serializer)
+    }
+}
+
+/// [`datetime_str`] for `Option<DateTime<Utc>>` fields; `null` is `None`.
+///
+/// Unlike [`optional_datetime_from_str`], query result timestamps are accepted too.
+pub mod option_datetime_str {
+    use super::datetime_from_value;
+    use chrono::{DateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Deserializes `null` or a date string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<Value>::deserialize(deserializer)?
+            .map(datetime_from_value)
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+
+    /// Serializes in the API metadata format, or `null`.
+    pub fn serialize<S: Serializer>(
+        value: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::optional_datetime_to_str(value, serializer)
+    }
+}
+
+/// [`datetime_str`] for `Vec<DateTime<Utc>>` fields (array columns).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::chrono::{DateTime, Utc};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Row {
+///     #[serde(with = "duners::parse_utils::vec_datetime_str")]
+///     days: Vec<DateTime<Utc>>,
+/// }
+///
+/// let row: Row = serde_json::from_str(r#"{"days": ["2024-01-01 00:00:00.000"]}"#).unwrap();
+/// assert_eq!(row.days[0].to_rfc3339(), "2024-01-01T00:00:00+00:00");
+/// ```
+pub mod vec_datetime_str {
+    use super::{datetime_from_value, API_DATE_OUTPUT_FORMAT};
+    use chrono::{DateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    /// Deserializes an array of date strings.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<DateTime<Utc>>, D::Error> {
+        Vec::<Value>::deserialize(deserializer)?
+            .into_iter()
+            .map(datetime_from_value)
+            .collect::<Result<_, _>>()
+            .map_err(de::Error::custom)
+    }
+
+    /// Serializes the values in the API metadata format.
+    pub fn serialize<S: Serializer>(
+        values: &[DateTime<Utc>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            values
+                .iter()
+                .map(|value| value.format(API_DATE_OUTPUT_FORMAT).to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_parse_works() {
+        let date_str = "2022-01-01T01:02:03.123Z";
+        assert_eq!(
+            date_parse(date_str).unwrap().to_string(),
+            "2022-01-01 01:02:03.123 UTC"
+        )
+    }
+
+    #[test]
+    fn datetime_to_str_round_trips() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Wrapper {
+            #[serde(
+                serialize_with = "datetime_to_str",
+                deserialize_with = "datetime_from_str"
+            )]
+            date: DateTime<Utc>,
+        }
+        let wrapper = Wrapper {
+            date: date_parse("2022-01-01T01:02:03.123Z").unwrap(),
+        };
+        let json = serde_json::to_string(&wrapper).unwrap();
+        assert_eq!(json, r#"{"date":"2022-01-01T01:02:03.123Z"}"#);
+        assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), wrapper);
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Amount {
+        #[serde(deserialize_with = "number_as_string")]
+        amount: String,
+    }
+
+    #[test]
+    fn number_as_string_accepts_numbers_and_strings() {
+        let from_number: Amount = serde_json::from_str(r#"{"amount": 42}"#).unwrap();
+        assert_eq!(from_number.amount, "42");
+        let from_string: Amount = serde_json::from_str(r#"{"amount": "3.14"}"#).unwrap();
+        assert_eq!(from_string.amount, "3.14");
+        assert!(serde_json::from_str::<Amount>(r#"{"amount": null}"#).is_err());
+    }
+
+    #[cfg(feature = "arbitrary-precision")]
+    #[test]
+    fn number_as_string_keeps_precision() {
+        let amount: Amount =
+            serde_json::from_str(r#"{"amount": 1234567890.123456789012345678}"#).unwrap();
+        assert_eq!(amount.amount, "1234567890.123456789012345678");
+    }
+
+    #[test]
+    fn booleans() {
+        for (value, expected) in [
+            (serde_json::json!(true), true),
+            (serde_json::json!("TRUE"), true),
+            (serde_json::json!("t"), true),
+            (serde_json::json!(1), true),
+            (serde_json::json!("1"), true),
+            (serde_json::json!(false), false),
+            (serde_json::json!(" False "), false),
+            (serde_json::json!("F"), false),
+            (serde_json::json!(0), false),
+        ] {
+            assert_eq!(bool_from_value(&value), Ok(expected), "{value}");
+        }
+        for value in [
+            serde_json::json!(2),
+            serde_json::json!(-1),
+            serde_json::json!("yes"),
+            serde_json::json!(null),
+        ] {
+            assert!(bool_from_value(&value).is_err(), "{value}");
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuids() {
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "optional_uuid_from_str")]
+            id: Option<uuid::Uuid>,
+        }
+        let parse = |id: serde_json::Value| {
+            serde_json::from_value::<Row>(serde_json::json!({ "id": id })).map(|row| row.id)
+        };
+        let id = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        assert_eq!(
+            parse("67e5504410b1426f9247bb680e5fe0c8".into()).unwrap(),
+            Some(id)
+        );
+        assert_eq!(parse(serde_json::Value::Null).unwrap(), None);
+        assert!(parse("67e55044".into()).is_err());
+    }
+
+    #[test]
+    fn hex_columns() {
+        #[derive(Deserialize, Debug)]
+        struct Row {
+            #[serde(deserialize_with = "address_from_str")]
+            address: [u8; 20],
+            #[serde(deserialize_with = "optional_address_from_str")]
+            owner: Option<[u8; 20]>,
+            #[serde(deserialize_with = "bytes_from_str")]
+            data: Vec<u8>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "address": "0xD8DA6BF26964AF9D7EED9E03E53415D37AA96045",
+            "owner": null,
+            "data": "00ff10"
+        }))
+        .unwrap();
+        assert_eq!(row.address[..2], [0xd8, 0xda]);
+        assert_eq!(row.address[19], 0x45);
+        assert_eq!(row.owner, None);
+        assert_eq!(row.data, [0x00, 0xff, 0x10]);
+
+        for (column, invalid) in [
+            ("address", "0x1234"),
+            ("data", "0x123"),
+            ("data", "0xzz"),
+            ("data", "0x€1"),
+        ] {
+            let mut row = serde_json::json!({
+                "address": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045", "owner": null, "data": "0x"
+            });
+            row[column] = invalid.into();
+            assert!(serde_json::from_value::<Row>(row).is_err(), "{invalid}");
+        }
+    }
+
+    #[cfg(feature = "alloy")]
+    #[test]
+    fn alloy_u256() {
+        use alloy_primitives::U256;
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "alloy::u256_from_str")]
+            amount: U256,
+        }
+        let parse = |amount: serde_json::Value| {
+            serde_json::from_value::<Row>(serde_json::json!({ "amount": amount }))
+                .map(|row| row.amount)
+        };
+        assert_eq!(parse("1000".into()).unwrap(), U256::from(1000u64));
+        assert_eq!(parse("0xFF".into()).unwrap(), U256::from(255u64));
+        assert_eq!(parse(7.into()).unwrap(), U256::from(7u64));
+        assert_eq!(parse(U256::MAX.to_string().into()).unwrap(), U256::MAX);
+        assert!(parse("-1".into()).is_err());
+        assert!(parse("0xzz".into()).is_err());
+        assert!(parse(1.5.into()).is_err());
+    }
+
+    #[cfg(feature = "alloy")]
+    #[test]
+    fn alloy_hex_columns() {
+        use alloy_primitives::{Address, Bytes};
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "alloy::address_from_str")]
+            address: Address,
+            #[serde(deserialize_with = "alloy::optional_bytes_from_str")]
+            data: Option<Bytes>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "address": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045", "data": "0x0102"
+        }))
+        .unwrap();
+        assert_eq!(
+            row.address,
+            "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(row.data, Some(Bytes::from(vec![1, 2])));
+    }
+
+    #[cfg(feature = "primitive-types")]
+    #[test]
+    fn primitive_types_u256() {
+        use ::primitive_types::U256;
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "primitive_types::u256_from_str")]
+            amount: U256,
+        }
+        let parse = |amount: serde_json::Value| {
+            serde_json::from_value::<Row>(serde_json::json!({ "amount": amount }))
+                .map(|row| row.amount)
+        };
+        assert_eq!(parse("1000".into()).unwrap(), U256::from(1000));
+        assert_eq!(parse("0xff".into()).unwrap(), U256::from(255));
+        assert_eq!(parse(U256::MAX.to_string().into()).unwrap(), U256::MAX);
+        assert!(parse("1e3".into()).is_err());
+        assert!(parse(true.into()).is_err());
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn rust_decimals() {
+        use ::rust_decimal::Decimal;
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "rust_decimal::optional_decimal_from_str")]
+            price: Option<Decimal>,
+        }
+        let parse = |price: serde_json::Value| {
+            serde_json::from_value::<Row>(serde_json::json!({ "price": price }))
+                .map(|row| row.price)
+        };
+        assert_eq!(
+            parse("12.340".into()).unwrap(),
+            Some(Decimal::new(12340, 3))
+        );
+        assert_eq!(parse("1E+2".into()).unwrap(), Some(Decimal::from(100)));
+        assert_eq!(parse(5.into()).unwrap(), Some(Decimal::from(5)));
+        assert_eq!(parse(serde_json::Value::Null).unwrap(), None);
+        assert!(parse("abc".into()).is_err());
+        assert!(parse(true.into()).is_err());
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn big_decimals() {
+        use ::bigdecimal::BigDecimal;
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "bigdecimal::decimal_from_str")]
+            amount: BigDecimal,
+        }
+        let parse = |amount: serde_json::Value| {
+            serde_json::from_value::<Row>(serde_json::json!({ "amount": amount }))
+                .map(|row| row.amount)
+        };
+        let wei = "1000000000000000000000000000000.000000000000000001";
+        assert_eq!(parse(wei.into()).unwrap().to_string(), wei);
+        assert_eq!(parse("1.5e3".into()).unwrap(), BigDecimal::from(1500));
+        assert!(parse("1.2.3".into()).is_err());
+    }
+
+    #[test]
+    fn decimal_shifts() {
+        assert_eq!(shift_decimal("1500000000000000000", -18).unwrap(), "1.5");
+        assert_eq!(shift_decimal("1", -3).unwrap(), "0.001");
+        assert_eq!(shift_decimal("-25", -1).unwrap(), "-2.5");
+        assert_eq!(shift_decimal("1e21", -18).unwrap(), "1000");
+        assert_eq!(shift_decimal("0.5", 2).unwrap(), "50");
+        assert_eq!(shift_decimal("-0", 5).unwrap(), "0");
+        assert_eq!(shift_decimal("000120.0", 0).unwrap(), "120");
+        for invalid in ["", ".", "1.2.3", "abc", "1e", "0x10", "--1"] {
+            assert!(shift_decimal(invalid, 0).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn scaled_values() {
+        #[derive(Deserialize, serde::Serialize, Debug, PartialEq)]
+        struct Row {
+            #[serde(with = "Scaled::<18>")]
+            value: f64,
+            #[serde(with = "Scaled::<6>")]
+            fee: Option<f64>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "value": "123456789000000000000", "fee": null
+        }))
+        .unwrap();
+        assert_eq!(
+            row,
+            Row {
+                value: 123.456789,
+                fee: None
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&row).unwrap(),
+            serde_json::json!({"value": "123456789000000000000", "fee": null})
+        );
+        let row: Row = serde_json::from_value(serde_json::json!({"value": 10, "fee": 1})).unwrap();
+        assert_eq!(
+            row,
+            Row {
+                value: 1e-17,
+                fee: Some(1e-6)
+            }
+        );
+        assert!(
+            serde_json::from_value::<Row>(serde_json::json!({"value": null, "fee": 1})).is_err()
+        );
+        assert!(
+            serde_json::from_value::<Row>(serde_json::json!({"value": "x", "fee": 1})).is_err()
+        );
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn scaled_rust_decimals() {
+        use ::rust_decimal::Decimal;
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(with = "Scaled::<18>")]
+            value: Decimal,
+        }
+        let row: Row =
+            serde_json::from_value(serde_json::json!({"value": "1000000000000000001"})).unwrap();
+        assert_eq!(
+            row.value,
+            "1.000000000000000001".parse::<Decimal>().unwrap()
+        );
+    }
+
+    #[test]
+    fn nested_json_columns() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Inner {
+            a: u32,
+        }
+        #[derive(Deserialize, Debug)]
+        struct Row {
+            #[serde(deserialize_with = "json_from_str")]
+            inner: Inner,
+            #[serde(deserialize_with = "json_from_str")]
+            tags: Vec<String>,
+            #[serde(deserialize_with = "optional_json_from_str")]
+            raw: Option<serde_json::Value>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "inner": "{\"a\": 1}", "tags": ["x"], "raw": "[1, null]"
+        }))
+        .unwrap();
+        assert_eq!(row.inner, Inner { a: 1 });
+        assert_eq!(row.tags, ["x"]);
+        assert_eq!(row.raw, Some(serde_json::json!([1, null])));
+
+        let invalid = serde_json::json!({"inner": "{\"a\": ", "tags": "[]", "raw": null});
+        let error = serde_json::from_value::<Row>(invalid).unwrap_err();
+        assert!(error.to_string().contains("EOF"), "{error}");
+    }
+
+    #[test]
+    fn array_columns() {
+        fn parse<T: DeserializeOwned>(column: serde_json::Value) -> Result<Vec<T>, String> {
+            array_from_value(column)
+        }
+        assert_eq!(parse::<u64>(serde_json::json!([1, "2"])).unwrap(), [1, 2]);
+        assert_eq!(parse::<u64>("[1, 2, 3]".into()).unwrap(), [1, 2, 3]);
+        assert_eq!(parse::<f64>("{1.5,2}".into()).unwrap(), [1.5, 2.0]);
+        assert_eq!(parse::<String>("[]".into()).unwrap(), Vec::<String>::new());
+        assert_eq!(
+            parse::<String>(r#"[ETH, "a, b", 0xabc]"#.into()).unwrap(),
+            ["ETH", "a, b", "0xabc"]
+        );
+        assert_eq!(
+            parse::<Vec<u32>>("[[1, 2], [3]]".into()).unwrap(),
+            [vec![1, 2], vec![3]]
+        );
+        assert_eq!(
+            parse::<Option<bool>>("[true, NULL]".into()).unwrap(),
+            [Some(true), None]
+        );
+        assert_eq!(
+            parse::<u64>("[1, x]".into()).unwrap_err(),
+            "array element 1: invalid type: string \"x\", expected u64"
+        );
+        assert!(parse::<u64>("1, 2".into()).is_err());
+        assert!(parse::<u64>("[1, 2]]".into()).is_err());
+        assert!(parse::<u64>(serde_json::json!(1)).is_err());
+    }
+
+    #[test]
+    fn integers_from_strings() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            #[serde(deserialize_with = "u64_from_str")]
+            block: u64,
+            #[serde(deserialize_with = "i64_from_str")]
+            delta: i64,
+            #[serde(deserialize_with = "i128_from_str")]
+            amount: i128,
+            #[serde(deserialize_with = "optional_u64_from_str")]
+            fee: Option<u64>,
+            #[serde(deserialize_with = "optional_i64_from_str")]
+            tip: Option<i64>,
+            #[serde(deserialize_with = "optional_i128_from_str")]
+            refund: Option<i128>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "block": "9007199254740993", "delta": -5, "amount": "100000000000000000000000",
+            "fee": "7", "tip": null, "refund": "-1"
+        }))
+        .unwrap();
+        assert_eq!(
+            row,
+            Row {
+                block: 9007199254740993,
+                delta: -5,
+                amount: 100000000000000000000000,
+                fee: Some(7),
+                tip: None,
+                refund: Some(-1),
+            }
+        );
+
+        for invalid in [
+            serde_json::json!({"block": "-1"}),
+            serde_json::json!({"block": "1.5"}),
+            serde_json::json!({"block": 1.5}),
+            serde_json::json!({"block": true}),
+        ] {
+            let mut row = serde_json::json!({
+                "delta": 0, "amount": 0, "fee": null, "tip": null, "refund": null
+            });
+            row["block"] = invalid["block"].clone();
+            assert!(serde_json::from_value::<Row>(row).is_err());
+        }
+    }
+
+    #[test]
+    fn with_modules_round_trip() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Row {
+            #[serde(with = "f64_str")]
+            price: f64,
+            #[serde(with = "option_f64_str")]
+            volume: Option<f64>,
+            #[serde(with = "vec_f64_str")]
+            ticks: Vec<f64>,
+            #[serde(with = "datetime_str")]
+            day: DateTime<Utc>,
+            #[serde(with = "option_datetime_str")]
+            closed: Option<DateTime<Utc>>,
+            #[serde(with = "vec_datetime_str")]
+            blocks: Vec<DateTime<Utc>>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "price": 3, "volume": "1.5", "ticks": ["1", 2.5],
+            "day": "2024-01-01 00:00:00.000", "closed": null,
+            "blocks": ["2024-01-01T00:00:00.0Z", "2024-01-02 00:00:00"]
+        }))
+        .unwrap();
+        assert_eq!(row.price, 3.0);
+        assert_eq!(row.volume, Some(1.5));
+        assert_eq!(row.ticks, vec![1.0, 2.5]);
+        assert_eq!(row.closed, None);
+        assert_eq!(row.blocks[1], dune_date("2024-01-02 00:00:00").unwrap());
+
+        let json = serde_json::to_value(&row).unwrap();
+        assert_eq!(json["price"], "3");
+        assert_eq!(json["ticks"], serde_json::json!(["1", "2.5"]));
+        assert_eq!(serde_json::from_value::<Row>(json).unwrap(), row);
+
+        let invalid = serde_json::json!({
+            "price": "abc", "volume": null, "ticks": [], "day": "2024-01-01 00:00:00",
+            "closed": null, "blocks": []
+        });
+        assert!(serde_json::from_value::<Row>(invalid).is_err());
+    }
+
+    #[test]
+    fn any_datetimes() {
+        let midnight = date_parse("2022-01-01T00:00:00.0Z").unwrap();
+        for text in [
+            "2022-01-01T00:00:00.000Z",
+            "2022-01-01T00:00:00Z",
+            "2022-01-01T01:00:00+01:00",
+            "2022-01-01 00:00:00.000 UTC",
+            "2022-01-01 00:00:00 UTC",
+            "2022-01-01 00:00:00",
+            "2022-01-01T00:00:00.000",
+            " 2022-01-01 ",
+        ] {
+            assert_eq!(parse_any_datetime(text).unwrap(), midnight, "{text}");
+        }
+        for text in [
+            "",
+            "2022-13-01",
+            "01/01/2022",
+            "2022-01-01 25:00:00",
+            "yesterday",
+        ] {
+            assert!(parse_any_datetime(text).is_err(), "{text}");
+        }
+
+        let day = NaiveDate::from_ymd_opt(2022, 1, 1).unwrap();
+        assert_eq!(parse_any_date("2022-01-01").unwrap(), day);
+        assert_eq!(parse_any_date("2022-01-01 23:59:59.999 UTC").unwrap(), day);
+        assert!(parse_any_date("2022-01").is_err());
+        assert_eq!(
+            parse_any_datetime("2022-01-01 00:00:00.25 UTC")
+                .unwrap()
+                .timestamp_subsec_millis(),
+            250
+        );
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "optional_any_datetime_from_str")]
+            time: Option<DateTime<Utc>>,
+            #[serde(deserialize_with = "optional_date_from_str")]
+            day: Option<NaiveDate>,
+        }
+        let row: Row =
+            serde_json::from_value(serde_json::json!({"time": null, "day": "2022-01-01"})).unwrap();
+        assert_eq!((row.time, row.day), (None, Some(day)));
+        let error = serde_json::from_value::<Row>(serde_json::json!({"time": "soon", "day": null}))
+            .err()
+            .unwrap();
+        assert!(error.to_string().starts_with("\"soon\": "), "{error}");
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_crate_types() {
+        use ::time::{Date, Month, OffsetDateTime};
+
+        let parsed = time::parse_offset_datetime("2022-01-01T01:00:00.5+01:00").unwrap();
+        assert_eq!(parsed.unix_timestamp(), 1_640_995_200);
+        assert_eq!(parsed.millisecond(), 500);
+        assert!(parsed.offset().is_utc());
+        assert_eq!(
+            time::parse_date("2022-03-04 23:00:00 UTC").unwrap(),
+            Date::from_calendar_date(2022, Month::March, 4).unwrap()
+        );
+
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "time::optional_offset_datetime_from_str")]
+            time: Option<OffsetDateTime>,
+            #[serde(deserialize_with = "time::optional_date_from_str")]
+            day: Option<Date>,
+        }
+        let row: Row =
+            serde_json::from_value(serde_json::json!({"time": null, "day": null})).unwrap();
+        assert_eq!((row.time, row.day), (None, None));
+        assert!(
+            serde_json::from_value::<Row>(serde_json::json!({"time": "later", "day": null}))
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn zoned_timestamps() {
+        use self::chrono_tz::{InZone, Zone};
+        use ::chrono_tz::Tz;
+
+        struct Tokyo;
+        impl Zone for Tokyo {
+            const TZ: Tz = Tz::Asia__Tokyo;
+        }
+
+        #[derive(Deserialize, serde::Serialize)]
+        struct Row {
+            #[serde(with = "InZone::<Tokyo>")]
+            time: DateTime<Tz>,
+            #[serde(with = "InZone::<Tokyo>")]
+            closed: Option<DateTime<Tz>>,
+        }
+        let row: Row = serde_json::from_value(serde_json::json!({
+            "time": "2024-01-01T00:00:00.0Z", "closed": "2024-01-01T18:00:00+03:00"
+        }))
+        .unwrap();
+        assert_eq!(row.time.to_rfc3339(), "2024-01-01T09:00:00+09:00");
+        assert_eq!(
+            row.closed.unwrap().to_rfc3339(),
+            "2024-01-02T00:00:00+09:00"
+        );
+        assert_eq!(
+            serde_json::to_value(&row).unwrap(),
+            serde_json::json!({
+                "time": "2024-01-01T09:00:00+09:00", "closed": "2024-01-02T00:00:00+09:00"
+            })
+        );
+        assert!(
+            serde_json::from_value::<Row>(serde_json::json!({"time": null, "closed": null}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn new_dune_date() {
+        let date_str = "2022-05-04 00:00:00.000";
+        assert_eq!(
+            dune_date(date_str).unwrap().to_string(),
+            "2022-05-04 00:00:00 UTC"
+        );
+        assert_eq!(
+            dune_date("2022-05-04 00:00:00.25").unwrap().to_string(),
+            "2022-05-04 00:00:00.250 UTC"
         )
     }
 
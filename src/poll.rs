@@ -0,0 +1,209 @@
+//! Polling strategies used while waiting for an execution to finish.
+//!
+//! [`refresh`](crate::client::DuneClient::refresh) and
+//! [`status_stream`](crate::client::DuneClient::status_stream) ask the client's [`PollStrategy`]
+//! how long to wait before the next status request. The default, [`QueueBackoff`], polls slowly
+//! while an execution sits deep in the queue and tightens as it moves up.
+//!
+//! The waiting itself goes through the client's [`Timer`], so polling works under any async
+//! runtime.
+
+use crate::response::{ExecutionStatus, GetStatusResponse};
+use futures_util::future::BoxFuture;
+use std::time::Duration;
+
+/// Decides how long to wait before the next status poll.
+///
+/// Implement this to plug custom pacing into the client via
+/// [`DuneClient::with_poll_strategy`](crate::client::DuneClient::with_poll_strategy).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::poll::PollStrategy;
+/// use duners::response::GetStatusResponse;
+/// use std::time::Duration;
+///
+/// /// Polls quickly at first, then settles on 10 seconds.
+/// struct EagerThenSlow;
+///
+/// impl PollStrategy for EagerThenSlow {
+///     fn next_delay(&self, _status: &GetStatusResponse, polls: u32) -> Duration {
+///         if polls < 3 {
+///             Duration::from_secs(1)
+///         } else {
+///             Duration::from_secs(10)
+///         }
+///     }
+/// }
+/// ```
+pub trait PollStrategy: Send + Sync {
+    /// Delay before the next poll, given the most recent status and the number of polls made so far.
+    fn next_delay(&self, status: &GetStatusResponse, polls: u32) -> Duration;
+}
+
+/// Polls at a constant interval regardless of the execution state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedInterval(pub Duration);
+
+impl PollStrategy for FixedInterval {
+    fn next_delay(&self, _status: &GetStatusResponse, _polls: u32) -> Duration {
+        self.0
+    }
+}
+
+/// Scales the poll interval with the execution's queue position.
+///
+/// While [`Pending`](ExecutionStatus::Pending), the delay is `base + per_position × queue_position`,
+/// capped at `max`. Once executing, polls happen every `base`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use duners::poll::QueueBackoff;
+/// use duners::DuneClient;
+/// use std::time::Duration;
+///
+/// let client = DuneClient::from_env().with_poll_strategy(QueueBackoff {
+///     base: Duration::from_secs(2),
+///     per_position: Duration::from_millis(500),
+///     max: Duration::from_secs(30),
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueueBackoff {
+    /// Interval used when the execution is running or at the front of the queue.
+    pub base: Duration,
+    /// Extra delay added per position in the queue.
+    pub per_position: Duration,
+    /// Upper bound on the delay.
+    pub max: Duration,
+}
+
+impl Default for QueueBackoff {
+    /// 5 seconds base, 1 extra second per queue position, at most 1 minute.
+    fn default() -> Self {
+        QueueBackoff {
+            base: Duration::from_secs(5),
+            per_position: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+        }
+    }
+}
+
+impl PollStrategy for QueueBackoff {
+    fn next_delay(&self, status: &GetStatusResponse, _polls: u32) -> Duration {
+        let position = match status.state {
+            ExecutionStatus::Pending => status.queue_position.unwrap_or(0),
+            _ => 0,
+        };
+        (self.base + self.per_position * position).min(self.max)
+    }
+}
+
+/// Sleeps on behalf of the client: between status polls and before retries.
+///
+/// The default, [`TokioTimer`], needs a tokio runtime. To poll under another runtime
+/// (async-std, smol, …), plug in its sleep via
+/// [`DuneClient::with_timer`](crate::client::DuneClient::with_timer). Note that reqwest still
+/// drives its connections through tokio's I/O, so HTTP requests need a tokio reactor as well,
+/// e.g. through the `async-compat` crate.
+///
+/// With smol, for instance, `sleep` would return
+/// `Box::pin(async move { smol::Timer::after(duration).await; })`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use duners::poll::Timer;
+/// use duners::DuneClient;
+/// use futures_util::future::BoxFuture;
+/// use std::time::Duration;
+///
+/// /// Never waits, e.g. for tests against a mock server.
+/// struct NoWait;
+///
+/// impl Timer for NoWait {
+///     fn sleep(&self, _duration: Duration) -> BoxFuture<'static, ()> {
+///         Box::pin(std::future::ready(()))
+///     }
+/// }
+///
+/// let client = DuneClient::from_env().with_timer(NoWait);
+/// ```
+pub trait Timer: Send + Sync {
+    /// A future completing after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// Sleeps with [`tokio::time::sleep`] (the default).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokioTimer;
+
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::ExecutionTimes;
+
+    fn status(state: ExecutionStatus, queue_position: Option<u32>) -> GetStatusResponse {
+        GetStatusResponse {
+            execution_id: "jerb".to_string(),
+            query_id: 1,
+            state,
+            times: ExecutionTimes {
+                submitted_at: Default::default(),
+                expires_at: None,
+                execution_started_at: None,
+                execution_ended_at: None,
+                cancelled_at: None,
+            },
+            queue_position,
+            result_metadata: None,
+            execution_cost_credits: None,
+            error: None,
+            rate_limit: None,
+            request_id: None,
+        }
+    }
+
+    #[test]
+    fn fixed_interval() {
+        let strategy = FixedInterval(Duration::from_secs(3));
+        let pending = status(ExecutionStatus::Pending, Some(100));
+        assert_eq!(strategy.next_delay(&pending, 0), Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn tokio_timer() {
+        let started = std::time::Instant::now();
+        TokioTimer.sleep(Duration::from_millis(20)).await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn queue_backoff() {
+        let strategy = QueueBackoff::default();
+        assert_eq!(
+            strategy.next_delay(&status(ExecutionStatus::Pending, Some(10)), 0),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            strategy.next_delay(&status(ExecutionStatus::Pending, Some(1000)), 0),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            strategy.next_delay(&status(ExecutionStatus::Pending, None), 0),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            strategy.next_delay(&status(ExecutionStatus::Executing, Some(10)), 0),
+            Duration::from_secs(5)
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Everything needed for typical use of the crate, in a single import.
+//!
+//! Brings in the client, its configuration types, query parameters and IDs, the response types
+//! most code matches on, and the [`parse_utils`](crate::parse_utils) deserializers used on row
+//! structs, together with the `chrono` types they produce.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use duners::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct Row {
+//!     #[serde(deserialize_with = "datetime_from_str")]
+//!     block_time: DateTime<Utc>,
+//!     #[serde(deserialize_with = "f64_from_str")]
+//!     amount: f64,
+//! }
+//!
+//! # async fn run() -> Result<(), DuneRequestError> {
+//! let client = DuneClient::from_env().with_poll_strategy(FixedInterval(std::time::Duration::from_secs(2)));
+//! let query_id: QueryId = "971694".parse()?;
+//! let results = client
+//!     .refresh::<Row>(query_id.0, Some(vec![Parameter::number("Days", "7")]), None)
+//!     .await?;
+//! println!("{:?}", results.get_rows());
+//! # Ok(()) }
+//! ```
+
+pub use crate::client::{DuneClient, DuneClientBuilder, Performance};
+pub use crate::error::{
+    DuneRequestError, ErrorDetails, HttpContext, InvalidParameter, TransportError,
+};
+pub use crate::join::JoinKind;
+pub use crate::mapping::ColumnMapping;
+pub use crate::metrics::{ExecutionReport, SlaThresholds, SlaTracker};
+pub use crate::parameters::{Parameter, ParameterValue, ToParameters};
+pub use crate::parse_utils::{
+    address_from_str, any_datetime_from_str, array_from_str, bool_from_str, bytes_from_str,
+    date_from_str, datetime_from_str, datetime_to_str, f64_from_str, i128_from_str, i64_from_str,
+    json_from_str, number_as_string, optional_address_from_str, optional_any_datetime_from_str,
+    optional_array_from_str, optional_bool_from_str, optional_bytes_from_str,
+    optional_date_from_str, optional_datetime_from_str, optional_datetime_to_str,
+    optional_i128_from_str, optional_i64_from_str, optional_json_from_str, optional_u64_from_str,
+    u64_from_str, Scaled,
+};
+pub use crate::poll::{FixedInterval, PollStrategy, QueueBackoff, Timer, TokioTimer};
+pub use crate::query::{QueryId, QueryRef, UpdateQuery};
+pub use crate::response::{
+    DuneColumnType, DynamicRow, ExecutionError, ExecutionStatus, GetResultResponse,
+    GetStatusResponse, Query, RateLimitStatus,
+};
+pub use crate::retry::{ExponentialBackoff, RetryPolicy};
+pub use crate::rowset::RowSet;
+pub use crate::schema::{FieldKind, FieldSpec, RowSchema};
+pub use crate::store::{ExecutionStore, FileExecutionStore};
+pub use crate::sync::QuerySync;
+pub use crate::table::{ChunkedInsert, ColumnDef, DuneType, InsertFormat};
+pub use crate::throttle::RateLimiter;
+pub use chrono::{DateTime, NaiveDate, Utc};
@@ -0,0 +1,395 @@
+//! Query identifiers and client-side validation.
+//!
+//! Dune numbers saved queries from 1; ID `0` never refers to a saved query. [`QueryId`] parses and
+//! validates IDs coming from configuration so typos surface before any request is sent, and
+//! [`DuneRequestError::QueryNotFound`] reports IDs that are invalid or unknown to Dune.
+//! [`QueryRef`] additionally accepts the query URLs analysts copy from the Dune website.
+//! [`UpdateQuery`] describes a partial update for
+//! [`DuneClient::update_query`](crate::client::DuneClient::update_query).
+
+use crate::error::DuneRequestError;
+use crate::parameters::Parameter;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// ID of a saved Dune query.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::query::QueryId;
+/// use duners::DuneRequestError;
+///
+/// let id: QueryId = " 971694".parse().unwrap();
+/// assert_eq!(id, QueryId(971694));
+/// assert_eq!("0".parse::<QueryId>(), Err(DuneRequestError::QueryNotFound(QueryId(0))));
+/// assert!(matches!(
+///     "97169x".parse::<QueryId>(),
+///     Err(DuneRequestError::InvalidQueryId(_))
+/// ));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct QueryId(pub u32);
+
+impl QueryId {
+    /// Returns `self` if it can refer to a saved query (i.e. is non-zero).
+    pub fn validate(self) -> Result<Self, DuneRequestError> {
+        match self.0 {
+            0 => Err(DuneRequestError::QueryNotFound(self)),
+            _ => Ok(self),
+        }
+    }
+}
+
+impl fmt::Display for QueryId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u32> for QueryId {
+    fn from(value: u32) -> Self {
+        QueryId(value)
+    }
+}
+
+impl From<QueryId> for u32 {
+    fn from(value: QueryId) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for QueryId {
+    type Err = DuneRequestError;
+
+    /// Parses a decimal query ID, rejecting non-numeric input, values beyond `u32` and `0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        trimmed
+            .parse::<u32>()
+            .map_err(|err| DuneRequestError::InvalidQueryId(format!("{trimmed:?}: {err}")))
+            .and_then(|id| QueryId(id).validate())
+    }
+}
+
+/// A reference to a saved query: its ID, or a Dune URL containing it.
+///
+/// Every [`DuneClient`](crate::client::DuneClient) method taking a query accepts
+/// `impl Into<QueryRef>`, so IDs (`971694`), [`QueryId`]s and URLs such as
+/// `https://dune.com/queries/971694/1680442` or `https://dune.com/embeds/971694/1680442` can be
+/// passed interchangeably. Strings are only resolved when used, so an invalid one surfaces as
+/// [`DuneRequestError::InvalidQueryId`] from the call.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::query::{QueryId, QueryRef};
+///
+/// let from_url = QueryRef::from("https://dune.com/queries/971694/1680442?d=1");
+/// assert_eq!(from_url.query_id(), Ok(QueryId(971694)));
+/// assert_eq!(QueryRef::from(971694).query_id(), Ok(QueryId(971694)));
+/// assert!(QueryRef::from("https://dune.com/bh2smith/my-dashboard").query_id().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryRef(Reference);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Reference {
+    Id(u32),
+    Text(String),
+}
+
+impl QueryRef {
+    /// The referenced query's ID, validated as by [`QueryId::validate`].
+    pub fn query_id(&self) -> Result<QueryId, DuneRequestError> {
+        match &self.0 {
+            Reference::Id(id) => QueryId(*id).validate(),
+            Reference::Text(text) => parse_reference(text),
+        }
+    }
+}
+
+/// Parses a bare ID or a `…/queries/<id>…` / `…/embeds/<id>…` URL.
+fn parse_reference(text: &str) -> Result<QueryId, DuneRequestError> {
+    let text = text.trim();
+    if text
+        .bytes()
+        .all(|b| b.is_ascii_digit() || b == b'-' || b == b'+')
+    {
+        return text.parse();
+    }
+    let path = text.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.split('/');
+    while let Some(segment) = segments.next() {
+        if segment == "queries" || segment == "embeds" {
+            if let Some(id) = segments.next() {
+                return id.parse();
+            }
+        }
+    }
+    Err(DuneRequestError::InvalidQueryId(format!(
+        "{text:?}: neither a query ID nor a Dune query URL"
+    )))
+}
+
+impl From<u32> for QueryRef {
+    fn from(value: u32) -> Self {
+        QueryRef(Reference::Id(value))
+    }
+}
+
+/// Integer literals default to `i32`; negative values fail to resolve.
+impl From<i32> for QueryRef {
+    fn from(value: i32) -> Self {
+        match u32::try_from(value) {
+            Ok(id) => QueryRef(Reference::Id(id)),
+            Err(_) => QueryRef(Reference::Text(value.to_string())),
+        }
+    }
+}
+
+/// Values beyond `u32::MAX` fail to resolve.
+impl From<u64> for QueryRef {
+    fn from(value: u64) -> Self {
+        match u32::try_from(value) {
+            Ok(id) => QueryRef(Reference::Id(id)),
+            Err(_) => QueryRef(Reference::Text(value.to_string())),
+        }
+    }
+}
+
+impl From<QueryId> for QueryRef {
+    fn from(value: QueryId) -> Self {
+        QueryRef(Reference::Id(value.0))
+    }
+}
+
+impl From<&str> for QueryRef {
+    fn from(value: &str) -> Self {
+        QueryRef(Reference::Text(value.to_string()))
+    }
+}
+
+impl From<String> for QueryRef {
+    fn from(value: String) -> Self {
+        QueryRef(Reference::Text(value))
+    }
+}
+
+impl From<&String> for QueryRef {
+    fn from(value: &String) -> Self {
+        QueryRef(Reference::Text(value.clone()))
+    }
+}
+
+/// Changes to apply to a saved query. Fields left as `None` keep their current value.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::query::UpdateQuery;
+///
+/// let update = UpdateQuery {
+///     query_sql: Some("SELECT number FROM ethereum.blocks ORDER BY number DESC LIMIT 10".into()),
+///     tags: Some(vec!["deployed".into()]),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Default)]
+pub struct UpdateQuery {
+    /// New SQL text.
+    pub query_sql: Option<String>,
+    /// New title.
+    pub name: Option<String>,
+    /// New description.
+    pub description: Option<String>,
+    /// Replacement tag list.
+    pub tags: Option<Vec<String>>,
+    /// Replacement parameter definitions (the values become the defaults).
+    pub parameters: Option<Vec<Parameter>>,
+}
+
+impl UpdateQuery {
+    /// Whether the update changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.query_sql.is_none()
+            && self.name.is_none()
+            && self.description.is_none()
+            && self.tags.is_none()
+            && self.parameters.is_none()
+    }
+
+    /// Request body containing only the fields being changed.
+    pub(crate) fn body(&self) -> Value {
+        let mut body = Map::new();
+        if let Some(query_sql) = &self.query_sql {
+            body.insert("query_sql".into(), query_sql.clone().into());
+        }
+        if let Some(name) = &self.name {
+            body.insert("name".into(), name.clone().into());
+        }
+        if let Some(description) = &self.description {
+            body.insert("description".into(), description.clone().into());
+        }
+        if let Some(tags) = &self.tags {
+            body.insert("tags".into(), tags.clone().into());
+        }
+        if let Some(parameters) = &self.parameters {
+            let definitions = parameters.iter().map(Parameter::definition).collect();
+            body.insert("parameters".into(), Value::Array(definitions));
+        }
+        Value::Object(body)
+    }
+}
+
+/// Rewrites errors of the `query/{query_id}` routes saying the query does not exist (404, or 400
+/// with Dune's "query not found" / "invalid query id" message) into
+/// [`DuneRequestError::QueryNotFound`].
+pub(crate) fn not_found(err: DuneRequestError, query_id: u32) -> DuneRequestError {
+    let on_query_route = err
+        .context()
+        .is_some_and(|context| is_query_route(&context.url, query_id));
+    match err {
+        DuneRequestError::NotFound { .. } if on_query_route => {
+            DuneRequestError::QueryNotFound(QueryId(query_id))
+        }
+        DuneRequestError::InvalidRequest { ref message, .. }
+            if on_query_route && is_not_found(message) =>
+        {
+            DuneRequestError::QueryNotFound(QueryId(query_id))
+        }
+        other => other,
+    }
+}
+
+fn is_not_found(msg: &str) -> bool {
+    let msg = msg.to_lowercase();
+    msg.contains("query not found") || msg.contains("invalid query id")
+}
+
+/// Whether `url` is `query/{query_id}` or one of its sub-routes (`query/{query_id}/execute`, …).
+fn is_query_route(url: &str, query_id: u32) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let id = query_id.to_string();
+    let segments: Vec<&str> = url.path_segments().into_iter().flatten().collect();
+    segments
+        .windows(2)
+        .any(|pair| pair[0] == "query" && pair[1] == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ErrorDetails, HttpContext};
+
+    #[test]
+    fn parse_query_id() {
+        assert_eq!("1215383".parse::<QueryId>(), Ok(QueryId(1215383)));
+        assert_eq!(
+            "0".parse::<QueryId>(),
+            Err(DuneRequestError::QueryNotFound(QueryId(0)))
+        );
+        assert_eq!(
+            "4294967296".parse::<QueryId>(),
+            Err(DuneRequestError::InvalidQueryId(
+                "\"4294967296\": number too large to fit in target type".to_string()
+            ))
+        );
+        assert!("-1".parse::<QueryId>().is_err());
+    }
+
+    #[test]
+    fn query_references() {
+        for url in [
+            "https://dune.com/queries/971694",
+            "https://dune.com/queries/971694/1680442",
+            "dune.com/queries/971694?d=7",
+            "https://dune.com/embeds/971694/1680442/dc1a0ae4-8b1c-4b35-a3b4-e8d7ae61b8f1",
+            " 971694 ",
+        ] {
+            assert_eq!(QueryRef::from(url).query_id(), Ok(QueryId(971694)), "{url}");
+        }
+        assert_eq!(
+            QueryRef::from(0u32).query_id(),
+            Err(DuneRequestError::QueryNotFound(QueryId(0)))
+        );
+        assert!(QueryRef::from(-1).query_id().is_err());
+        assert!(QueryRef::from(u64::MAX).query_id().is_err());
+        assert!(QueryRef::from("https://dune.com/queries/abc")
+            .query_id()
+            .is_err());
+        assert!(QueryRef::from("https://example.com").query_id().is_err());
+    }
+
+    #[test]
+    fn update_body_is_partial() {
+        let update = UpdateQuery {
+            name: Some("Renamed".to_string()),
+            tags: Some(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(
+            update.body(),
+            serde_json::json!({"name": "Renamed", "tags": []})
+        );
+        assert!(UpdateQuery::default().is_empty());
+        assert!(!update.is_empty());
+    }
+
+    #[test]
+    fn maps_not_found_responses() {
+        let context = |status: u16, url: &str| HttpContext {
+            status: Some(status),
+            url: url.to_string(),
+            request_id: None,
+        };
+        let missing = |url: &str| DuneRequestError::NotFound {
+            message: "Not found".to_string(),
+            context: context(404, url),
+        };
+        assert_eq!(
+            not_found(missing("https://api.dune.com/api/v1/query/7"), 7),
+            DuneRequestError::QueryNotFound(QueryId(7))
+        );
+        assert_eq!(
+            not_found(missing("https://api.dune.com/api/v1/query/7/execute"), 7),
+            DuneRequestError::QueryNotFound(QueryId(7))
+        );
+        // Other routes, or another query's, keep their error.
+        let elsewhere = missing("https://api.dune.com/api/v1/execution/7/status");
+        assert_eq!(not_found(elsewhere.clone(), 7), elsewhere);
+        let other_query = missing("https://api.dune.com/api/v1/query/70");
+        assert_eq!(not_found(other_query.clone(), 7), other_query);
+
+        let invalid = |message: &str, details: ErrorDetails| DuneRequestError::InvalidRequest {
+            message: message.to_string(),
+            details: Box::new(details),
+            context: context(400, "https://api.dune.com/api/v1/query/7/execute"),
+        };
+        assert_eq!(
+            not_found(invalid("Query not found", ErrorDetails::default()), 7),
+            DuneRequestError::QueryNotFound(QueryId(7))
+        );
+        assert_eq!(
+            not_found(invalid("Invalid query ID", ErrorDetails::default()), 7),
+            DuneRequestError::QueryNotFound(QueryId(7))
+        );
+        // Bad SQL, bad parameter types or rejected parameters are the caller's to fix, not a
+        // missing query.
+        let bad_request = invalid("Bad request", ErrorDetails::default());
+        assert_eq!(not_found(bad_request.clone(), 7), bad_request);
+        let parameters = invalid(
+            "Bad request",
+            ErrorDetails {
+                missing_parameters: vec!["Start".to_string()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(not_found(parameters.clone(), 7), parameters);
+    }
+}
@@ -4,37 +4,202 @@
 //! when calling [`refresh`](crate::client::DuneClient::refresh) or [`get_results`](crate::client::DuneClient::get_results).
 //! The generic `T` is your row type (a struct with `#[derive(Deserialize)]` matching the query columns).
 
-use crate::parse_utils::{datetime_from_str, optional_datetime_from_str};
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
-use serde_with::DeserializeFromStr;
+use crate::error::{deserialize_row, DuneRequestError};
+use crate::parse_utils::{
+    datetime_from_str, datetime_to_str, optional_datetime_from_str, optional_datetime_to_str,
+};
+use crate::query::QueryId;
+use crate::schema::{self, RowSchema};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 /// Returned from [`DuneClient::execute_query`](crate::client::DuneClient::execute_query). Contains the execution ID to poll or fetch results.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ExecutionResponse {
     /// Use this ID with [`get_status`](crate::client::DuneClient::get_status) and [`get_results`](crate::client::DuneClient::get_results).
     pub execution_id: String,
     /// Current state of the execution (e.g. [`ExecutionStatus::Pending`]).
     pub state: ExecutionStatus,
+    /// Rate-limit headers of the response, if Dune sent any.
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimitStatus>,
+    /// ID of the request (`X-Request-Id` or similar), for support tickets.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+/// Returned from [`DuneClient::create_query`](crate::client::DuneClient::create_query).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CreateQueryResponse {
+    /// ID of the newly created query; use it to execute or update the query.
+    pub query_id: QueryId,
+}
+
+/// Returned from [`DuneClient::update_query`](crate::client::DuneClient::update_query) and the
+/// query lifecycle methods ([`archive_query`](crate::client::DuneClient::archive_query),
+/// [`make_private`](crate::client::DuneClient::make_private), …).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UpdateQueryResponse {
+    /// ID of the updated query.
+    pub query_id: u32,
+}
+
+/// A query definition, returned from [`DuneClient::get_query`](crate::client::DuneClient::get_query).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Query {
+    /// ID of the query.
+    pub query_id: u32,
+    /// Query title.
+    pub name: String,
+    /// Query description (may be empty).
+    #[serde(default)]
+    pub description: String,
+    /// Tags attached to the query.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Version number, incremented on every saved change.
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// Parameters the query accepts, with their default values.
+    #[serde(default)]
+    pub parameters: Vec<QueryParameter>,
+    /// Query engine the SQL runs on (e.g. `"v2 Dune SQL"`).
+    #[serde(default)]
+    pub query_engine: Option<String>,
+    /// The query's SQL text.
+    pub query_sql: String,
+    /// Whether the query is only visible to its owner.
+    #[serde(default)]
+    pub is_private: bool,
+    /// Whether the query has been archived.
+    #[serde(default)]
+    pub is_archived: bool,
+    /// Whether the query has never been saved.
+    #[serde(default)]
+    pub is_unsaved: bool,
+    /// User or team owning the query.
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+/// A parameter as declared in a [`Query`] definition.
+///
+/// Serializes to the definition format accepted when creating a query.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct QueryParameter {
+    /// Parameter name, referenced as `{{key}}` in the SQL.
+    pub key: String,
+    /// Default value.
+    pub value: String,
+    /// Parameter type: `"text"`, `"number"`, `"datetime"` or `"enum"`.
+    #[serde(rename = "type")]
+    pub parameter_type: String,
+    /// Allowed values of an `"enum"` parameter.
+    #[serde(
+        rename = "enumOptions",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enum_options: Option<Vec<String>>,
+}
+
+/// Returned from [`DuneClient::upload_csv`](crate::client::DuneClient::upload_csv).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UploadCsvResponse {
+    /// Whether the upload was accepted.
+    pub success: bool,
+    /// Namespace (user or team name) the table was created in.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Name of the table, as referenced in queries after `dune.<namespace>.`.
+    #[serde(default)]
+    pub table_name: Option<String>,
+    /// Fully qualified name to use in queries (e.g. `dune.my_team.dataset_labels`).
+    #[serde(default)]
+    pub full_name: Option<String>,
+}
+
+/// Returned from [`DuneClient::create_table`](crate::client::DuneClient::create_table).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CreateTableResponse {
+    /// Namespace the table lives in.
+    pub namespace: String,
+    /// Name of the table.
+    pub table_name: String,
+    /// Fully qualified name to use in queries (e.g. `dune.my_team.labels`).
+    pub full_name: String,
+    /// A query selecting from the new table.
+    #[serde(default)]
+    pub example_query: Option<String>,
+    /// Whether a table with this name already existed (in which case it was left untouched).
+    #[serde(default)]
+    pub already_existed: bool,
+    /// Informational message from Dune.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Returned from [`DuneClient::insert_rows`](crate::client::DuneClient::insert_rows).
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct InsertTableResponse {
+    /// Number of rows added to the table.
+    pub rows_written: u64,
+    /// Size of the data written.
+    #[serde(default)]
+    pub bytes_written: u64,
+}
+
+/// Returned from [`DuneClient::clear_table`](crate::client::DuneClient::clear_table).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ClearTableResponse {
+    /// Confirmation message from Dune.
+    pub message: String,
+}
+
+/// Returned from [`DuneClient::delete_table`](crate::client::DuneClient::delete_table).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DeleteTableResponse {
+    /// Confirmation message from Dune.
+    pub message: String,
 }
 
 /// Represents all possible states of query execution.
 /// Most states are self-explanatory.
 /// Failure can occur if query takes too long (30 minutes) to execute.
 /// Pending state also comes along with a "queue position"
-#[derive(DeserializeFromStr, Debug, PartialEq)]
+#[derive(DeserializeFromStr, SerializeDisplay, Debug, Clone, PartialEq, Eq)]
 pub enum ExecutionStatus {
     /// Query finished successfully; results are available.
     Complete,
+    /// Query finished, but its results were truncated (e.g. because they exceeded the result
+    /// size limit); [`GetResultResponse::partial`] is set on its results.
+    PartiallyComplete,
+    /// The execution finished, but its results have expired on Dune's servers and must be
+    /// re-executed.
+    Expired,
     /// Query is currently running.
     Executing,
     /// Query is queued; check `queue_position` on [`GetStatusResponse`].
     Pending,
     /// Execution was cancelled (e.g. via [`cancel_execution`](crate::client::DuneClient::cancel_execution)).
     Cancelled,
-    /// Execution failed (e.g. timeout after 30 minutes).
+    /// Execution failed (e.g. an SQL error, or a timeout after 30 minutes); the status carries
+    /// the [`ExecutionError`].
     Failed,
+    /// A state this version of the crate does not know yet, as sent by Dune. It is treated as
+    /// not terminal, so waiting continues until a known state is reached; after 20 consecutive
+    /// polls in unknown states, [`refresh`](crate::client::DuneClient::refresh) gives up with
+    /// [`PollFailed`](crate::DuneRequestError::PollFailed).
+    Unknown(String),
 }
 
 impl FromStr for ExecutionStatus {
@@ -43,17 +208,37 @@ impl FromStr for ExecutionStatus {
     fn from_str(input: &str) -> Result<ExecutionStatus, Self::Err> {
         match input {
             "QUERY_STATE_COMPLETED" => Ok(ExecutionStatus::Complete),
+            "QUERY_STATE_COMPLETED_PARTIAL" => Ok(ExecutionStatus::PartiallyComplete),
+            "QUERY_STATE_EXPIRED" => Ok(ExecutionStatus::Expired),
             "QUERY_STATE_EXECUTING" => Ok(ExecutionStatus::Executing),
             "QUERY_STATE_PENDING" => Ok(ExecutionStatus::Pending),
             "QUERY_STATE_CANCELLED" => Ok(ExecutionStatus::Cancelled),
             "QUERY_STATE_FAILED" => Ok(ExecutionStatus::Failed),
-            other => Err(format!("Parse Error {other}")),
+            other => Ok(ExecutionStatus::Unknown(other.to_string())),
         }
     }
 }
 
+impl std::fmt::Display for ExecutionStatus {
+    /// The state as named by the API, e.g. `QUERY_STATE_COMPLETED`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            ExecutionStatus::Complete => "QUERY_STATE_COMPLETED",
+            ExecutionStatus::PartiallyComplete => "QUERY_STATE_COMPLETED_PARTIAL",
+            ExecutionStatus::Expired => "QUERY_STATE_EXPIRED",
+            ExecutionStatus::Executing => "QUERY_STATE_EXECUTING",
+            ExecutionStatus::Pending => "QUERY_STATE_PENDING",
+            ExecutionStatus::Cancelled => "QUERY_STATE_CANCELLED",
+            ExecutionStatus::Failed => "QUERY_STATE_FAILED",
+            ExecutionStatus::Unknown(state) => state,
+        };
+        f.write_str(state)
+    }
+}
+
 impl ExecutionStatus {
-    /// Returns `true` when execution will not change state again (complete, cancelled, or failed).
+    /// Returns `true` when execution will not change state again (complete, partially complete,
+    /// expired, cancelled, or failed).
     ///
     /// # Example
     ///
@@ -66,31 +251,441 @@ impl ExecutionStatus {
     pub fn is_terminal(&self) -> bool {
         match self {
             ExecutionStatus::Complete => true,
+            ExecutionStatus::PartiallyComplete => true,
+            ExecutionStatus::Expired => true,
             ExecutionStatus::Cancelled => true,
             ExecutionStatus::Failed => true,
             ExecutionStatus::Executing => false,
             ExecutionStatus::Pending => false,
+            ExecutionStatus::Unknown(_) => false,
+        }
+    }
+}
+
+/// Type of a result column, parsed from the Dune SQL type names in
+/// [`ResultMetaData::column_types`] (e.g. `varchar`, `double`, `timestamp(3) with time zone`).
+///
+/// Type names are matched case-insensitively; names this version of the crate does not know are
+/// kept verbatim in [`DuneColumnType::Other`].
+///
+/// # Example
+///
+/// ```rust
+/// use duners::response::DuneColumnType;
+///
+/// let column_type: DuneColumnType = "array(timestamp(3) with time zone)".parse().unwrap();
+/// assert_eq!(column_type.rust_type(), "Vec<chrono::DateTime<chrono::Utc>>");
+/// assert_eq!(column_type.to_string(), "array(timestamp(3) with time zone)");
+/// ```
+#[derive(DeserializeFromStr, SerializeDisplay, Debug, Clone, PartialEq, Eq)]
+pub enum DuneColumnType {
+    /// Variable-length string (`varchar`, `varchar(n)`).
+    Varchar,
+    /// `true` / `false`.
+    Boolean,
+    /// 8-bit signed integer.
+    Tinyint,
+    /// 16-bit signed integer.
+    Smallint,
+    /// 32-bit signed integer (`integer`, `int`).
+    Integer,
+    /// 64-bit signed integer.
+    Bigint,
+    /// 32-bit floating point number.
+    Real,
+    /// 64-bit floating point number.
+    Double,
+    /// Fixed-point number with `precision` digits, `scale` of them after the decimal point.
+    Decimal {
+        /// Total number of digits.
+        precision: u32,
+        /// Digits after the decimal point.
+        scale: u32,
+    },
+    /// 256-bit unsigned integer, e.g. token amounts.
+    Uint256,
+    /// 256-bit signed integer.
+    Int256,
+    /// Raw bytes, e.g. addresses and hashes (`0x…` strings in results).
+    Varbinary,
+    /// Calendar date.
+    Date,
+    /// Date and time, with the fractional-second `precision` when reported.
+    Timestamp {
+        /// Digits of fractional seconds, e.g. `3` for `timestamp(3)`.
+        precision: Option<u32>,
+        /// Whether the type is `… with time zone`.
+        with_time_zone: bool,
+    },
+    /// JSON document.
+    Json,
+    /// Array of values of the inner type.
+    Array(Box<DuneColumnType>),
+    /// Map from keys of the first type to values of the second.
+    Map(Box<DuneColumnType>, Box<DuneColumnType>),
+    /// A type this version of the crate does not know (e.g. `row(…)`), as sent by Dune.
+    Other(String),
+}
+
+impl FromStr for DuneColumnType {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<DuneColumnType, Self::Err> {
+        let normalized = input.trim().to_lowercase();
+        Ok(parse_column_type(&normalized)
+            .unwrap_or_else(|| DuneColumnType::Other(input.to_string())))
+    }
+}
+
+/// Parses a trimmed, lower-case type name; `None` for unknown types.
+fn parse_column_type(name: &str) -> Option<DuneColumnType> {
+    let column_type = match name {
+        "varchar" => DuneColumnType::Varchar,
+        "boolean" => DuneColumnType::Boolean,
+        "tinyint" => DuneColumnType::Tinyint,
+        "smallint" => DuneColumnType::Smallint,
+        "integer" | "int" => DuneColumnType::Integer,
+        "bigint" => DuneColumnType::Bigint,
+        "real" => DuneColumnType::Real,
+        "double" => DuneColumnType::Double,
+        "uint256" => DuneColumnType::Uint256,
+        "int256" => DuneColumnType::Int256,
+        "varbinary" => DuneColumnType::Varbinary,
+        "date" => DuneColumnType::Date,
+        "json" => DuneColumnType::Json,
+        _ => {
+            if arguments(name, "varchar").is_some() {
+                DuneColumnType::Varchar
+            } else if let Some(inner) = arguments(name, "array") {
+                DuneColumnType::Array(Box::new(inner.trim().parse().ok()?))
+            } else if let Some(inner) = arguments(name, "map") {
+                let (key, value) = split_top_level(inner)?;
+                DuneColumnType::Map(
+                    Box::new(key.trim().parse().ok()?),
+                    Box::new(value.trim().parse().ok()?),
+                )
+            } else if let Some(inner) = arguments(name, "decimal") {
+                let (precision, scale) = inner.split_once(',')?;
+                DuneColumnType::Decimal {
+                    precision: precision.trim().parse().ok()?,
+                    scale: scale.trim().parse().ok()?,
+                }
+            } else {
+                let rest = name.strip_prefix("timestamp")?;
+                let (precision, rest) = match rest.strip_prefix('(') {
+                    Some(rest) => {
+                        let (precision, rest) = rest.split_once(')')?;
+                        (Some(precision.trim().parse().ok()?), rest)
+                    }
+                    None => (None, rest),
+                };
+                let with_time_zone = match rest.trim() {
+                    "" | "without time zone" => false,
+                    "with time zone" => true,
+                    _ => return None,
+                };
+                DuneColumnType::Timestamp {
+                    precision,
+                    with_time_zone,
+                }
+            }
+        }
+    };
+    Some(column_type)
+}
+
+/// The text between the parentheses of `name(…)`.
+fn arguments<'a>(column_type: &'a str, name: &str) -> Option<&'a str> {
+    column_type
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Splits `a, b` at the first comma outside of parentheses.
+fn split_top_level(arguments: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    for (position, character) in arguments.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => return Some((&arguments[..position], &arguments[position + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl std::fmt::Display for DuneColumnType {
+    /// The Dune SQL name of the type, e.g. `timestamp(3) with time zone`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuneColumnType::Varchar => f.write_str("varchar"),
+            DuneColumnType::Boolean => f.write_str("boolean"),
+            DuneColumnType::Tinyint => f.write_str("tinyint"),
+            DuneColumnType::Smallint => f.write_str("smallint"),
+            DuneColumnType::Integer => f.write_str("integer"),
+            DuneColumnType::Bigint => f.write_str("bigint"),
+            DuneColumnType::Real => f.write_str("real"),
+            DuneColumnType::Double => f.write_str("double"),
+            DuneColumnType::Decimal { precision, scale } => {
+                write!(f, "decimal({precision},{scale})")
+            }
+            DuneColumnType::Uint256 => f.write_str("uint256"),
+            DuneColumnType::Int256 => f.write_str("int256"),
+            DuneColumnType::Varbinary => f.write_str("varbinary"),
+            DuneColumnType::Date => f.write_str("date"),
+            DuneColumnType::Timestamp {
+                precision,
+                with_time_zone,
+            } => {
+                f.write_str("timestamp")?;
+                if let Some(precision) = precision {
+                    write!(f, "({precision})")?;
+                }
+                if *with_time_zone {
+                    f.write_str(" with time zone")?;
+                }
+                Ok(())
+            }
+            DuneColumnType::Json => f.write_str("json"),
+            DuneColumnType::Array(inner) => write!(f, "array({inner})"),
+            DuneColumnType::Map(key, value) => write!(f, "map({key}, {value})"),
+            DuneColumnType::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+impl DuneColumnType {
+    /// Suggested Rust type for values of this column, e.g. `f64` for `double`.
+    ///
+    /// Paths are fully qualified (`chrono::DateTime<chrono::Utc>`, `serde_json::Value`), so the
+    /// name can be pasted into generated code as is. Values that do not fit a primitive type
+    /// (`uint256`, `int256`, `varbinary`) are suggested as `String`, `decimal` as `f64`, and
+    /// unknown types as `serde_json::Value`. Timestamps need
+    /// [`datetime_from_str`](crate::parse_utils::datetime_from_str) to deserialize. Any column
+    /// may contain `null`, so wrap the type in `Option` where that can happen.
+    pub fn rust_type(&self) -> String {
+        match self {
+            DuneColumnType::Varchar => "String".to_string(),
+            DuneColumnType::Boolean => "bool".to_string(),
+            DuneColumnType::Tinyint => "i8".to_string(),
+            DuneColumnType::Smallint => "i16".to_string(),
+            DuneColumnType::Integer => "i32".to_string(),
+            DuneColumnType::Bigint => "i64".to_string(),
+            DuneColumnType::Real => "f32".to_string(),
+            DuneColumnType::Double | DuneColumnType::Decimal { .. } => "f64".to_string(),
+            DuneColumnType::Uint256 | DuneColumnType::Int256 | DuneColumnType::Varbinary => {
+                "String".to_string()
+            }
+            DuneColumnType::Date | DuneColumnType::Timestamp { .. } => {
+                "chrono::DateTime<chrono::Utc>".to_string()
+            }
+            DuneColumnType::Json | DuneColumnType::Other(_) => "serde_json::Value".to_string(),
+            DuneColumnType::Array(inner) => format!("Vec<{}>", inner.rust_type()),
+            DuneColumnType::Map(key, value) => format!(
+                "std::collections::HashMap<{}, {}>",
+                key.rust_type(),
+                value.rust_type()
+            ),
+        }
+    }
+
+    /// Whether the column holds numbers (integers, floating point or decimals).
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            DuneColumnType::Tinyint
+                | DuneColumnType::Smallint
+                | DuneColumnType::Integer
+                | DuneColumnType::Bigint
+                | DuneColumnType::Real
+                | DuneColumnType::Double
+                | DuneColumnType::Decimal { .. }
+                | DuneColumnType::Uint256
+                | DuneColumnType::Int256
+        )
+    }
+
+    /// Whether the column holds dates or timestamps.
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self,
+            DuneColumnType::Date | DuneColumnType::Timestamp { .. }
+        )
+    }
+}
+
+/// Why an execution ended in [`ExecutionStatus::Failed`], from the `error` field of status and
+/// result responses.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::response::ExecutionError;
+///
+/// let error: ExecutionError = serde_json::from_str(
+///     r#"{
+///         "type": "FAILED_TYPE_EXECUTION_FAILED",
+///         "message": "line 3:8: Column 'amount_usd' cannot be resolved",
+///         "metadata": {"line": 3, "column": 8}
+///     }"#,
+/// )
+/// .unwrap();
+/// assert!(error.is_sql_error());
+/// assert!(!error.is_timeout());
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "RawExecutionError", into = "RawExecutionError")]
+pub struct ExecutionError {
+    /// Failure category as sent by Dune, e.g. `FAILED_TYPE_EXECUTION_FAILED`.
+    pub kind: String,
+    /// Error message from the query engine.
+    pub message: String,
+    /// Line of the query SQL the error points at, if any.
+    pub line: Option<u32>,
+    /// Column of the query SQL the error points at, if any.
+    pub column: Option<u32>,
+}
+
+impl ExecutionError {
+    /// Whether the execution ran out of time rather than failing on its SQL.
+    pub fn is_timeout(&self) -> bool {
+        let message = self.message.to_lowercase();
+        self.kind.contains("TIMEOUT")
+            || message.contains("timed out")
+            || message.contains("timeout")
+    }
+
+    /// Whether the error points at a position in the query SQL (syntax errors, unknown
+    /// columns, type mismatches, …), i.e. the query itself needs fixing.
+    pub fn is_sql_error(&self) -> bool {
+        self.line.is_some() && !self.is_timeout()
+    }
+}
+
+impl std::fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " (line {line}, column {column})"),
+            (Some(line), None) => write!(f, " (line {line})"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Wire format of [`ExecutionError`], with the position nested in `metadata`.
+#[derive(Deserialize, Serialize, Clone)]
+struct RawExecutionError {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<ErrorPosition>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct ErrorPosition {
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+impl From<ExecutionError> for RawExecutionError {
+    fn from(error: ExecutionError) -> Self {
+        RawExecutionError {
+            kind: error.kind,
+            message: error.message,
+            metadata: (error.line.is_some() || error.column.is_some()).then_some(ErrorPosition {
+                line: error.line,
+                column: error.column,
+            }),
+        }
+    }
+}
+
+impl From<RawExecutionError> for ExecutionError {
+    fn from(raw: RawExecutionError) -> Self {
+        let (line, column) = raw
+            .metadata
+            .map_or((None, None), |position| (position.line, position.column));
+        ExecutionError {
+            kind: raw.kind,
+            message: raw.message,
+            line,
+            column,
         }
     }
 }
 
 /// Returned from call to `DuneClient::cancel_execution`
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct CancellationResponse {
     /// true when cancellation was successful, otherwise false.
     pub success: bool,
 }
 
+/// A result row of unknown schema: column name to JSON value.
+///
+/// Use it as the row type when the columns are not known ahead of time (`serde_json::Value`
+/// works too). The map is sorted by column name; [`ResultMetaData::columns`] and
+/// [`ResultMetaData::ordered`] restore the query's column order and types.
+///
+/// # Example
+///
+/// ```no_run
+/// use duners::response::DynamicRow;
+/// use duners::{DuneClient, DuneRequestError};
+///
+/// # async fn run() -> Result<(), DuneRequestError> {
+/// let client = DuneClient::from_env();
+/// let results = client.refresh::<DynamicRow>(971694, None, None).await?;
+/// let metadata = &results.result.metadata;
+/// for (name, column_type) in metadata.columns() {
+///     match column_type {
+///         Some(column_type) => print!("{name} ({column_type}) "),
+///         None => print!("{name} "),
+///     }
+/// }
+/// for row in &results.result.rows {
+///     println!("{:?}", metadata.ordered(row));
+/// }
+/// # Ok(()) }
+/// ```
+pub type DynamicRow = serde_json::Map<String, Value>;
+
+/// A result row of unknown schema whose values can be read by column name: [`DynamicRow`] or
+/// `serde_json::Value`. Results with such rows can be exported, e.g. with
+/// [`GetResultResponse::write_csv`].
+pub trait ResultRow {
+    /// The value of `column`, if the row has one.
+    fn cell(&self, column: &str) -> Option<&Value>;
+}
+
+impl ResultRow for DynamicRow {
+    fn cell(&self, column: &str) -> Option<&Value> {
+        self.get(column)
+    }
+}
+
+impl ResultRow for Value {
+    fn cell(&self, column: &str) -> Option<&Value> {
+        self.get(column)
+    }
+}
+
 /// Meta content returned optionally
 /// with [GetStatusResponse](GetStatusResponse)
 /// and always contained in [ExecutionResult](ExecutionResult).
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ResultMetaData {
     /// Names of columns in the result set.
     pub column_names: Vec<String>,
-    /// Optional Dune type names for each column.
+    /// Dune types of the columns, in the order of `column_names`, when the API reports them.
     #[serde(default)]
-    pub column_types: Option<Vec<String>>,
+    pub column_types: Option<Vec<DuneColumnType>>,
     /// Number of rows in this result set (when present).
     #[serde(default)]
     pub row_count: Option<u32>,
@@ -112,30 +707,165 @@ pub struct ResultMetaData {
 /// Nested inside [GetStatusResponse](GetStatusResponse)
 /// and [GetResultResponse](GetResultResponse).
 /// Contains several UTC timestamps related to the query execution.
-#[derive(Deserialize, Debug)]
+///
+/// Serializes back into the same string format the API uses, so it can be stored and re-read.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ExecutionTimes {
     /// Time when query execution was submitted.
-    #[serde(deserialize_with = "datetime_from_str")]
+    #[serde(
+        deserialize_with = "datetime_from_str",
+        serialize_with = "datetime_to_str"
+    )]
     pub submitted_at: DateTime<Utc>,
     /// Time when execution results will no longer be stored on Dune servers.
     /// None when query execution has not yet completed.
-    #[serde(deserialize_with = "optional_datetime_from_str", default)]
+    #[serde(
+        deserialize_with = "optional_datetime_from_str",
+        serialize_with = "optional_datetime_to_str",
+        default
+    )]
     pub expires_at: Option<DateTime<Utc>>,
     /// Time when query execution began.
     /// Differs from `submitted_at` if execution was pending in the queue.
-    #[serde(deserialize_with = "optional_datetime_from_str", default)]
+    #[serde(
+        deserialize_with = "optional_datetime_from_str",
+        serialize_with = "optional_datetime_to_str",
+        default
+    )]
     pub execution_started_at: Option<DateTime<Utc>>,
     /// Time that query execution completed.
-    #[serde(deserialize_with = "optional_datetime_from_str", default)]
+    #[serde(
+        deserialize_with = "optional_datetime_from_str",
+        serialize_with = "optional_datetime_to_str",
+        default
+    )]
     pub execution_ended_at: Option<DateTime<Utc>>,
     /// Time that query execution was cancelled.
-    #[serde(deserialize_with = "optional_datetime_from_str", default)]
+    #[serde(
+        deserialize_with = "optional_datetime_from_str",
+        serialize_with = "optional_datetime_to_str",
+        default
+    )]
     pub cancelled_at: Option<DateTime<Utc>>,
 }
 
+impl ResultMetaData {
+    /// Column names in query order, each paired with its Dune type when the API reported types.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, Option<&DuneColumnType>)> {
+        let types = self.column_types.as_deref().unwrap_or_default();
+        self.column_names
+            .iter()
+            .enumerate()
+            .map(move |(i, name)| (name.as_str(), types.get(i)))
+    }
+
+    /// Checks the columns against the [`RowSchema`] of `T`, failing with
+    /// [`DuneRequestError::SchemaMismatch`] listing every missing, unread or mistyped column.
+    ///
+    /// Use [`schema::check`] to inspect the issues instead, e.g. to tolerate extra columns.
+    pub fn check_schema<T: RowSchema>(&self) -> Result<(), DuneRequestError> {
+        let issues = schema::check::<T>(self);
+        match issues.is_empty() {
+            true => Ok(()),
+            false => Err(DuneRequestError::SchemaMismatch(issues)),
+        }
+    }
+
+    /// The values of `row` in query column order (`null` for columns missing from the row).
+    pub fn ordered<'a>(&self, row: &'a DynamicRow) -> Vec<&'a Value> {
+        self.column_names
+            .iter()
+            .map(|name| row.get(name).unwrap_or(&Value::Null))
+            .collect()
+    }
+}
+
+impl ExecutionTimes {
+    /// Time spent waiting in the queue (`submitted_at` → `execution_started_at`).
+    ///
+    /// `None` while the execution has not started.
+    pub fn queue_duration(&self) -> Option<Duration> {
+        self.execution_started_at
+            .map(|started| started - self.submitted_at)
+    }
+
+    /// Time spent running the query (`execution_started_at` → `execution_ended_at`).
+    pub fn execution_duration(&self) -> Option<Duration> {
+        Some(self.execution_ended_at? - self.execution_started_at?)
+    }
+
+    /// Wall-clock time from submission until the execution finished or was cancelled.
+    ///
+    /// `None` while the execution is still pending or running.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::parse_utils::date_parse;
+    /// use duners::response::ExecutionTimes;
+    ///
+    /// let times = ExecutionTimes {
+    ///     submitted_at: date_parse("2024-01-01T00:00:00.0Z").unwrap(),
+    ///     expires_at: None,
+    ///     execution_started_at: Some(date_parse("2024-01-01T00:00:02.0Z").unwrap()),
+    ///     execution_ended_at: Some(date_parse("2024-01-01T00:00:07.0Z").unwrap()),
+    ///     cancelled_at: None,
+    /// };
+    /// assert_eq!(times.queue_duration().unwrap().num_seconds(), 2);
+    /// assert_eq!(times.total_duration().unwrap().num_seconds(), 7);
+    /// ```
+    pub fn total_duration(&self) -> Option<Duration> {
+        self.execution_ended_at
+            .or(self.cancelled_at)
+            .map(|finished| finished - self.submitted_at)
+    }
+
+    /// Whether the results have expired at `now`, treating them as expired `tolerance` early
+    /// to absorb clock differences. `false` when no `expires_at` is known; `true` when the
+    /// tolerance reaches past the end of representable time.
+    ///
+    /// Pass a server-corrected `now` (see
+    /// [`DuneClient::server_now`](crate::client::DuneClient::server_now)) on hosts with drifting clocks.
+    pub fn is_expired_at(&self, now: DateTime<Utc>, tolerance: Duration) -> bool {
+        self.expires_at.is_some_and(|expires_at| {
+            now.checked_add_signed(tolerance)
+                .is_none_or(|deadline| deadline >= expires_at)
+        })
+    }
+
+    /// Flattens the timestamps (RFC 3339) and derived durations (milliseconds) into string pairs,
+    /// convenient for structured logging. Absent values are omitted.
+    pub fn to_flat_map(&self) -> BTreeMap<String, String> {
+        let mut map = BTreeMap::new();
+        let timestamps = [
+            ("submitted_at", Some(self.submitted_at)),
+            ("expires_at", self.expires_at),
+            ("execution_started_at", self.execution_started_at),
+            ("execution_ended_at", self.execution_ended_at),
+            ("cancelled_at", self.cancelled_at),
+        ];
+        for (key, value) in timestamps {
+            if let Some(value) = value {
+                map.insert(key.to_string(), value.to_rfc3339());
+            }
+        }
+        let durations = [
+            ("queue_duration_ms", self.queue_duration()),
+            ("execution_duration_ms", self.execution_duration()),
+            ("total_duration_ms", self.total_duration()),
+        ];
+        for (key, value) in durations {
+            if let Some(value) = value {
+                map.insert(key.to_string(), value.num_milliseconds().to_string());
+            }
+        }
+        map
+    }
+}
+
 /// Returned by successful call to `DuneClient::get_status`.
 /// Indicates the current state of execution along with some metadata.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct GetStatusResponse {
     /// Same execution ID used in the status request.
     pub execution_id: String,
@@ -151,12 +881,80 @@ pub struct GetStatusResponse {
     pub queue_position: Option<u32>,
     /// This field will be non-empty once query execution has completed.
     pub result_metadata: Option<ResultMetaData>,
+    /// Credits charged for the execution, when reported by the API.
+    #[serde(default)]
+    pub execution_cost_credits: Option<f64>,
+    /// Why the execution failed, when its state is [`ExecutionStatus::Failed`].
+    #[serde(default)]
+    pub error: Option<ExecutionError>,
+    /// Rate-limit headers of the response, if Dune sent any.
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimitStatus>,
+    /// ID of the request (`X-Request-Id` or similar), for support tickets.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+impl GetStatusResponse {
+    /// When the execution was submitted.
+    ///
+    /// The timestamps are top-level fields in the API's JSON (and in the serialized response);
+    /// these accessors read them from [`times`](Self::times).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let status = client.get_status("01HB7NDSAV6TCGYZ6NN0WXRRXJ").await?;
+    /// println!("submitted {}, expires {:?}", status.submitted_at(), status.expires_at());
+    /// # Ok(()) }
+    /// ```
+    pub fn submitted_at(&self) -> DateTime<Utc> {
+        self.times.submitted_at
+    }
+
+    /// When the results expire, once known.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.times.expires_at
+    }
+
+    /// When the execution started running.
+    pub fn execution_started_at(&self) -> Option<DateTime<Utc>> {
+        self.times.execution_started_at
+    }
+
+    /// When the execution finished.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let status = client.get_status("01HB7NDSAV6TCGYZ6NN0WXRRXJ").await?;
+    /// if let (Some(started), Some(ended)) = (status.execution_started_at(), status.execution_ended_at()) {
+    ///     println!("ran for {}s", (ended - started).num_seconds());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn execution_ended_at(&self) -> Option<DateTime<Utc>> {
+        self.times.execution_ended_at
+    }
+
+    /// When the execution was cancelled.
+    pub fn cancelled_at(&self) -> Option<DateTime<Utc>> {
+        self.times.cancelled_at
+    }
 }
 
 /// Contains the query results along with some additional metadata.
 /// This struct is nested inside [GetResultResponse](GetResultResponse)
 /// as the `result` field.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ExecutionResult<T> {
     /// Deserialized result rows; `T` is your row type (e.g. a struct with `#[derive(Deserialize)]`).
     pub rows: Vec<T>,
@@ -167,7 +965,7 @@ pub struct ExecutionResult<T> {
 /// Returned by a successful call to `DuneClient::get_results`.
 /// Contains similar information to [GetStatusResponse](GetStatusResponse)
 /// except that [ResultMetaData](ResultMetaData) is contained within the `result` field.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct GetResultResponse<T> {
     /// Execution ID for this result.
     pub execution_id: String,
@@ -178,14 +976,30 @@ pub struct GetResultResponse<T> {
     pub is_execution_finished: Option<bool>,
     /// Final state (typically [`ExecutionStatus::Complete`] when results are available).
     pub state: ExecutionStatus,
-    // TODO - this `flatten` isn't what I had hoped for.
-    //  I want the `times` field to disappear
-    //  and all sub-fields to be brought up to this layer.
     /// Timestamps for submitted_at, expires_at, execution_started_at, etc.
     #[serde(flatten)]
     pub times: ExecutionTimes,
     /// The result set (rows and metadata).
     pub result: ExecutionResult<T>,
+    /// Why the execution failed, when its state is [`ExecutionStatus::Failed`].
+    #[serde(default)]
+    pub error: Option<ExecutionError>,
+    /// Offset of the next page when results were requested with a `limit` and more rows remain.
+    #[serde(default)]
+    pub next_offset: Option<u64>,
+    /// Ready-made URI for the next page (same condition as `next_offset`).
+    #[serde(default)]
+    pub next_uri: Option<String>,
+    /// `true` when the execution only [partially completed](ExecutionStatus::PartiallyComplete),
+    /// i.e. `result` holds fewer rows than the query produced.
+    #[serde(skip)]
+    pub partial: bool,
+    /// Rate-limit headers of the response, if Dune sent any.
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimitStatus>,
+    /// ID of the request (`X-Request-Id` or similar), for support tickets.
+    #[serde(skip)]
+    pub request_id: Option<String>,
 }
 
 impl<T> GetResultResponse<T> {
@@ -209,18 +1023,752 @@ impl<T> GetResultResponse<T> {
     pub fn get_rows(self) -> Vec<T> {
         self.result.rows
     }
+
+    /// When the execution was submitted (see [`GetStatusResponse::submitted_at`]).
+    pub fn submitted_at(&self) -> DateTime<Utc> {
+        self.times.submitted_at
+    }
+
+    /// Why the rows may not be the whole result set, or `None` when their number matches the
+    /// metadata.
+    ///
+    /// Checked in order: a [`partial`](Self::partial) execution, a
+    /// [`next_offset`](Self::next_offset) pointing at more pages, then the number of rows against
+    /// `row_count` (when present) and `total_row_count`. Single pages of a paged read therefore
+    /// report [`RowCountIssue::MorePages`] until the last one, and the last one a mismatch with
+    /// `total_row_count` unless it holds every row.
+    pub fn row_count_issue(&self) -> Option<RowCountIssue> {
+        let rows = self.result.rows.len() as u64;
+        let metadata = &self.result.metadata;
+        let total_row_count = u64::from(metadata.total_row_count);
+        if self.partial || self.state == ExecutionStatus::PartiallyComplete {
+            return Some(RowCountIssue::Partial {
+                rows,
+                total_row_count,
+            });
+        }
+        if let Some(next_offset) = self.next_offset {
+            return Some(RowCountIssue::MorePages {
+                rows,
+                total_row_count,
+                next_offset,
+            });
+        }
+        let expected = match metadata.row_count {
+            Some(row_count) if u64::from(row_count) != rows => u64::from(row_count),
+            _ => total_row_count,
+        };
+        (rows != expected).then_some(RowCountIssue::Mismatch { rows, expected })
+    }
+
+    /// Fails with [`DuneRequestError::IncompleteResults`] unless the rows are the whole result
+    /// set (see [`row_count_issue`](Self::row_count_issue)), so truncated or paged results are
+    /// not mistaken for complete ones. [`DuneClient::with_row_count_check`](crate::client::DuneClient::with_row_count_check)
+    /// applies it to every full result fetched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::response::{GetResultResponse, RowCountIssue};
+    /// use duners::DuneRequestError;
+    ///
+    /// let results: GetResultResponse<serde_json::Value> = serde_json::from_value(serde_json::json!({
+    ///     "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+    ///     "submitted_at": "2024-01-01T00:00:00.0Z", "next_offset": 1,
+    ///     "result": {
+    ///         "rows": [{"n": 1}],
+    ///         "metadata": {
+    ///             "column_names": ["n"], "row_count": 1, "result_set_bytes": 0,
+    ///             "total_row_count": 3, "datapoint_count": 3,
+    ///             "pending_time_millis": null, "execution_time_millis": 1
+    ///         }
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// assert!(matches!(
+    ///     results.check_row_count(),
+    ///     Err(DuneRequestError::IncompleteResults(RowCountIssue::MorePages { next_offset: 1, .. }))
+    /// ));
+    /// ```
+    pub fn check_row_count(&self) -> Result<(), DuneRequestError> {
+        match self.row_count_issue() {
+            Some(issue) => Err(DuneRequestError::IncompleteResults(issue)),
+            None => Ok(()),
+        }
+    }
+
+    /// When the results expire.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde_json::Value;
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = client.get_results::<Value>("01HB7NDSAV6TCGYZ6NN0WXRRXJ").await?;
+    /// if let Some(expires_at) = results.expires_at() {
+    ///     println!("cache these results until {expires_at}");
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.times.expires_at
+    }
+
+    /// When the execution started running.
+    pub fn execution_started_at(&self) -> Option<DateTime<Utc>> {
+        self.times.execution_started_at
+    }
+
+    /// When the execution finished.
+    pub fn execution_ended_at(&self) -> Option<DateTime<Utc>> {
+        self.times.execution_ended_at
+    }
+
+    /// When the execution was cancelled.
+    pub fn cancelled_at(&self) -> Option<DateTime<Utc>> {
+        self.times.cancelled_at
+    }
+}
+
+impl GetResultResponse<Value> {
+    /// Deserializes the untyped rows into `T`, skipping rows that do not fit instead of failing
+    /// the whole result. Skipped rows are reported in [`LossyRows::invalid`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde::Deserialize;
+    /// use serde_json::Value;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Row { symbol: String, max_price: f64 }
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = client.refresh::<Value>(971694, None, None).await?;
+    /// let lossy = results.get_rows_lossy::<Row>();
+    /// for invalid in &lossy.invalid {
+    ///     eprintln!("skipped row {}: {}", invalid.index, invalid.error);
+    /// }
+    /// println!("{} valid rows", lossy.rows.len());
+    /// # Ok(()) }
+    /// ```
+    pub fn get_rows_lossy<T: DeserializeOwned>(self) -> LossyRows<T> {
+        let mut lossy = LossyRows {
+            rows: Vec::with_capacity(self.result.rows.len()),
+            invalid: Vec::new(),
+        };
+        for (index, raw) in self.result.rows.into_iter().enumerate() {
+            match deserialize_row(index, &raw) {
+                Ok(row) => lossy.rows.push(row),
+                Err(error) => lossy.invalid.push(InvalidRow { index, error, raw }),
+            }
+        }
+        lossy
+    }
+
+    /// Checks the columns against the [`RowSchema`] of `T` (see
+    /// [`ResultMetaData::check_schema`]), then deserializes the rows into `T`.
+    ///
+    /// Fetch results as `Value` to check them before any row is deserialized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::schema::{FieldKind, FieldSpec, RowSchema};
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde::Deserialize;
+    /// use serde_json::Value;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Row { symbol: String, max_price: f64 }
+    ///
+    /// impl RowSchema for Row {
+    ///     fn schema() -> Vec<FieldSpec> {
+    ///         vec![
+    ///             FieldSpec::new("symbol", FieldKind::String),
+    ///             FieldSpec::new("max_price", FieldKind::Float),
+    ///         ]
+    ///     }
+    /// }
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = client.refresh::<Value>(971694, None, None).await?;
+    /// let rows = results.into_checked::<Row>()?.get_rows();
+    /// # Ok(()) }
+    /// ```
+    pub fn into_checked<T: RowSchema + DeserializeOwned>(
+        self,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        self.result.metadata.check_schema::<T>()?;
+        self.try_map_rows(deserialize_row)
+    }
+
+    /// Converts every row with `convert` (given the row's index), keeping everything else.
+    pub(crate) fn try_map_rows<T>(
+        self,
+        mut convert: impl FnMut(usize, &Value) -> Result<T, DuneRequestError>,
+    ) -> Result<GetResultResponse<T>, DuneRequestError> {
+        let rows = self
+            .result
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| convert(index, row))
+            .collect::<Result<_, _>>()?;
+        Ok(GetResultResponse {
+            execution_id: self.execution_id,
+            query_id: self.query_id,
+            is_execution_finished: self.is_execution_finished,
+            state: self.state,
+            times: self.times,
+            result: ExecutionResult {
+                rows,
+                metadata: self.result.metadata,
+            },
+            error: self.error,
+            next_offset: self.next_offset,
+            next_uri: self.next_uri,
+            partial: self.partial,
+            rate_limit: self.rate_limit,
+            request_id: self.request_id,
+        })
+    }
+}
+
+impl<T: ResultRow> GetResultResponse<T> {
+    /// Writes the rows as CSV to `writer`: a header of the `column_names`, then one line per
+    /// row with the values in the same column order.
+    ///
+    /// Strings are written as they are, numbers and booleans as JSON writes them, `null` as an
+    /// empty field, and arrays and objects as JSON text. Fields containing commas, quotes or
+    /// line breaks are quoted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::GetResultResponse;
+    ///
+    /// let results: GetResultResponse<serde_json::Value> = serde_json::from_value(serde_json::json!({
+    ///     "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+    ///     "submitted_at": "2024-01-01T00:00:00.0Z",
+    ///     "result": {
+    ///         "rows": [{"symbol": "ETH", "max_price": 3000.5}, {"symbol": "B,TC", "max_price": null}],
+    ///         "metadata": {
+    ///             "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    ///             "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    ///             "pending_time_millis": null, "execution_time_millis": 1
+    ///         }
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// let mut csv = Vec::new();
+    /// results.write_csv(&mut csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "symbol,max_price\nETH,3000.5\n\"B,TC\",\n");
+    /// ```
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let columns = &self.result.metadata.column_names;
+        write_csv_record(
+            &mut writer,
+            columns.iter().map(|name| Cow::from(name.as_str())),
+        )?;
+        for row in &self.result.rows {
+            let fields = columns.iter().map(|name| csv_field(row.cell(name)));
+            write_csv_record(&mut writer, fields)?;
+        }
+        writer.flush()
+    }
+}
+
+/// The CSV text of a value.
+fn csv_field(value: Option<&Value>) -> Cow<'_, str> {
+    match value {
+        None | Some(Value::Null) => Cow::from(""),
+        Some(Value::String(text)) => Cow::from(text.as_str()),
+        Some(other) => Cow::from(other.to_string()),
+    }
+}
+
+/// Writes one CSV line, quoting fields that need it.
+fn write_csv_record<'a>(
+    writer: &mut impl Write,
+    fields: impl Iterator<Item = Cow<'a, str>>,
+) -> io::Result<()> {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            writer.write_all(b",")?;
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\n")
+}
+
+#[cfg(feature = "arrow")]
+impl<T: ResultRow> GetResultResponse<T> {
+    /// Converts the rows (as `Value` or [`DynamicRow`]) to an Arrow record batch with the
+    /// [`arrow_schema`](crate::columnar::arrow_schema) of the result, ready for Parquet writers,
+    /// DataFusion or IPC. Slice it with `RecordBatch::slice` for smaller batches.
+    ///
+    /// Values that do not convert to their column's type fail with
+    /// [`DuneRequestError::Deserialization`] naming the row and column.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::GetResultResponse;
+    ///
+    /// let results: GetResultResponse<serde_json::Value> = serde_json::from_value(serde_json::json!({
+    ///     "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+    ///     "submitted_at": "2024-01-01T00:00:00.0Z",
+    ///     "result": {
+    ///         "rows": [{"symbol": "ETH", "max_price": "3000.5"}, {"symbol": "BTC", "max_price": 61000}],
+    ///         "metadata": {
+    ///             "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    ///             "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    ///             "pending_time_millis": null, "execution_time_millis": 1
+    ///         }
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// let batch = results.to_record_batch().unwrap();
+    /// assert_eq!(batch.num_rows(), 2);
+    /// assert_eq!(batch.schema().field(1).data_type(), &arrow_schema::DataType::Float64);
+    /// ```
+    pub fn to_record_batch(&self) -> Result<arrow_array::RecordBatch, DuneRequestError> {
+        crate::columnar::result_to_record_batch(&self.result)
+    }
+
+    /// Writes the rows to `writer` as a Parquet file, with the columns of
+    /// [`to_record_batch`](Self::to_record_batch) (feature `parquet`).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::columnar::ParquetOptions;
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde_json::Value;
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = client.refresh::<Value>(971694, None, None).await?;
+    /// let file = std::fs::File::create("prices.parquet")?;
+    /// results.write_parquet(file, ParquetOptions::default())?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(
+        &self,
+        writer: W,
+        options: crate::columnar::ParquetOptions,
+    ) -> Result<(), DuneRequestError> {
+        crate::columnar::write_parquet(&self.to_record_batch()?, writer, options)
+    }
+
+    /// Writes the rows to `writer` in the Arrow IPC file format, also known as Feather v2, with
+    /// the columns of [`to_record_batch`](Self::to_record_batch) (feature `ipc`).
+    ///
+    /// pyarrow (`pyarrow.feather.read_table`, `pandas.read_feather`), polars
+    /// (`pl.read_ipc`) and R (`arrow::read_feather`) read the file without parsing any text.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use duners::{DuneClient, DuneRequestError};
+    /// use serde_json::Value;
+    ///
+    /// # async fn run() -> Result<(), DuneRequestError> {
+    /// let client = DuneClient::from_env();
+    /// let results = client.refresh::<Value>(971694, None, None).await?;
+    /// let mut bytes = Vec::new();
+    /// results.write_ipc(&mut bytes)?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "ipc")]
+    pub fn write_ipc<W: Write>(&self, writer: W) -> Result<(), DuneRequestError> {
+        crate::columnar::write_ipc(&self.to_record_batch()?, writer)
+    }
+
+    /// Registers the rows as the table `table_name` of a DataFusion session, with the columns of
+    /// [`to_record_batch`](Self::to_record_batch) (feature `datafusion`), for local SQL joins and
+    /// aggregations across result sets without querying Dune again.
+    ///
+    /// Registering a name the session already has fails with [`DuneRequestError::Encode`];
+    /// call `ctx.deregister_table` first to replace a table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use datafusion::prelude::SessionContext;
+    /// use duners::DuneClient;
+    /// use serde_json::Value;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = DuneClient::from_env();
+    /// let ctx = SessionContext::new();
+    /// let prices = client.refresh::<Value>(971694, None, None).await?;
+    /// prices.register_with(&ctx, "prices")?;
+    /// let volumes = client.refresh::<Value>(1215383, None, None).await?;
+    /// volumes.register_with(&ctx, "volumes")?;
+    /// ctx.sql("SELECT p.symbol, p.max_price, v.volume FROM prices p JOIN volumes v USING (symbol)")
+    ///     .await?
+    ///     .show()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "datafusion")]
+    pub fn register_with(
+        &self,
+        ctx: &datafusion::prelude::SessionContext,
+        table_name: &str,
+    ) -> Result<(), DuneRequestError> {
+        let batch = crate::columnar::to_datafusion_batch(&self.to_record_batch()?)?;
+        ctx.register_batch(table_name, batch)
+            .map_err(|err| DuneRequestError::Encode(err.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "polars")]
+impl<T: ResultRow> GetResultResponse<T> {
+    /// Converts the rows (as `Value` or [`DynamicRow`]) to a polars data frame, with one
+    /// column per result column typed by its Dune type (see
+    /// [`polars_type`](crate::columnar::polars_type)), so numbers and timestamps Dune sends as
+    /// strings arrive as numbers and datetimes.
+    ///
+    /// Values that do not convert to their column's type fail with
+    /// [`DuneRequestError::Deserialization`] naming the row and column.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::GetResultResponse;
+    /// use polars::prelude::DataType;
+    ///
+    /// let results: GetResultResponse<serde_json::Value> = serde_json::from_value(serde_json::json!({
+    ///     "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+    ///     "submitted_at": "2024-01-01T00:00:00.0Z",
+    ///     "result": {
+    ///         "rows": [{"symbol": "ETH", "max_price": "3000.5"}, {"symbol": "BTC", "max_price": 61000}],
+    ///         "metadata": {
+    ///             "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    ///             "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    ///             "pending_time_millis": null, "execution_time_millis": 1
+    ///         }
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// let frame = results.to_dataframe().unwrap();
+    /// assert_eq!(frame.shape(), (2, 2));
+    /// assert_eq!(frame.dtypes(), vec![DataType::String, DataType::Float64]);
+    /// ```
+    pub fn to_dataframe(&self) -> Result<polars::prelude::DataFrame, DuneRequestError> {
+        crate::columnar::result_to_dataframe(&self.result)
+    }
+}
+
+/// Longest cell, in characters, [`to_table_string`](GetResultResponse::to_table_string) prints
+/// before cutting it off.
+#[cfg(feature = "pretty")]
+const MAX_TABLE_CELL_CHARS: usize = 48;
+
+#[cfg(feature = "pretty")]
+impl<T: ResultRow> GetResultResponse<T> {
+    /// Renders the first `max_rows` rows as an aligned ASCII table (feature `pretty`), for
+    /// debugging and command-line output.
+    ///
+    /// The header shows each column's name above its Dune type. Numbers are right-aligned,
+    /// `null` reads `NULL`, and cells longer than 48 characters are cut off with `...`. When
+    /// rows are left out, a last line counts them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::GetResultResponse;
+    ///
+    /// let results: GetResultResponse<serde_json::Value> = serde_json::from_value(serde_json::json!({
+    ///     "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+    ///     "submitted_at": "2024-01-01T00:00:00.0Z",
+    ///     "result": {
+    ///         "rows": [
+    ///             {"symbol": "ETH", "max_price": 3000.5},
+    ///             {"symbol": "BTC", "max_price": 61000},
+    ///             {"symbol": "DAI", "max_price": null}
+    ///         ],
+    ///         "metadata": {
+    ///             "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    ///             "result_set_bytes": 0, "total_row_count": 3, "datapoint_count": 6,
+    ///             "pending_time_millis": null, "execution_time_millis": 1
+    ///         }
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// assert_eq!(
+    ///     results.to_table_string(2),
+    ///     "\
+    /// +---------+-----------+
+    /// | symbol  | max_price |
+    /// | varchar | double    |
+    /// +=====================+
+    /// | ETH     |    3000.5 |
+    /// | BTC     |     61000 |
+    /// +---------+-----------+
+    /// ... 1 more row"
+    /// );
+    /// ```
+    pub fn to_table_string(&self, max_rows: usize) -> String {
+        use comfy_table::presets::ASCII_FULL_CONDENSED;
+        use comfy_table::{Cell, CellAlignment, Table};
+        let metadata = &self.result.metadata;
+        let mut table = Table::new();
+        table.load_style(ASCII_FULL_CONDENSED);
+        table.set_header(metadata.columns().map(|(name, column_type)| {
+            let header = match column_type {
+                Some(column_type) => format!("{name}\n{column_type}"),
+                None => name.to_string(),
+            };
+            Cell::new(header).set_alignment(CellAlignment::Left)
+        }));
+        for row in self.result.rows.iter().take(max_rows) {
+            table.add_row(
+                metadata
+                    .columns()
+                    .map(|(name, _)| table_cell(row.cell(name))),
+            );
+        }
+        for (index, (_, column_type)) in metadata.columns().enumerate() {
+            if column_type.is_some_and(DuneColumnType::is_numeric) {
+                if let Some(column) = table.column_mut(index) {
+                    column.set_cell_alignment(CellAlignment::Right);
+                }
+            }
+        }
+        let mut rendered = table.to_string();
+        let hidden = self.result.rows.len().saturating_sub(max_rows);
+        if hidden > 0 {
+            let plural = if hidden == 1 { "" } else { "s" };
+            rendered.push_str(&format!("\n... {hidden} more row{plural}"));
+        }
+        rendered
+    }
+}
+
+/// The text of a value in [`GetResultResponse::to_table_string`].
+#[cfg(feature = "pretty")]
+fn table_cell(value: Option<&Value>) -> String {
+    let text = match value {
+        None | Some(Value::Null) => return "NULL".to_string(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    };
+    // Line breaks would split the row.
+    let text = text.replace(['\n', '\r'], " ");
+    match text.char_indices().nth(MAX_TABLE_CELL_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+/// Why results do not hold every row of the result set, from
+/// [`GetResultResponse::row_count_issue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowCountIssue {
+    /// The execution only [partially completed](ExecutionStatus::PartiallyComplete), e.g.
+    /// because its results exceeded the size limit.
+    Partial {
+        /// Rows held.
+        rows: u64,
+        /// Rows the metadata reports for the whole result set.
+        total_row_count: u64,
+    },
+    /// The rows are one page of a paged read and more pages follow.
+    MorePages {
+        /// Rows held.
+        rows: u64,
+        /// Rows the metadata reports for the whole result set.
+        total_row_count: u64,
+        /// Offset of the next page.
+        next_offset: u64,
+    },
+    /// The number of rows differs from `row_count` (or `total_row_count`) with no explanation
+    /// in the response, e.g. a body cut off in transit or rows dropped while reading it.
+    Mismatch {
+        /// Rows held.
+        rows: u64,
+        /// Rows the metadata reports.
+        expected: u64,
+    },
+}
+
+impl std::fmt::Display for RowCountIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowCountIssue::Partial {
+                rows,
+                total_row_count,
+            } => write!(
+                f,
+                "the execution only partially completed ({rows} of {total_row_count} rows)"
+            ),
+            RowCountIssue::MorePages {
+                rows,
+                total_row_count,
+                next_offset,
+            } => write!(
+                f,
+                "{rows} of {total_row_count} rows, more pages start at offset {next_offset}"
+            ),
+            RowCountIssue::Mismatch { rows, expected } => {
+                write!(f, "got {rows} rows, the metadata reports {expected}")
+            }
+        }
+    }
+}
+
+/// Rows of a result deserialized with
+/// [`get_rows_lossy`](GetResultResponse::get_rows_lossy).
+#[derive(Debug)]
+pub struct LossyRows<T> {
+    /// The rows that deserialized, in order.
+    pub rows: Vec<T>,
+    /// The rows that did not.
+    pub invalid: Vec<InvalidRow>,
+}
+
+/// A result row that could not be deserialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidRow {
+    /// Position of the row in the result.
+    pub index: usize,
+    /// Why it failed: a [`DuneRequestError::Deserialization`] naming the row, column and value.
+    pub error: DuneRequestError,
+    /// The row as sent by Dune.
+    pub raw: Value,
+}
+
+/// Difference between the Dune server clock and the local clock, as last observed
+/// from a response's `Date` header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockSkew {
+    /// Server time minus local time (positive when the local clock is behind).
+    pub offset: Duration,
+    /// Local time at which the offset was measured.
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Rate-limit budget reported by Dune in a response's `X-RateLimit-*` headers.
+///
+/// The latest one is available from
+/// [`DuneClient::rate_limit_status`](crate::client::DuneClient::rate_limit_status), and
+/// execution, status and result responses carry their own in a `rate_limit` field.
+///
+/// # Example
+///
+/// Slow down before hitting the limit instead of reacting to 429s:
+///
+/// ```no_run
+/// use duners::{DuneClient, DuneRequestError};
+///
+/// # async fn run() -> Result<(), DuneRequestError> {
+/// let client = DuneClient::from_env();
+/// let status = client.get_status("your-execution-id").await?;
+/// if let Some(limit) = status.rate_limit.filter(|limit| limit.is_exhausted()) {
+///     println!("out of requests until {:?}", limit.reset);
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests allowed per window (`X-RateLimit-Limit`).
+    pub limit: Option<u32>,
+    /// Requests left in the current window (`X-RateLimit-Remaining`).
+    pub remaining: Option<u32>,
+    /// When the window resets (`X-RateLimit-Reset`, sent either as a Unix timestamp or as
+    /// seconds from now).
+    pub reset: Option<DateTime<Utc>>,
+    /// Local time at which the headers were received.
+    pub observed_at: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    /// Reads the `X-RateLimit-*` (or unprefixed `RateLimit-*`) headers received at `now`;
+    /// `None` if there are none.
+    pub(crate) fn from_headers(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Self> {
+        let header = |name: &str| {
+            [format!("x-ratelimit-{name}"), format!("ratelimit-{name}")]
+                .iter()
+                .find_map(|name| headers.get(name.as_str())?.to_str().ok())
+                .map(str::trim)
+        };
+        let limit = header("limit").and_then(|value| value.parse().ok());
+        let remaining = header("remaining").and_then(|value| value.parse().ok());
+        let reset = header("reset")
+            .and_then(|value| value.parse::<f64>().ok())
+            .and_then(|seconds| match seconds >= UNIX_TIMESTAMP_THRESHOLD {
+                true => DateTime::from_timestamp(seconds as i64, 0),
+                false => Some(now + Duration::milliseconds((seconds * 1000.0) as i64)),
+            });
+        (limit.is_some() || remaining.is_some() || reset.is_some()).then_some(RateLimitStatus {
+            limit,
+            remaining,
+            reset,
+            observed_at: now,
+        })
+    }
+
+    /// Whether no requests are left in the current window.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+/// `X-RateLimit-Reset` values at least this large are Unix timestamps rather than delays.
+const UNIX_TIMESTAMP_THRESHOLD: f64 = 1_000_000_000.0;
+
+/// Returned by [`DuneClient::download_csv`](crate::client::DuneClient::download_csv).
+///
+/// Describes the file written and whether it matches what Dune reported for the execution.
+#[derive(Debug, PartialEq)]
+pub struct CsvDownload {
+    /// Where the CSV was written.
+    pub path: PathBuf,
+    /// Number of (decompressed) bytes written to `path`.
+    pub bytes_written: u64,
+    /// Number of data rows in the file (excluding the header).
+    pub rows: u64,
+    /// Row count reported in the execution's [`ResultMetaData`], if available.
+    pub expected_rows: Option<u64>,
+    /// `true` when the row count matches `expected_rows` and every page was received
+    /// in full (per its `Content-Length`, when sent).
+    pub verified: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse_utils::date_parse;
 
     #[test]
     fn status_from_str() {
         assert_eq!(
-            ExecutionStatus::from_str("invalid"),
-            Err(String::from("Parse Error invalid"))
+            ExecutionStatus::from_str("QUERY_STATE_SOMETHING_NEW"),
+            Ok(ExecutionStatus::Unknown(
+                "QUERY_STATE_SOMETHING_NEW".to_string()
+            ))
         );
+        let status: GetStatusResponse = serde_json::from_str(
+            r#"{"execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_SOMETHING_NEW", "submitted_at": "2024-01-01T00:00:00.000Z"}"#,
+        )
+        .unwrap();
+        assert!(!status.state.is_terminal());
         assert_eq!(
             ExecutionStatus::from_str("QUERY_STATE_COMPLETED"),
             Ok(ExecutionStatus::Complete)
@@ -241,6 +1789,14 @@ mod tests {
             ExecutionStatus::from_str("QUERY_STATE_FAILED"),
             Ok(ExecutionStatus::Failed)
         );
+        assert_eq!(
+            ExecutionStatus::from_str("QUERY_STATE_COMPLETED_PARTIAL"),
+            Ok(ExecutionStatus::PartiallyComplete)
+        );
+        assert_eq!(
+            ExecutionStatus::from_str("QUERY_STATE_EXPIRED"),
+            Ok(ExecutionStatus::Expired)
+        );
     }
 
     #[test]
@@ -248,10 +1804,434 @@ mod tests {
         assert!(ExecutionStatus::Complete.is_terminal());
         assert!(ExecutionStatus::Cancelled.is_terminal());
         assert!(ExecutionStatus::Failed.is_terminal());
+        assert!(ExecutionStatus::PartiallyComplete.is_terminal());
+        assert!(ExecutionStatus::Expired.is_terminal());
 
         assert!(!ExecutionStatus::Pending.is_terminal());
         assert!(!ExecutionStatus::Executing.is_terminal());
     }
+    #[test]
+    fn query_definition() {
+        let query: Query = serde_json::from_str(
+            r#"{
+                "query_id": 1215383,
+                "name": "Parameterized",
+                "description": "",
+                "tags": ["test"],
+                "version": 3,
+                "parameters": [
+                    {"key": "TextField", "value": "Plain Text", "type": "text"},
+                    {"key": "ListField", "value": "Option 1", "type": "enum", "enumOptions": ["Option 1", "Option 2"]}
+                ],
+                "query_engine": "v2 Dune SQL",
+                "query_sql": "SELECT '{{TextField}}' AS text_field",
+                "is_private": false,
+                "is_archived": false,
+                "is_unsaved": false,
+                "owner": "bh2smith"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(query.tags, vec!["test"]);
+        assert_eq!(query.parameters[1].parameter_type, "enum");
+        assert_eq!(
+            query.parameters[1].enum_options,
+            Some(vec!["Option 1".to_string(), "Option 2".to_string()])
+        );
+        assert_eq!(query.parameters[0].enum_options, None);
+    }
+
+    #[test]
+    fn created_query_id() {
+        let created: CreateQueryResponse = serde_json::from_str(r#"{"query_id": 42}"#).unwrap();
+        assert_eq!(created.query_id, QueryId(42));
+        assert_eq!(
+            serde_json::to_string(&created).unwrap(),
+            r#"{"query_id":42}"#
+        );
+    }
+
+    #[test]
+    fn execution_time_helpers() {
+        let times = ExecutionTimes {
+            submitted_at: date_parse("2024-01-01T00:00:00.0Z").unwrap(),
+            expires_at: None,
+            execution_started_at: Some(date_parse("2024-01-01T00:00:01.0Z").unwrap()),
+            execution_ended_at: Some(date_parse("2024-01-01T00:00:04.0Z").unwrap()),
+            cancelled_at: None,
+        };
+        assert_eq!(times.queue_duration(), Some(Duration::seconds(1)));
+        assert_eq!(times.execution_duration(), Some(Duration::seconds(3)));
+        assert_eq!(times.total_duration(), Some(Duration::seconds(4)));
+
+        let map = times.to_flat_map();
+        assert_eq!(map["submitted_at"], "2024-01-01T00:00:00+00:00");
+        assert_eq!(map["total_duration_ms"], "4000");
+        assert!(!map.contains_key("expires_at"));
+
+        let pending = ExecutionTimes {
+            execution_started_at: None,
+            execution_ended_at: None,
+            ..times
+        };
+        assert_eq!(pending.queue_duration(), None);
+        assert_eq!(pending.total_duration(), None);
+    }
+
+    #[test]
+    fn expiry_with_tolerance() {
+        let expires_at = date_parse("2024-01-01T12:00:00.0Z").unwrap();
+        let times = ExecutionTimes {
+            submitted_at: Default::default(),
+            expires_at: Some(expires_at),
+            execution_started_at: None,
+            execution_ended_at: None,
+            cancelled_at: None,
+        };
+        let before = expires_at - Duration::seconds(10);
+        assert!(!times.is_expired_at(before, Duration::zero()));
+        assert!(times.is_expired_at(before, Duration::seconds(30)));
+        assert!(times.is_expired_at(expires_at, Duration::zero()));
+        // A tolerance overflowing the calendar counts as expired instead of panicking.
+        assert!(times.is_expired_at(before, Duration::MAX));
+    }
+
+    #[test]
+    fn execution_times_serialize_round_trip() {
+        let json = r#"{"submitted_at":"2024-01-01T00:00:00.000Z","expires_at":null,"execution_started_at":"2024-01-01T00:00:01.000Z","execution_ended_at":null,"cancelled_at":null}"#;
+        let times: ExecutionTimes = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&times).unwrap(), json);
+    }
+
+    #[test]
+    fn rate_limit_headers() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateLimitStatus::from_headers(&headers, now), None);
+
+        headers.insert("x-ratelimit-limit", "40".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "30".parse().unwrap());
+        let status = RateLimitStatus::from_headers(&headers, now).unwrap();
+        assert_eq!(status.limit, Some(40));
+        assert_eq!(status.remaining, Some(0));
+        assert_eq!(status.reset, Some(now + Duration::seconds(30)));
+        assert!(status.is_exhausted());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit-remaining", "7".parse().unwrap());
+        headers.insert("ratelimit-reset", "1700000060".parse().unwrap());
+        let status = RateLimitStatus::from_headers(&headers, now).unwrap();
+        assert_eq!((status.limit, status.remaining), (None, Some(7)));
+        assert_eq!(status.reset, Some(now + Duration::seconds(60)));
+        assert!(!status.is_exhausted());
+    }
+
+    #[test]
+    fn results_round_trip() {
+        let json = serde_json::json!({
+            "execution_id": "jerb",
+            "query_id": 1,
+            "state": "QUERY_STATE_FAILED",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "error": {"type": "FAILED_TYPE_EXECUTION_FAILED", "message": "boom", "metadata": {"line": 2, "column": 4}},
+            "result": {
+                "rows": [{"price": 1.5}],
+                "metadata": {
+                    "column_names": ["price"], "result_set_bytes": 8, "total_row_count": 1,
+                    "datapoint_count": 1, "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        });
+        let results: GetResultResponse<Value> = serde_json::from_value(json).unwrap();
+        let cached = serde_json::to_string(&results).unwrap();
+        let restored: GetResultResponse<Value> = serde_json::from_str(&cached).unwrap();
+        assert_eq!(restored, results);
+        assert_eq!(restored.clone().state, ExecutionStatus::Failed);
+        assert_eq!(
+            serde_json::to_value(ExecutionStatus::Unknown("QUERY_STATE_NEW".to_string())).unwrap(),
+            "QUERY_STATE_NEW"
+        );
+    }
+
+    #[test]
+    fn flat_timestamps() {
+        let json = serde_json::json!({
+            "execution_id": "jerb",
+            "query_id": 1,
+            "state": "QUERY_STATE_COMPLETED",
+            "submitted_at": "2024-01-01T00:00:00.0Z",
+            "expires_at": "2024-04-01T00:00:00.0Z",
+            "execution_started_at": "2024-01-01T00:00:01.0Z",
+            "execution_ended_at": "2024-01-01T00:00:05.0Z",
+            "cancelled_at": null,
+            "queue_position": null,
+            "result_metadata": null
+        });
+        let status: GetStatusResponse = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(status.submitted_at(), status.times.submitted_at);
+        assert_eq!(
+            status.expires_at(),
+            date_parse("2024-04-01T00:00:00.0Z").ok()
+        );
+        assert_eq!(
+            status.execution_ended_at(),
+            date_parse("2024-01-01T00:00:05.0Z").ok()
+        );
+        assert_eq!(status.cancelled_at(), None);
+
+        let serialized = serde_json::to_value(&status).unwrap();
+        assert!(serialized.get("times").is_none());
+        assert_eq!(
+            serialized["execution_started_at"],
+            "2024-01-01T00:00:01.000Z"
+        );
+    }
+
+    #[test]
+    fn column_types() {
+        let parse = |name: &str| name.parse::<DuneColumnType>().unwrap();
+        assert_eq!(parse("VARCHAR"), DuneColumnType::Varchar);
+        assert_eq!(parse("varchar(42)"), DuneColumnType::Varchar);
+        assert_eq!(
+            parse("decimal(38, 0)"),
+            DuneColumnType::Decimal {
+                precision: 38,
+                scale: 0
+            }
+        );
+        assert_eq!(
+            parse("timestamp(3) with time zone"),
+            DuneColumnType::Timestamp {
+                precision: Some(3),
+                with_time_zone: true
+            }
+        );
+        assert_eq!(
+            parse("map(varchar, array(double))"),
+            DuneColumnType::Map(
+                Box::new(DuneColumnType::Varchar),
+                Box::new(DuneColumnType::Array(Box::new(DuneColumnType::Double)))
+            )
+        );
+        assert_eq!(
+            parse("row(a varchar)"),
+            DuneColumnType::Other("row(a varchar)".to_string())
+        );
+        assert_eq!(
+            parse("timestamp(x)"),
+            DuneColumnType::Other("timestamp(x)".to_string())
+        );
+
+        for name in [
+            "bigint",
+            "decimal(38,0)",
+            "timestamp(3) with time zone",
+            "array(varbinary)",
+            "map(varchar, double)",
+            "row(a varchar)",
+        ] {
+            assert_eq!(parse(name).to_string(), name);
+        }
+        assert_eq!(
+            serde_json::to_value(vec![parse("uint256")]).unwrap(),
+            serde_json::json!(["uint256"])
+        );
+
+        assert_eq!(parse("bigint").rust_type(), "i64");
+        assert_eq!(parse("uint256").rust_type(), "String");
+        assert_eq!(
+            parse("map(varchar, bigint)").rust_type(),
+            "std::collections::HashMap<String, i64>"
+        );
+        assert_eq!(parse("row(a varchar)").rust_type(), "serde_json::Value");
+        assert!(parse("decimal(38,0)").is_numeric());
+        assert!(!parse("varchar").is_numeric());
+        assert!(parse("date").is_temporal());
+    }
+
+    #[test]
+    fn dynamic_rows() {
+        let results: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+            "rows": [{"symbol": "ETH", "max_price": 3000.5}, {"symbol": "BTC"}],
+            "metadata": {
+                "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+                "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+                "pending_time_millis": null, "execution_time_millis": 1
+            }
+        }))
+        .unwrap();
+        let metadata = &results.metadata;
+        assert_eq!(
+            metadata.columns().collect::<Vec<_>>(),
+            vec![
+                ("symbol", Some(&DuneColumnType::Varchar)),
+                ("max_price", Some(&DuneColumnType::Double))
+            ]
+        );
+        assert_eq!(
+            metadata.ordered(&results.rows[0]),
+            vec![&Value::from("ETH"), &Value::from(3000.5)]
+        );
+        assert_eq!(
+            metadata.ordered(&results.rows[1]),
+            vec![&Value::from("BTC"), &Value::Null]
+        );
+    }
+
+    #[cfg(feature = "pretty")]
+    #[test]
+    fn table_rendering() {
+        let long = "x".repeat(60);
+        let results: GetResultResponse<Value> = serde_json::from_value(serde_json::json!({
+            "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "result": {
+                "rows": [{"note": long, "n": null}, {"note": "two\nlines", "n": 7}],
+                "metadata": {
+                    "column_names": ["note", "n"], "result_set_bytes": 0,
+                    "total_row_count": 2, "datapoint_count": 4,
+                    "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        }))
+        .unwrap();
+        let table = results.to_table_string(10);
+        assert!(table.contains(&format!("| {}... | NULL |", "x".repeat(48))));
+        assert!(table.contains("| two lines "));
+        assert!(!table.contains("more row"));
+        assert!(results.to_table_string(0).ends_with("+\n... 2 more rows"));
+    }
+
+    #[test]
+    fn row_count_issues() {
+        let results = |rows: Value, row_count: Value, next_offset: Value| {
+            serde_json::from_value::<GetResultResponse<Value>>(serde_json::json!({
+                "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+                "submitted_at": "2024-01-01T00:00:00.000Z", "next_offset": next_offset,
+                "result": {
+                    "rows": rows,
+                    "metadata": {
+                        "column_names": ["n"], "row_count": row_count, "result_set_bytes": 0,
+                        "total_row_count": 2, "datapoint_count": 2,
+                        "pending_time_millis": null, "execution_time_millis": 1
+                    }
+                }
+            }))
+            .unwrap()
+        };
+        let two = serde_json::json!([{"n": 1}, {"n": 2}]);
+        assert_eq!(
+            results(two.clone(), Value::Null, Value::Null).row_count_issue(),
+            None
+        );
+        assert_eq!(
+            results(two.clone(), 2.into(), Value::Null).row_count_issue(),
+            None
+        );
+        assert_eq!(
+            results(two, 3.into(), Value::Null).row_count_issue(),
+            Some(RowCountIssue::Mismatch {
+                rows: 2,
+                expected: 3
+            })
+        );
+        let one = serde_json::json!([{"n": 1}]);
+        let truncated = results(one.clone(), 1.into(), Value::Null);
+        assert_eq!(
+            truncated.row_count_issue(),
+            Some(RowCountIssue::Mismatch {
+                rows: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            truncated.check_row_count().unwrap_err().to_string(),
+            "incomplete results: got 1 rows, the metadata reports 2"
+        );
+        assert_eq!(
+            results(one.clone(), 1.into(), 1.into()).row_count_issue(),
+            Some(RowCountIssue::MorePages {
+                rows: 1,
+                total_row_count: 2,
+                next_offset: 1
+            })
+        );
+        let mut partial = results(one, 1.into(), 1.into());
+        partial.partial = true;
+        assert_eq!(
+            partial.row_count_issue(),
+            Some(RowCountIssue::Partial {
+                rows: 1,
+                total_row_count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn csv_export() {
+        let results: GetResultResponse<DynamicRow> = serde_json::from_value(serde_json::json!({
+            "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "result": {
+                "rows": [
+                    {"z": "say \"hi\"", "a": true, "tags": ["x", "y"]},
+                    {"z": "two\nlines", "a": 1}
+                ],
+                "metadata": {
+                    "column_names": ["z", "a", "tags"], "result_set_bytes": 0,
+                    "total_row_count": 2, "datapoint_count": 6,
+                    "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        }))
+        .unwrap();
+        let mut csv = Vec::new();
+        results.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "z,a,tags\n\"say \"\"hi\"\"\",true,\"[\"\"x\"\",\"\"y\"\"]\"\n\"two\nlines\",1,\n"
+        );
+    }
+
+    #[test]
+    fn lossy_rows() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            price: f64,
+        }
+        let results: GetResultResponse<Value> = serde_json::from_value(serde_json::json!({
+            "execution_id": "jerb",
+            "query_id": 1,
+            "state": "QUERY_STATE_COMPLETED",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "result": {
+                "rows": [{"price": 1.5}, {"price": "n/a"}, {"price": 2.5}],
+                "metadata": {
+                    "column_names": ["price"], "result_set_bytes": 0, "total_row_count": 3,
+                    "datapoint_count": 3, "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        }))
+        .unwrap();
+        let lossy = results.get_rows_lossy::<Row>();
+        assert_eq!(lossy.rows, vec![Row { price: 1.5 }, Row { price: 2.5 }]);
+        assert_eq!(lossy.invalid.len(), 1);
+        let invalid = &lossy.invalid[0];
+        assert_eq!(invalid.index, 1);
+        assert_eq!(invalid.raw, serde_json::json!({"price": "n/a"}));
+        match &invalid.error {
+            DuneRequestError::Deserialization {
+                row, column, value, ..
+            } => {
+                assert_eq!(*row, Some(1));
+                assert_eq!(column.as_deref(), Some("price"));
+                assert_eq!(value.as_deref(), Some("\"n/a\""));
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
     #[test]
     fn derive_debug() {
         assert_eq!(
@@ -259,10 +2239,12 @@ mod tests {
                 "{:?}",
                 ExecutionResponse {
                     execution_id: "jerb".to_string(),
-                    state: ExecutionStatus::Failed
+                    state: ExecutionStatus::Failed,
+                    rate_limit: None,
+                    request_id: None,
                 }
             ),
-            "ExecutionResponse { execution_id: \"jerb\", state: Failed }"
+            "ExecutionResponse { execution_id: \"jerb\", state: Failed, rate_limit: None, request_id: None }"
         );
         assert_eq!(
             format!("{:?}", CancellationResponse { success: false }),
@@ -297,6 +2279,10 @@ mod tests {
                         pending_time_millis: None,
                         execution_time_millis: 0,
                     }),
+                    execution_cost_credits: None,
+                    error: None,
+                    rate_limit: None,
+                    request_id: None,
                 }
             ),
             "GetStatusResponse { \
@@ -321,8 +2307,12 @@ mod tests {
                         datapoint_count: 0, \
                         pending_time_millis: None, \
                         execution_time_millis: 0 \
-                }\
-             ) }",
+                }), \
+                execution_cost_credits: None, \
+                error: None, \
+                rate_limit: None, \
+                request_id: None \
+            }",
         );
         assert_eq!(
             format!(
@@ -353,6 +2343,12 @@ mod tests {
                             execution_time_millis: 0,
                         }
                     },
+                    next_offset: None,
+                    next_uri: None,
+                    partial: false,
+                    error: None,
+                    rate_limit: None,
+                    request_id: None,
                 }
             ),
             "GetResultResponse { \
@@ -380,7 +2376,13 @@ mod tests {
                         pending_time_millis: None, \
                         execution_time_millis: 0 \
                     } \
-                } \
+                }, \
+                error: None, \
+                next_offset: None, \
+                next_uri: None, \
+                partial: false, \
+                rate_limit: None, \
+                request_id: None \
             }",
         );
     }
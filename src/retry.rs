@@ -0,0 +1,233 @@
+//! Retrying failed requests.
+//!
+//! Every failed request (an error response other than 429, or no response at all) is shown to
+//! the client's [`RetryPolicy`], which decides whether and when to try again. The default,
+//! [`ExponentialBackoff`], retries idempotent requests (status polls, result downloads, query
+//! reads, …) that failed with a 5xx response, a connection error or a timeout. Executions and
+//! other writes are not retried by it, so a flaky network cannot start an execution twice.
+//! Rate-limited requests (429) are handled separately, see
+//! [`rate_limit_retries`](crate::client::DuneClientBuilder::rate_limit_retries).
+
+use reqwest::{Method, StatusCode, Url};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Decides whether a failed request is retried.
+///
+/// Plug a policy into the client with
+/// [`DuneClientBuilder::retry`](crate::client::DuneClientBuilder::retry).
+///
+/// # Example
+///
+/// A budget of retries shared by all requests, so an outage does not multiply the load:
+///
+/// ```rust
+/// use duners::retry::{ExponentialBackoff, FailedAttempt, RetryPolicy};
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::time::Duration;
+///
+/// struct RetryBudget {
+///     remaining: AtomicU32,
+///     backoff: ExponentialBackoff,
+/// }
+///
+/// impl RetryPolicy for RetryBudget {
+///     fn retry_delay(&self, attempt: &FailedAttempt<'_>) -> Option<Duration> {
+///         let delay = self.backoff.retry_delay(attempt)?;
+///         self.remaining
+///             .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+///             .ok()
+///             .map(|_| delay)
+///     }
+/// }
+/// ```
+pub trait RetryPolicy: Send + Sync {
+    /// How long to wait before retrying `attempt`, or `None` to give up and return the failure.
+    fn retry_delay(&self, attempt: &FailedAttempt<'_>) -> Option<Duration>;
+}
+
+/// A request that just failed, as seen by a [`RetryPolicy`].
+#[derive(Debug)]
+pub struct FailedAttempt<'a> {
+    /// Request method.
+    pub method: &'a Method,
+    /// Request URL, e.g. to treat endpoints differently.
+    pub url: &'a Url,
+    /// How often this request was already retried.
+    pub retries: u32,
+    /// Response status, if a response arrived.
+    pub status: Option<StatusCode>,
+    /// The error, if no response arrived (connection failure, timeout, …).
+    pub error: Option<&'a reqwest::Error>,
+}
+
+impl FailedAttempt<'_> {
+    /// Whether repeating the request is harmless (`GET`, `PUT`, `DELETE`, …).
+    pub fn is_idempotent(&self) -> bool {
+        self.method.is_idempotent()
+    }
+
+    /// Whether the failure is likely temporary: a 5xx or 408 response, a connection error or a
+    /// timeout.
+    pub fn is_transient(&self) -> bool {
+        match (self.status, self.error) {
+            (Some(status), _) => status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT,
+            (None, Some(err)) => err.is_timeout() || err.is_connect() || err.is_request(),
+            (None, None) => false,
+        }
+    }
+}
+
+/// Retries with exponentially growing delays: `base_delay`, `2 × base_delay`, `4 × base_delay`, …
+/// capped at `max_delay`.
+///
+/// With `jitter`, each delay is scaled by a random factor between ½ and 1, so many clients that
+/// failed together do not retry in lockstep.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use duners::client::DuneClient;
+/// use duners::retry::ExponentialBackoff;
+/// use std::time::Duration;
+///
+/// let client = DuneClient::builder()
+///     .retry(ExponentialBackoff {
+///         max_retries: 5,
+///         base_delay: Duration::from_secs(1),
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialBackoff {
+    /// How often a failed request is retried (`0` disables retries).
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Upper bound on the delay.
+    pub max_delay: Duration,
+    /// Whether delays are randomized.
+    pub jitter: bool,
+}
+
+impl Default for ExponentialBackoff {
+    /// 3 retries, starting at 500 ms, at most 30 seconds apart, with jitter.
+    fn default() -> Self {
+        ExponentialBackoff {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    /// Never retries.
+    pub fn none() -> Self {
+        ExponentialBackoff {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before retry number `attempt` (starting at 1), without jitter.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Delay before retry number `attempt` (starting at 1), with jitter if enabled.
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.backoff(attempt);
+        match self.jitter {
+            true => delay.mul_f64(0.5 + 0.5 * random_fraction()),
+            false => delay,
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    /// Retries transient failures of idempotent requests, up to `max_retries` times.
+    fn retry_delay(&self, attempt: &FailedAttempt<'_>) -> Option<Duration> {
+        (attempt.is_idempotent() && attempt.is_transient() && attempt.retries < self.max_retries)
+            .then(|| self.delay(attempt.retries + 1))
+    }
+}
+
+/// A number in `[0, 1)`, random enough to spread out retries.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = ExponentialBackoff {
+            max_retries: 10,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            jitter: false,
+        };
+        let delays: Vec<_> = (1..=5)
+            .map(|attempt| policy.delay(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(10));
+
+        let jittered = ExponentialBackoff {
+            jitter: true,
+            ..policy
+        };
+        for attempt in 1..=5 {
+            let delay = jittered.delay(attempt);
+            assert!(delay <= policy.backoff(attempt));
+            assert!(delay >= policy.backoff(attempt) / 2);
+        }
+    }
+
+    #[test]
+    fn backoff_policy() {
+        let policy = ExponentialBackoff {
+            jitter: false,
+            ..Default::default()
+        };
+        let url = Url::parse("https://api.dune.com/api/v1/execution/jerb/status").unwrap();
+        let attempt = |method, status, retries| FailedAttempt {
+            method,
+            url: &url,
+            retries,
+            status: Some(status),
+            error: None,
+        };
+        assert_eq!(
+            policy.retry_delay(&attempt(&Method::GET, StatusCode::BAD_GATEWAY, 1)),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.retry_delay(&attempt(&Method::GET, StatusCode::BAD_GATEWAY, 3)),
+            None
+        );
+        assert_eq!(
+            policy.retry_delay(&attempt(&Method::POST, StatusCode::BAD_GATEWAY, 0)),
+            None
+        );
+        assert_eq!(
+            policy.retry_delay(&attempt(&Method::GET, StatusCode::BAD_REQUEST, 0)),
+            None
+        );
+    }
+}
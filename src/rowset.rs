@@ -0,0 +1,527 @@
+//! Untyped results with typed access to individual values.
+//!
+//! A [`RowSet`] sits between deserializing every row into a struct and handling raw JSON: rows
+//! are kept as [`DynamicRow`]s and values are converted when read, with `row.get::<f64>("price")`
+//! or `rows.column::<String>("symbol")`. Conversions use the result's `column_types`, so numbers
+//! and timestamps Dune sends as strings still read as `f64`, `u64` or `DateTime<Utc>`.
+//! [`RowSet::column_stats`] summarizes every column (nulls, min, max, mean) to sanity-check
+//! results before loading them further.
+
+use crate::error::DuneRequestError;
+use crate::parse_utils::{bool_from_value, parse_any_datetime};
+use crate::response::{DuneColumnType, DynamicRow, ExecutionResult, GetResultResponse};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+
+/// Result rows of unknown schema with typed accessors.
+///
+/// # Example
+///
+/// ```no_run
+/// use duners::response::DynamicRow;
+/// use duners::rowset::RowSet;
+/// use duners::{DuneClient, DuneRequestError};
+///
+/// # async fn run() -> Result<(), DuneRequestError> {
+/// let client = DuneClient::from_env();
+/// let rows = RowSet::from(client.refresh::<DynamicRow>(971694, None, None).await?);
+/// let symbols = rows.column::<String>("symbol")?;
+/// for row in rows.iter() {
+///     let price: f64 = row.get("max_price")?;
+///     println!("{} {price}", row.get::<String>("symbol")?);
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowSet {
+    columns: Vec<String>,
+    types: Vec<Option<DuneColumnType>>,
+    rows: Vec<DynamicRow>,
+}
+
+impl RowSet {
+    /// Builds a row set from untyped rows and the metadata describing their columns.
+    pub fn new(result: ExecutionResult<DynamicRow>) -> Self {
+        let types = result
+            .metadata
+            .columns()
+            .map(|(_, column_type)| column_type.cloned())
+            .collect();
+        RowSet {
+            columns: result.metadata.column_names,
+            types,
+            rows: result.rows,
+        }
+    }
+
+    /// A row set of `rows` with the given columns and their types.
+    pub(crate) fn from_parts(
+        columns: Vec<String>,
+        types: Vec<Option<DuneColumnType>>,
+        rows: Vec<DynamicRow>,
+    ) -> Self {
+        RowSet {
+            columns,
+            types,
+            rows,
+        }
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Column names in query order.
+    pub fn column_names(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Dune type of `column` (e.g. `double`, `varchar`), if the API reported it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use duners::response::{DuneColumnType, DynamicRow, ExecutionResult};
+    /// # use duners::rowset::RowSet;
+    /// # let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+    /// #     "rows": [{"symbol": "ETH", "max_price": "3000.5"}, {"symbol": "BTC", "max_price": 61000}],
+    /// #     "metadata": {
+    /// #         "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    /// #         "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    /// #         "pending_time_millis": null, "execution_time_millis": 1
+    /// #     }
+    /// # })).unwrap();
+    /// let rows = RowSet::new(result);
+    /// assert_eq!(rows.column_type("max_price"), Some(&DuneColumnType::Double));
+    /// assert_eq!(rows.column_type("missing"), None);
+    /// ```
+    pub fn column_type(&self, column: &str) -> Option<&DuneColumnType> {
+        let position = self.columns.iter().position(|name| name == column)?;
+        self.types.get(position)?.as_ref()
+    }
+
+    /// Row `index`, if there is one.
+    pub fn row(&self, index: usize) -> Option<Row<'_>> {
+        self.rows.get(index).map(|values| Row {
+            set: self,
+            index,
+            values,
+        })
+    }
+
+    /// Iterates over the rows in order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use duners::response::{DynamicRow, ExecutionResult};
+    /// # use duners::rowset::RowSet;
+    /// # let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+    /// #     "rows": [{"symbol": "ETH", "max_price": "3000.5"}, {"symbol": "BTC", "max_price": 61000}],
+    /// #     "metadata": {
+    /// #         "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    /// #         "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    /// #         "pending_time_millis": null, "execution_time_millis": 1
+    /// #     }
+    /// # })).unwrap();
+    /// let rows = RowSet::new(result);
+    /// let expensive: Vec<String> = rows
+    ///     .iter()
+    ///     .filter(|row| row.get::<f64>("max_price").unwrap() > 10_000.0)
+    ///     .map(|row| row.get("symbol").unwrap())
+    ///     .collect();
+    /// assert_eq!(expensive, vec!["BTC"]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Row<'_>> {
+        self.rows.iter().enumerate().map(|(index, values)| Row {
+            set: self,
+            index,
+            values,
+        })
+    }
+
+    /// All values of `column` as `T`, failing on the first value that does not convert.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use duners::response::{DynamicRow, ExecutionResult};
+    /// # use duners::rowset::RowSet;
+    /// # let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+    /// #     "rows": [{"symbol": "ETH", "max_price": "3000.5"}, {"symbol": "BTC", "max_price": 61000}],
+    /// #     "metadata": {
+    /// #         "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    /// #         "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    /// #         "pending_time_millis": null, "execution_time_millis": 1
+    /// #     }
+    /// # })).unwrap();
+    /// let rows = RowSet::new(result);
+    /// // "3000.5" is converted because the column is a `double`.
+    /// assert_eq!(rows.column::<f64>("max_price").unwrap(), vec![3000.5, 61000.0]);
+    /// assert!(rows.column::<f64>("symbol").is_err());
+    /// ```
+    pub fn column<T: DeserializeOwned>(&self, column: &str) -> Result<Vec<T>, DuneRequestError> {
+        self.iter().map(|row| row.get(column)).collect()
+    }
+
+    /// Statistics of every column, in query order.
+    ///
+    /// Values are ordered by the column's Dune type: numerically for numeric columns (numbers
+    /// Dune sends as strings included), by instant for dates and timestamps, and as text
+    /// otherwise. Columns without a reported type are numeric when all their values are JSON
+    /// numbers. Values that do not convert to the column's type count as values but are left out
+    /// of `min`, `max` and `mean`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use duners::response::{DynamicRow, ExecutionResult};
+    /// # use duners::rowset::RowSet;
+    /// # use serde_json::json;
+    /// # let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+    /// #     "rows": [
+    /// #         {"symbol": "ETH", "max_price": "3000.5"},
+    /// #         {"symbol": "BTC", "max_price": 61000},
+    /// #         {"symbol": "DAI", "max_price": null}
+    /// #     ],
+    /// #     "metadata": {
+    /// #         "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    /// #         "result_set_bytes": 0, "total_row_count": 3, "datapoint_count": 6,
+    /// #         "pending_time_millis": null, "execution_time_millis": 1
+    /// #     }
+    /// # })).unwrap();
+    /// let rows = RowSet::new(result);
+    /// let stats = rows.column_stats();
+    /// assert_eq!(stats[0].min, Some(json!("BTC")));
+    /// assert_eq!(stats[0].mean, None);
+    /// let price = &stats[1];
+    /// assert_eq!((price.count, price.null_count), (2, 1));
+    /// assert_eq!(price.min, Some(json!("3000.5")));
+    /// assert_eq!(price.max, Some(json!(61000)));
+    /// assert_eq!(price.mean, Some(32000.25));
+    /// ```
+    pub fn column_stats(&self) -> Vec<ColumnStats> {
+        self.columns
+            .iter()
+            .zip(&self.types)
+            .map(|(column, column_type)| self.stats_of(column, column_type.as_ref()))
+            .collect()
+    }
+
+    fn stats_of(&self, column: &str, column_type: Option<&DuneColumnType>) -> ColumnStats {
+        let values: Vec<&Value> = self
+            .rows
+            .iter()
+            .filter_map(|row| row.get(column))
+            .filter(|value| !value.is_null())
+            .collect();
+        let mut stats = ColumnStats {
+            column: column.to_string(),
+            count: values.len(),
+            null_count: self.rows.len() - values.len(),
+            min: None,
+            max: None,
+            mean: None,
+        };
+        let numeric = match column_type {
+            Some(column_type) => column_type.is_numeric(),
+            None => !values.is_empty() && values.iter().all(|value| value.is_number()),
+        };
+        let keyed: Vec<(SortKey, &Value)> = values
+            .iter()
+            .filter_map(|value| Some((sort_key(value, column_type, numeric)?, *value)))
+            .collect();
+        let by_key = |a: &&(SortKey, &Value), b: &&(SortKey, &Value)| a.0.cmp_to(&b.0);
+        stats.min = keyed
+            .iter()
+            .min_by(by_key)
+            .map(|(_, value)| (*value).clone());
+        stats.max = keyed
+            .iter()
+            .max_by(by_key)
+            .map(|(_, value)| (*value).clone());
+        if numeric && !keyed.is_empty() {
+            let numbers = keyed.iter().filter_map(|(key, _)| match key {
+                SortKey::Number(number) => Some(number),
+                _ => None,
+            });
+            stats.mean = Some(numbers.sum::<f64>() / keyed.len() as f64);
+        }
+        stats
+    }
+
+    /// The underlying rows, borrowed.
+    pub fn rows(&self) -> &[DynamicRow] {
+        &self.rows
+    }
+
+    /// The underlying rows.
+    pub fn into_rows(self) -> Vec<DynamicRow> {
+        self.rows
+    }
+}
+
+impl From<ExecutionResult<DynamicRow>> for RowSet {
+    fn from(result: ExecutionResult<DynamicRow>) -> Self {
+        RowSet::new(result)
+    }
+}
+
+impl From<GetResultResponse<DynamicRow>> for RowSet {
+    fn from(response: GetResultResponse<DynamicRow>) -> Self {
+        RowSet::new(response.result)
+    }
+}
+
+/// One row of a [`RowSet`].
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    set: &'a RowSet,
+    index: usize,
+    values: &'a DynamicRow,
+}
+
+impl<'a> Row<'a> {
+    /// Position of the row in its [`RowSet`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The raw JSON value of `column` (`None` if the row has no such column).
+    pub fn raw(&self, column: &str) -> Option<&'a Value> {
+        self.values.get(column)
+    }
+
+    /// The value of `column` as `T`.
+    ///
+    /// The JSON value is tried as is first; if that fails, strings are converted according to
+    /// the column's Dune type (numbers, timestamps, booleans) and tried again. A missing column
+    /// reads as `null`, so it converts to `Option<T>` as `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use duners::response::{DynamicRow, ExecutionResult};
+    /// # use duners::rowset::RowSet;
+    /// # let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+    /// #     "rows": [{"symbol": "ETH", "max_price": "3000.5"}, {"symbol": "BTC", "max_price": 61000}],
+    /// #     "metadata": {
+    /// #         "column_names": ["symbol", "max_price"], "column_types": ["varchar", "double"],
+    /// #         "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 4,
+    /// #         "pending_time_millis": null, "execution_time_millis": 1
+    /// #     }
+    /// # })).unwrap();
+    /// let rows = RowSet::new(result);
+    /// let eth = rows.row(0).unwrap();
+    /// assert_eq!(eth.get::<String>("symbol").unwrap(), "ETH");
+    /// assert_eq!(eth.get::<f64>("max_price").unwrap(), 3000.5);
+    /// assert_eq!(eth.get::<Option<u64>>("volume").unwrap(), None);
+    /// ```
+    pub fn get<T: DeserializeOwned>(&self, column: &str) -> Result<T, DuneRequestError> {
+        let value = self.values.get(column).unwrap_or(&Value::Null);
+        let error = match serde_json::from_value(value.clone()) {
+            Ok(converted) => return Ok(converted),
+            Err(err) => err,
+        };
+        if let Some(coerced) = coerce(value, self.set.column_type(column)) {
+            if let Ok(converted) = serde_json::from_value(coerced) {
+                return Ok(converted);
+            }
+        }
+        let source = error.to_string();
+        Err(DuneRequestError::Deserialization {
+            row: Some(self.index),
+            column: Some(column.to_string()),
+            value: Some(value.to_string()),
+            expected: Some(std::any::type_name::<T>().to_string()),
+            source,
+            context: Box::default(),
+        })
+    }
+}
+
+/// Summary of one column of a [`RowSet`], from [`RowSet::column_stats`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    /// Column name.
+    pub column: String,
+    /// Number of non-null values.
+    pub count: usize,
+    /// Number of `null` (or missing) values.
+    pub null_count: usize,
+    /// Smallest value, as it appears in the result.
+    pub min: Option<Value>,
+    /// Largest value, as it appears in the result.
+    pub max: Option<Value>,
+    /// Average of the values of a numeric column.
+    pub mean: Option<f64>,
+}
+
+/// What the values of a column are ordered by.
+enum SortKey {
+    Number(f64),
+    Instant(chrono::DateTime<chrono::Utc>),
+    Text(String),
+}
+
+impl SortKey {
+    fn cmp_to(&self, other: &SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Instant(a), SortKey::Instant(b)) => a.cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            // Keys of one column are all of one kind.
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// The key `value` is ordered by, `None` if it does not convert to the column's type.
+fn sort_key(value: &Value, column_type: Option<&DuneColumnType>, numeric: bool) -> Option<SortKey> {
+    if numeric {
+        let number = match value {
+            Value::Number(number) => number.as_f64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        };
+        return number
+            .filter(|number: &f64| number.is_finite())
+            .map(SortKey::Number);
+    }
+    if column_type.is_some_and(DuneColumnType::is_temporal) {
+        return parse_any_datetime(value.as_str()?)
+            .ok()
+            .map(SortKey::Instant);
+    }
+    Some(SortKey::Text(match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }))
+}
+
+/// Converts a string `value` into the JSON value its Dune `column_type` stands for.
+pub(crate) fn coerce(value: &Value, column_type: Option<&DuneColumnType>) -> Option<Value> {
+    let text = value.as_str()?.trim();
+    let column_type = column_type?;
+    if column_type.is_numeric() {
+        let number = match (text.parse::<i64>(), text.parse::<u64>()) {
+            (Ok(signed), _) => Number::from(signed),
+            (_, Ok(unsigned)) => Number::from(unsigned),
+            _ => Number::from_f64(text.parse().ok()?)?,
+        };
+        Some(Value::Number(number))
+    } else if column_type.is_temporal() {
+        let parsed = parse_any_datetime(text).ok()?;
+        Some(Value::String(parsed.to_rfc3339()))
+    } else if *column_type == DuneColumnType::Boolean {
+        bool_from_value(value).ok().map(Value::Bool)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    fn rows() -> RowSet {
+        let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+            "rows": [
+                {"symbol": "ETH", "max_price": "3000.5", "volume": 12, "day": "2024-01-01 00:00:00.000 UTC"},
+                {"symbol": "BTC", "max_price": 61000, "volume": null, "day": "2024-01-02 00:00:00.000 UTC"}
+            ],
+            "metadata": {
+                "column_names": ["symbol", "max_price", "volume", "day"],
+                "column_types": ["varchar", "double", "bigint", "timestamp(3) with time zone"],
+                "result_set_bytes": 0, "total_row_count": 2, "datapoint_count": 8,
+                "pending_time_millis": null, "execution_time_millis": 1
+            }
+        }))
+        .unwrap();
+        RowSet::from(result)
+    }
+
+    #[test]
+    fn typed_access() {
+        let rows = rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows.column_type("max_price"), Some(&DuneColumnType::Double));
+        assert_eq!(
+            rows.column::<String>("symbol").unwrap(),
+            vec!["ETH".to_string(), "BTC".to_string()]
+        );
+        assert_eq!(
+            rows.column::<f64>("max_price").unwrap(),
+            vec![3000.5, 61000.0]
+        );
+        assert_eq!(
+            rows.column::<Option<u64>>("volume").unwrap(),
+            vec![Some(12), None]
+        );
+        let day: DateTime<Utc> = rows.row(1).unwrap().get("day").unwrap();
+        assert_eq!(day.to_rfc3339(), "2024-01-02T00:00:00+00:00");
+    }
+
+    #[test]
+    fn column_statistics() {
+        let stats = rows().column_stats();
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats[0].min, Some(Value::from("BTC")));
+        assert_eq!(stats[0].max, Some(Value::from("ETH")));
+        let volume = &stats[2];
+        assert_eq!((volume.count, volume.null_count), (1, 1));
+        assert_eq!(volume.mean, Some(12.0));
+        let day = &stats[3];
+        assert_eq!(day.min, Some(Value::from("2024-01-01 00:00:00.000 UTC")));
+        assert_eq!(day.max, Some(Value::from("2024-01-02 00:00:00.000 UTC")));
+        assert_eq!(day.mean, None);
+
+        // Untyped columns are numeric when every value is a number.
+        let result: ExecutionResult<DynamicRow> = serde_json::from_value(serde_json::json!({
+            "rows": [{"a": 10, "b": "x"}, {"a": 2, "b": 1}, {"b": "abc"}],
+            "metadata": {
+                "column_names": ["a", "b"], "result_set_bytes": 0, "total_row_count": 3,
+                "datapoint_count": 5, "pending_time_millis": null, "execution_time_millis": 1
+            }
+        }))
+        .unwrap();
+        let stats = RowSet::new(result).column_stats();
+        assert_eq!(stats[0].null_count, 1);
+        assert_eq!(
+            (stats[0].min.clone(), stats[0].mean),
+            (Some(2.into()), Some(6.0))
+        );
+        assert_eq!(stats[1].min, Some(1.into()));
+        assert_eq!(stats[1].max, Some("x".into()));
+        assert_eq!(stats[1].mean, None);
+    }
+
+    #[test]
+    fn conversion_errors() {
+        let rows = rows();
+        match rows.row(1).unwrap().get::<u64>("symbol").unwrap_err() {
+            DuneRequestError::Deserialization {
+                row,
+                column,
+                value,
+                expected,
+                ..
+            } => {
+                assert_eq!(row, Some(1));
+                assert_eq!(column.as_deref(), Some("symbol"));
+                assert_eq!(value.as_deref(), Some("\"BTC\""));
+                assert_eq!(expected.as_deref(), Some("u64"));
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Checking result columns against a row type before deserializing.
+//!
+//! A row struct declares the columns it reads by implementing [`RowSchema`]. Comparing that with
+//! a result's [`ResultMetaData`] reports every column the struct expects but the query does not
+//! return, every returned column the struct does not read, and every column whose Dune type
+//! cannot fill the field, instead of the first serde error on the first row.
+
+use crate::response::{DuneColumnType, ResultMetaData};
+use std::fmt;
+
+/// A row type that declares the columns it reads.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::schema::{FieldKind, FieldSpec, RowSchema};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     symbol: String,
+///     max_price: f64,
+///     volume: Option<u64>,
+/// }
+///
+/// impl RowSchema for Row {
+///     fn schema() -> Vec<FieldSpec> {
+///         vec![
+///             FieldSpec::new("symbol", FieldKind::String),
+///             FieldSpec::new("max_price", FieldKind::Float),
+///             FieldSpec::new("volume", FieldKind::Integer).optional(),
+///         ]
+///     }
+/// }
+/// ```
+pub trait RowSchema {
+    /// The columns the row type reads, by their names in the result (i.e. after serde renames).
+    fn schema() -> Vec<FieldSpec>;
+}
+
+/// One column a [`RowSchema`] reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpec {
+    /// Column name in the result.
+    pub column: String,
+    /// What the field can hold.
+    pub kind: FieldKind,
+    /// Whether the column may be absent from the result (e.g. an `Option` or
+    /// `#[serde(default)]` field).
+    pub optional: bool,
+}
+
+impl FieldSpec {
+    /// A column that must be present in the result.
+    pub fn new(column: &str, kind: FieldKind) -> Self {
+        FieldSpec {
+            column: column.to_string(),
+            kind,
+            optional: false,
+        }
+    }
+
+    /// Allows the column to be absent from the result.
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+}
+
+/// The kind of value a row field holds, matched against the [`DuneColumnType`] of its column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    /// `String`: text, but also bytes (`0x…`), 256-bit integers, decimals, dates and timestamps,
+    /// which Dune sends as strings.
+    String,
+    /// An integer type (`i64`, `u64`, …).
+    Integer,
+    /// A floating point type (`f64`), filled by any numeric column.
+    Float,
+    /// `bool`.
+    Bool,
+    /// A date or timestamp (e.g. `DateTime<Utc>` with
+    /// [`datetime_from_str`](crate::parse_utils::datetime_from_str)).
+    Timestamp,
+    /// A `Vec` of the inner kind.
+    Array(Box<FieldKind>),
+    /// Any column type (e.g. `serde_json::Value`).
+    Any,
+}
+
+impl FieldKind {
+    /// Whether a column of `column_type` can fill a field of this kind. Column types this crate
+    /// does not know are always accepted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use duners::response::DuneColumnType;
+    /// use duners::schema::FieldKind;
+    ///
+    /// assert!(FieldKind::Float.accepts(&DuneColumnType::Bigint));
+    /// assert!(!FieldKind::Integer.accepts(&DuneColumnType::Double));
+    /// assert!(FieldKind::String.accepts(&DuneColumnType::Varbinary));
+    /// ```
+    pub fn accepts(&self, column_type: &DuneColumnType) -> bool {
+        use DuneColumnType as Column;
+        match (self, column_type) {
+            (FieldKind::Any, _) | (_, Column::Other(_)) => true,
+            (FieldKind::String, column_type) => matches!(
+                column_type,
+                Column::Varchar
+                    | Column::Varbinary
+                    | Column::Uint256
+                    | Column::Int256
+                    | Column::Decimal { .. }
+                    | Column::Date
+                    | Column::Timestamp { .. }
+                    | Column::Json
+            ),
+            (FieldKind::Integer, column_type) => matches!(
+                column_type,
+                Column::Tinyint
+                    | Column::Smallint
+                    | Column::Integer
+                    | Column::Bigint
+                    | Column::Uint256
+                    | Column::Int256
+                    | Column::Decimal { scale: 0, .. }
+            ),
+            (FieldKind::Float, column_type) => column_type.is_numeric(),
+            (FieldKind::Bool, column_type) => *column_type == Column::Boolean,
+            (FieldKind::Timestamp, column_type) => column_type.is_temporal(),
+            (FieldKind::Array(kind), Column::Array(inner)) => kind.accepts(inner),
+            (FieldKind::Array(_), _) => false,
+        }
+    }
+}
+
+impl fmt::Display for FieldKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldKind::String => f.write_str("string"),
+            FieldKind::Integer => f.write_str("integer"),
+            FieldKind::Float => f.write_str("float"),
+            FieldKind::Bool => f.write_str("boolean"),
+            FieldKind::Timestamp => f.write_str("timestamp"),
+            FieldKind::Array(kind) => write!(f, "array of {kind}"),
+            FieldKind::Any => f.write_str("any"),
+        }
+    }
+}
+
+/// A difference between a result's columns and a [`RowSchema`].
+///
+/// # Example
+///
+/// ```rust
+/// use duners::schema::SchemaIssue;
+///
+/// let issue = SchemaIssue::UnmappedColumn { column: "foo".to_string() };
+/// assert_eq!(issue.to_string(), "column `foo` missing from struct");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaIssue {
+    /// The row type reads a column the result does not have.
+    MissingColumn {
+        /// Column name.
+        column: String,
+    },
+    /// The result has a column the row type does not read.
+    UnmappedColumn {
+        /// Column name.
+        column: String,
+    },
+    /// The column's Dune type cannot fill the field.
+    TypeMismatch {
+        /// Column name.
+        column: String,
+        /// What the field holds.
+        expected: FieldKind,
+        /// The column's type in the result.
+        actual: DuneColumnType,
+    },
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaIssue::MissingColumn { column } => {
+                write!(f, "column `{column}` missing from result")
+            }
+            SchemaIssue::UnmappedColumn { column } => {
+                write!(f, "column `{column}` missing from struct")
+            }
+            SchemaIssue::TypeMismatch {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column `{column}` has type {actual}, struct expects {expected}"
+            ),
+        }
+    }
+}
+
+/// Compares the columns described by `metadata` with the schema of `T`.
+///
+/// Types are only compared when the API reported `column_types`. An empty list means the result
+/// fits `T`.
+///
+/// # Example
+///
+/// Tolerating columns the struct does not read:
+///
+/// ```no_run
+/// use duners::schema::{self, FieldKind, FieldSpec, RowSchema, SchemaIssue};
+/// use duners::{DuneClient, DuneRequestError};
+/// use serde::Deserialize;
+/// use serde_json::Value;
+///
+/// #[derive(Deserialize)]
+/// struct Row { symbol: String }
+///
+/// impl RowSchema for Row {
+///     fn schema() -> Vec<FieldSpec> {
+///         vec![FieldSpec::new("symbol", FieldKind::String)]
+///     }
+/// }
+///
+/// # async fn run() -> Result<(), DuneRequestError> {
+/// let client = DuneClient::from_env();
+/// let results = client.refresh::<Value>(971694, None, None).await?;
+/// let issues: Vec<_> = schema::check::<Row>(&results.result.metadata)
+///     .into_iter()
+///     .filter(|issue| !matches!(issue, SchemaIssue::UnmappedColumn { .. }))
+///     .collect();
+/// if !issues.is_empty() {
+///     return Err(DuneRequestError::SchemaMismatch(issues));
+/// }
+/// # Ok(()) }
+/// ```
+pub fn check<T: RowSchema>(metadata: &ResultMetaData) -> Vec<SchemaIssue> {
+    let fields = T::schema();
+    let mut issues = Vec::new();
+    for field in &fields {
+        let column = metadata.columns().find(|(name, _)| *name == field.column);
+        match column {
+            None if field.optional => {}
+            None => issues.push(SchemaIssue::MissingColumn {
+                column: field.column.clone(),
+            }),
+            Some((_, Some(column_type))) if !field.kind.accepts(column_type) => {
+                issues.push(SchemaIssue::TypeMismatch {
+                    column: field.column.clone(),
+                    expected: field.kind.clone(),
+                    actual: column_type.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for name in &metadata.column_names {
+        if !fields.iter().any(|field| field.column == *name) {
+            issues.push(SchemaIssue::UnmappedColumn {
+                column: name.clone(),
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::GetResultResponse;
+
+    struct Row;
+
+    impl RowSchema for Row {
+        fn schema() -> Vec<FieldSpec> {
+            vec![
+                FieldSpec::new("symbol", FieldKind::String),
+                FieldSpec::new("max_price", FieldKind::Float),
+                FieldSpec::new("volume", FieldKind::Integer).optional(),
+                FieldSpec::new("day", FieldKind::Timestamp),
+                FieldSpec::new("tags", FieldKind::Array(Box::new(FieldKind::String))),
+            ]
+        }
+    }
+
+    fn metadata(columns: &[(&str, &str)]) -> ResultMetaData {
+        serde_json::from_value(serde_json::json!({
+            "column_names": columns.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            "column_types": columns.iter().map(|(_, column_type)| column_type).collect::<Vec<_>>(),
+            "result_set_bytes": 0, "total_row_count": 0, "datapoint_count": 0,
+            "pending_time_millis": null, "execution_time_millis": 1
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matching_schema() {
+        let metadata = metadata(&[
+            ("symbol", "varchar"),
+            ("max_price", "decimal(38,2)"),
+            ("day", "timestamp(3) with time zone"),
+            ("tags", "array(varchar)"),
+        ]);
+        assert_eq!(check::<Row>(&metadata), vec![]);
+    }
+
+    #[test]
+    fn schema_issues() {
+        let metadata = metadata(&[
+            ("symbol", "varchar"),
+            ("max_price", "varchar"),
+            ("volume", "double"),
+            ("tags", "array(bigint)"),
+            ("extra", "boolean"),
+        ]);
+        let issues = check::<Row>(&metadata);
+        assert_eq!(
+            issues.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "column `max_price` has type varchar, struct expects float",
+                "column `volume` has type double, struct expects integer",
+                "column `day` missing from result",
+                "column `tags` has type array(bigint), struct expects array of string",
+                "column `extra` missing from struct",
+            ]
+        );
+    }
+
+    #[test]
+    fn checked_results() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Price {
+            symbol: String,
+        }
+
+        impl RowSchema for Price {
+            fn schema() -> Vec<FieldSpec> {
+                vec![FieldSpec::new("symbol", FieldKind::String)]
+            }
+        }
+
+        let results = |column_type: &str| -> GetResultResponse<serde_json::Value> {
+            serde_json::from_value(serde_json::json!({
+                "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+                "submitted_at": "2024-01-01T00:00:00.0Z",
+                "result": {"rows": [{"symbol": "ETH"}], "metadata": metadata(&[("symbol", column_type)])}
+            }))
+            .unwrap()
+        };
+        let checked = results("varchar").into_checked::<Price>().unwrap();
+        assert_eq!(
+            checked.get_rows(),
+            vec![Price {
+                symbol: "ETH".into()
+            }]
+        );
+
+        let error = results("bigint").into_checked::<Price>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "result does not match the row type: column `symbol` has type bigint, struct expects string"
+        );
+    }
+}
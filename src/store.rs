@@ -0,0 +1,199 @@
+//! Persistence of in-flight executions, so polling can resume after a restart.
+//!
+//! When a client has an [`ExecutionStore`] (see
+//! [`DuneClient::with_execution_store`](crate::client::DuneClient::with_execution_store)),
+//! [`refresh`](crate::client::DuneClient::refresh) records each execution it starts and forgets it
+//! once the results were fetched. After a crash,
+//! [`resume`](crate::client::DuneClient::resume) picks the recorded execution back up instead of
+//! paying for a new one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// An execution started by `refresh` that has not yet delivered its results.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StoredExecution {
+    /// Query that was executed.
+    pub query_id: u32,
+    /// Execution to resume polling.
+    pub execution_id: String,
+    /// Parameter values (name → value) the execution was started with.
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl StoredExecution {
+    fn matches(&self, query_id: u32, parameters: &BTreeMap<String, String>) -> bool {
+        self.query_id == query_id && &self.parameters == parameters
+    }
+}
+
+/// Storage for [`StoredExecution`]s, keyed by query ID and parameter values (one pending
+/// execution per query and parameters).
+///
+/// # Example
+///
+/// An in-memory store (e.g. for tests):
+///
+/// ```rust
+/// use duners::store::{ExecutionStore, StoredExecution};
+/// use std::collections::{BTreeMap, HashMap};
+/// use std::io;
+/// use std::sync::Mutex;
+///
+/// type Parameters = BTreeMap<String, String>;
+///
+/// #[derive(Default)]
+/// struct MemoryStore(Mutex<HashMap<(u32, Parameters), StoredExecution>>);
+///
+/// impl ExecutionStore for MemoryStore {
+///     fn save(&self, execution: &StoredExecution) -> io::Result<()> {
+///         let key = (execution.query_id, execution.parameters.clone());
+///         self.0.lock().unwrap().insert(key, execution.clone());
+///         Ok(())
+///     }
+///     fn load(&self, query_id: u32, parameters: &Parameters) -> io::Result<Option<StoredExecution>> {
+///         Ok(self.0.lock().unwrap().get(&(query_id, parameters.clone())).cloned())
+///     }
+///     fn remove(&self, query_id: u32, parameters: &Parameters) -> io::Result<()> {
+///         self.0.lock().unwrap().remove(&(query_id, parameters.clone()));
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ExecutionStore: Send + Sync {
+    /// Records `execution`, replacing any previous entry for the same query and parameters.
+    fn save(&self, execution: &StoredExecution) -> io::Result<()>;
+    /// The pending execution of `query_id` with these parameter values, if any.
+    fn load(
+        &self,
+        query_id: u32,
+        parameters: &BTreeMap<String, String>,
+    ) -> io::Result<Option<StoredExecution>>;
+    /// Forgets the pending execution of `query_id` with these parameter values.
+    fn remove(&self, query_id: u32, parameters: &BTreeMap<String, String>) -> io::Result<()>;
+}
+
+/// [`ExecutionStore`] backed by a single JSON file.
+///
+/// Writes go to a temporary file that is then renamed over the original, so a crash mid-write
+/// never leaves a corrupt store behind.
+///
+/// # Example
+///
+/// ```no_run
+/// use duners::store::FileExecutionStore;
+/// use duners::DuneClient;
+///
+/// let client = DuneClient::from_env()
+///     .with_execution_store(FileExecutionStore::new("/var/lib/my-service/executions.json"));
+/// ```
+pub struct FileExecutionStore {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles within this process.
+    lock: Mutex<()>,
+}
+
+impl FileExecutionStore {
+    /// Uses the file at `path`, which is created on the first save.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        FileExecutionStore {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> io::Result<Vec<StoredExecution>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(io::Error::from),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn write(&self, executions: &[StoredExecution]) -> io::Result<()> {
+        // Unique per process and write, so concurrent writers (other processes, or threads of
+        // this one) do not clobber each other's temporary file.
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+        let write = WRITES.fetch_add(1, Ordering::Relaxed);
+        let tmp = self
+            .path
+            .with_extension(format!("tmp{}-{write}", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec_pretty(executions)?)?;
+        fs::rename(tmp, &self.path)
+    }
+}
+
+impl ExecutionStore for FileExecutionStore {
+    fn save(&self, execution: &StoredExecution) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let mut executions = self.read()?;
+        executions.retain(|stored| !stored.matches(execution.query_id, &execution.parameters));
+        executions.push(execution.clone());
+        self.write(&executions)
+    }
+
+    fn load(
+        &self,
+        query_id: u32,
+        parameters: &BTreeMap<String, String>,
+    ) -> io::Result<Option<StoredExecution>> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self
+            .read()?
+            .into_iter()
+            .find(|stored| stored.matches(query_id, parameters)))
+    }
+
+    fn remove(&self, query_id: u32, parameters: &BTreeMap<String, String>) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let mut executions = self.read()?;
+        let before = executions.len();
+        executions.retain(|stored| !stored.matches(query_id, parameters));
+        if executions.len() != before {
+            self.write(&executions)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_store_round_trip() {
+        let path = std::env::temp_dir().join("duners-file-store-test.json");
+        let _ = fs::remove_file(&path);
+        let store = FileExecutionStore::new(&path);
+        let start = |value: &str| BTreeMap::from([("Start".to_string(), value.to_string())]);
+        assert_eq!(store.load(1, &start("5")).unwrap(), None);
+
+        let execution = StoredExecution {
+            query_id: 1,
+            execution_id: "jerb".to_string(),
+            parameters: start("5"),
+        };
+        let other = StoredExecution {
+            execution_id: "other-jerb".to_string(),
+            parameters: start("6"),
+            ..execution.clone()
+        };
+        store.save(&execution).unwrap();
+        store.save(&other).unwrap();
+        // A fresh handle sees what the previous one wrote.
+        let reopened = FileExecutionStore::new(&path);
+        assert_eq!(reopened.load(1, &start("5")).unwrap(), Some(execution));
+        assert_eq!(reopened.load(1, &start("6")).unwrap(), Some(other.clone()));
+        assert_eq!(reopened.load(2, &start("5")).unwrap(), None);
+
+        reopened.remove(1, &start("5")).unwrap();
+        assert_eq!(store.load(1, &start("5")).unwrap(), None);
+        assert_eq!(store.load(1, &start("6")).unwrap(), Some(other));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,406 @@
+//! Query-as-code: keep a directory of `.sql` files in sync with saved Dune queries.
+//!
+//! A synced directory holds one `.sql` file per query plus a [`Manifest`] (`dune.json`) mapping
+//! each file to its query ID and metadata:
+//!
+//! ```json
+//! {
+//!   "queries": [
+//!     {
+//!       "file": "max_price.sql",
+//!       "query_id": 971694,
+//!       "name": "Max price",
+//!       "tags": ["prices"],
+//!       "parameters": [{ "key": "Days", "value": "7", "type": "number" }],
+//!       "is_private": false
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! [`QuerySync::push`] creates queries for entries without a `query_id` (recording the new ID in
+//! the manifest) and updates the ones whose SQL or metadata differ from Dune. Every `{{name}}`
+//! placeholder in the SQL must have an entry in `parameters`.
+//! [`QuerySync::pull`] overwrites the local SQL and metadata with what is saved on Dune; add an
+//! entry with just `file` and `query_id` to start tracking an existing query.
+
+use crate::client::DuneClient;
+use crate::error::{DuneRequestError, InvalidParameter};
+use crate::parameters::extract_placeholders;
+use crate::response::{Query, QueryParameter};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest file inside a synced directory.
+pub const MANIFEST_FILE: &str = "dune.json";
+
+/// The list of queries managed in a directory.
+///
+/// # Example
+///
+/// Start tracking an existing query, then [`pull`](QuerySync::pull) its SQL:
+///
+/// ```no_run
+/// use duners::sync::{Manifest, ManifestEntry, MANIFEST_FILE};
+///
+/// let path = std::path::Path::new("queries").join(MANIFEST_FILE);
+/// let mut manifest = Manifest::load(&path).unwrap_or_default();
+/// manifest.queries.push(ManifestEntry {
+///     file: "max_price.sql".into(),
+///     query_id: Some(971694),
+///     name: String::new(),
+///     description: String::new(),
+///     tags: vec![],
+///     parameters: vec![],
+///     is_private: false,
+/// });
+/// manifest.save(&path).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    /// One entry per `.sql` file.
+    pub queries: Vec<ManifestEntry>,
+}
+
+/// A `.sql` file and the query it is deployed as.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    /// Path of the SQL file, relative to the manifest's directory.
+    pub file: PathBuf,
+    /// Dune query ID; `None` until the query is first pushed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_id: Option<u32>,
+    /// Query title.
+    #[serde(default)]
+    pub name: String,
+    /// Query description.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Query tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Parameter definitions with their default values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<QueryParameter>,
+    /// Whether the query is private.
+    #[serde(default)]
+    pub is_private: bool,
+}
+
+impl Manifest {
+    /// Reads the manifest at `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        serde_json::from_slice(&fs::read(path)?).map_err(io::Error::from)
+    }
+
+    /// Writes the manifest to `path` (through a temporary file, like
+    /// [`FileExecutionStore`](crate::store::FileExecutionStore)).
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        let mut json = serde_json::to_vec_pretty(self)?;
+        json.push(b'\n');
+        fs::write(&tmp, json)?;
+        fs::rename(tmp, path)
+    }
+}
+
+impl ManifestEntry {
+    /// Whether `remote` differs from this entry (with `sql` as its SQL) in anything a push
+    /// would update, ignoring trailing whitespace in the SQL.
+    fn differs_from(&self, sql: &str, remote: &Query) -> bool {
+        sql.trim_end() != remote.query_sql.trim_end()
+            || self.name != remote.name
+            || self.description != remote.description
+            || self.tags != remote.tags
+            || self.parameters != remote.parameters
+    }
+
+    /// Checks that every placeholder of `sql` has a parameter definition in this entry.
+    fn check_placeholders(&self, sql: &str) -> Result<(), DuneRequestError> {
+        let undeclared: Vec<_> = extract_placeholders(sql)
+            .into_iter()
+            .filter(|name| !self.parameters.iter().any(|p| p.key == *name))
+            .map(|name| InvalidParameter {
+                message: Some(format!(
+                    "used in {} but not declared in the manifest",
+                    self.file.display()
+                )),
+                name,
+            })
+            .collect();
+        match undeclared.is_empty() {
+            true => Ok(()),
+            false => Err(DuneRequestError::InvalidParameters(undeclared)),
+        }
+    }
+
+    /// Copies the metadata of `remote` into this entry.
+    fn update_from(&mut self, remote: &Query) {
+        self.name.clone_from(&remote.name);
+        self.description.clone_from(&remote.description);
+        self.tags.clone_from(&remote.tags);
+        self.parameters.clone_from(&remote.parameters);
+        self.is_private = remote.is_private;
+    }
+}
+
+/// What a push or pull did for one manifest entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    /// A new query was created on Dune.
+    Created,
+    /// The query on Dune was updated from the local files.
+    Updated,
+    /// The local files were overwritten from Dune.
+    Pulled,
+    /// Nothing differed.
+    Unchanged,
+}
+
+/// Result of syncing one manifest entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncOutcome {
+    /// The entry's SQL file (relative to the synced directory).
+    pub file: PathBuf,
+    /// The entry's query ID.
+    pub query_id: u32,
+    /// What was done.
+    pub action: SyncAction,
+}
+
+/// Pushes and pulls the queries of one directory.
+///
+/// # Example
+///
+/// ```no_run
+/// use duners::sync::{QuerySync, SyncAction};
+/// use duners::{DuneClient, DuneRequestError};
+///
+/// # async fn run() -> Result<(), DuneRequestError> {
+/// let client = DuneClient::from_env();
+/// for outcome in QuerySync::new(&client, "queries").push().await? {
+///     if outcome.action != SyncAction::Unchanged {
+///         println!("{:?} {} ({})", outcome.action, outcome.file.display(), outcome.query_id);
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+pub struct QuerySync<'a> {
+    client: &'a DuneClient,
+    dir: PathBuf,
+}
+
+impl<'a> QuerySync<'a> {
+    /// Syncs the directory `dir`, which must contain a [`MANIFEST_FILE`].
+    pub fn new(client: &'a DuneClient, dir: impl AsRef<Path>) -> Self {
+        QuerySync {
+            client,
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.dir.join(MANIFEST_FILE)
+    }
+
+    /// Creates or updates the Dune queries to match the local SQL files and manifest.
+    ///
+    /// The manifest is rewritten after every created query, so IDs are not lost if a later
+    /// entry fails. An entry whose SQL uses a placeholder missing from its `parameters` fails
+    /// with [`DuneRequestError::InvalidParameters`] before anything is sent for it.
+    pub async fn push(&self) -> Result<Vec<SyncOutcome>, DuneRequestError> {
+        let mut manifest = Manifest::load(self.manifest_path())?;
+        let mut outcomes = Vec::with_capacity(manifest.queries.len());
+        for index in 0..manifest.queries.len() {
+            let entry = &manifest.queries[index];
+            let sql = fs::read_to_string(self.dir.join(&entry.file))?;
+            entry.check_placeholders(&sql)?;
+            let outcome = match entry.query_id {
+                None => {
+                    let query_id = self
+                        .client
+                        ._create_query_json(&json!({
+                            "name": entry.name,
+                            "description": entry.description,
+                            "tags": entry.tags,
+                            "query_sql": sql,
+                            "parameters": entry.parameters,
+                            "is_private": entry.is_private,
+                        }))
+                        .await?
+                        .query_id
+                        .0;
+                    manifest.queries[index].query_id = Some(query_id);
+                    manifest.save(self.manifest_path())?;
+                    SyncOutcome {
+                        file: manifest.queries[index].file.clone(),
+                        query_id,
+                        action: SyncAction::Created,
+                    }
+                }
+                Some(query_id) => self.push_existing(entry, query_id, &sql).await?,
+            };
+            info!("push {}: {:?}", outcome.file.display(), outcome.action);
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    async fn push_existing(
+        &self,
+        entry: &ManifestEntry,
+        query_id: u32,
+        sql: &str,
+    ) -> Result<SyncOutcome, DuneRequestError> {
+        let remote = self.client.get_query(query_id).await?;
+        let mut action = SyncAction::Unchanged;
+        if entry.differs_from(sql, &remote) {
+            self.client
+                ._update_query_json(
+                    query_id,
+                    &json!({
+                        "name": entry.name,
+                        "description": entry.description,
+                        "tags": entry.tags,
+                        "query_sql": sql,
+                        "parameters": entry.parameters,
+                    }),
+                )
+                .await?;
+            action = SyncAction::Updated;
+        }
+        if entry.is_private != remote.is_private {
+            match entry.is_private {
+                true => self.client.make_private(query_id).await?,
+                false => self.client.make_public(query_id).await?,
+            };
+            action = SyncAction::Updated;
+        }
+        Ok(SyncOutcome {
+            file: entry.file.clone(),
+            query_id,
+            action,
+        })
+    }
+
+    /// Overwrites the local SQL files and manifest metadata with the queries saved on Dune.
+    ///
+    /// Entries without a `query_id` are left alone.
+    pub async fn pull(&self) -> Result<Vec<SyncOutcome>, DuneRequestError> {
+        let mut manifest = Manifest::load(self.manifest_path())?;
+        let mut outcomes = vec![];
+        for entry in manifest.queries.iter_mut() {
+            let Some(query_id) = entry.query_id else {
+                continue;
+            };
+            let remote = self.client.get_query(query_id).await?;
+            let path = self.dir.join(&entry.file);
+            let local = match fs::read_to_string(&path) {
+                Ok(sql) => Some(sql),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err.into()),
+            };
+            let unchanged = local
+                .as_deref()
+                .is_some_and(|sql| !entry.differs_from(sql, &remote))
+                && entry.is_private == remote.is_private;
+            if !unchanged {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, format!("{}\n", remote.query_sql.trim_end()))?;
+                entry.update_from(&remote);
+            }
+            outcomes.push(SyncOutcome {
+                file: entry.file.clone(),
+                query_id,
+                action: match unchanged {
+                    true => SyncAction::Unchanged,
+                    false => SyncAction::Pulled,
+                },
+            });
+        }
+        manifest.save(self.manifest_path())?;
+        Ok(outcomes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote() -> Query {
+        Query {
+            query_id: 1,
+            name: "Blocks".to_string(),
+            description: String::new(),
+            tags: vec!["test".to_string()],
+            version: Some(1),
+            parameters: vec![],
+            query_engine: None,
+            query_sql: "SELECT 1".to_string(),
+            is_private: true,
+            is_archived: false,
+            is_unsaved: false,
+            owner: None,
+        }
+    }
+
+    #[test]
+    fn entry_diff_and_update() {
+        let mut entry = ManifestEntry {
+            file: PathBuf::from("blocks.sql"),
+            query_id: Some(1),
+            name: "Blocks".to_string(),
+            description: String::new(),
+            tags: vec!["test".to_string()],
+            parameters: vec![],
+            is_private: true,
+        };
+        assert!(!entry.differs_from("SELECT 1\n", &remote()));
+        assert!(entry.differs_from("SELECT 2\n", &remote()));
+
+        entry.name = "Renamed".to_string();
+        assert!(entry.differs_from("SELECT 1", &remote()));
+        entry.update_from(&remote());
+        assert_eq!(entry.name, "Blocks");
+
+        let sql = "SELECT * FROM ethereum.blocks LIMIT {{Limit}} OFFSET {{Offset}}";
+        assert_eq!(
+            entry.check_placeholders(sql).unwrap_err().to_string(),
+            "invalid parameters: Limit (used in blocks.sql but not declared in the manifest); \
+             Offset (used in blocks.sql but not declared in the manifest)"
+        );
+        entry.parameters = serde_json::from_str(
+            r#"[{"key": "Limit", "value": "5", "type": "number"},
+                {"key": "Offset", "value": "0", "type": "number"}]"#,
+        )
+        .unwrap();
+        assert_eq!(entry.check_placeholders(sql), Ok(()));
+    }
+
+    #[test]
+    fn manifest_round_trip() {
+        let dir = std::env::temp_dir().join("duners-sync-manifest-test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(MANIFEST_FILE);
+        fs::write(
+            &path,
+            r#"{"queries": [{"file": "new.sql", "name": "New"}, {"file": "old.sql", "query_id": 7}]}"#,
+        )
+        .unwrap();
+        let manifest = Manifest::load(&path).unwrap();
+        assert_eq!(manifest.queries[0].query_id, None);
+        assert_eq!(manifest.queries[1].query_id, Some(7));
+        assert!(!manifest.queries[1].is_private);
+
+        manifest.save(&path).unwrap();
+        assert_eq!(Manifest::load(&path).unwrap(), manifest);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
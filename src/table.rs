@@ -0,0 +1,290 @@
+//! Request types for the Tables API.
+//!
+//! [`ColumnDef`]s describe the schema passed to
+//! [`DuneClient::create_table`](crate::client::DuneClient::create_table); rows are sent to
+//! [`insert_rows`](crate::client::DuneClient::insert_rows) as newline-delimited JSON.
+//! Large datasets go through [`insert_rows_chunked`](crate::client::DuneClient::insert_rows_chunked)
+//! or [`insert_reader`](crate::client::DuneClient::insert_reader), which split the data into
+//! size-bounded requests as configured by [`ChunkedInsert`].
+
+use serde::Serialize;
+use std::fmt;
+use std::mem;
+use std::time::Duration;
+
+/// Content type of newline-delimited JSON request bodies.
+pub(crate) const NDJSON: &str = "application/x-ndjson";
+
+/// Column types supported by Dune tables.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DuneType {
+    /// Variable-length string.
+    Varchar,
+    /// 32-bit signed integer.
+    Integer,
+    /// 64-bit signed integer.
+    Bigint,
+    /// 64-bit floating point number.
+    Double,
+    /// `true` / `false`.
+    Boolean,
+    /// Date and time (UTC).
+    Timestamp,
+    /// Calendar date.
+    Date,
+    /// Raw bytes, e.g. addresses and hashes (`0x…` in CSV/JSON).
+    Varbinary,
+    /// 256-bit unsigned integer, e.g. token amounts.
+    Uint256,
+    /// 256-bit signed integer.
+    Int256,
+}
+
+impl DuneType {
+    /// Name of the type in Dune SQL.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuneType::Varchar => "varchar",
+            DuneType::Integer => "integer",
+            DuneType::Bigint => "bigint",
+            DuneType::Double => "double",
+            DuneType::Boolean => "boolean",
+            DuneType::Timestamp => "timestamp",
+            DuneType::Date => "date",
+            DuneType::Varbinary => "varbinary",
+            DuneType::Uint256 => "uint256",
+            DuneType::Int256 => "int256",
+        }
+    }
+}
+
+impl fmt::Display for DuneType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One column of a table schema.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::table::{ColumnDef, DuneType};
+///
+/// let schema = vec![
+///     ColumnDef::new("address", DuneType::Varbinary),
+///     ColumnDef::new("label", DuneType::Varchar).nullable(),
+///     ColumnDef::new("first_seen", DuneType::Timestamp),
+/// ];
+/// assert!(schema[1].nullable);
+/// ```
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDef {
+    /// Column name.
+    pub name: String,
+    /// Column type.
+    #[serde(rename = "type")]
+    pub column_type: DuneType,
+    /// Whether the column accepts nulls.
+    pub nullable: bool,
+}
+
+impl ColumnDef {
+    /// A non-nullable column.
+    pub fn new(name: &str, column_type: DuneType) -> Self {
+        ColumnDef {
+            name: name.to_string(),
+            column_type,
+            nullable: false,
+        }
+    }
+
+    /// Allows nulls in this column.
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+}
+
+/// Encoding of data streamed into a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertFormat {
+    /// Newline-delimited JSON, one object per line.
+    Ndjson,
+    /// CSV whose first line is the header; the header is repeated at the start of every chunk.
+    Csv,
+}
+
+impl InsertFormat {
+    pub(crate) fn content_type(&self) -> &'static str {
+        match self {
+            InsertFormat::Ndjson => NDJSON,
+            InsertFormat::Csv => "text/csv",
+        }
+    }
+}
+
+/// How [`insert_rows_chunked`](crate::client::DuneClient::insert_rows_chunked) and
+/// [`insert_reader`](crate::client::DuneClient::insert_reader) split and send data.
+///
+/// A chunk whose request could not connect to Dune is retried up to `max_retries` times, waiting
+/// `retry_delay × attempt` in between. Other errors, including timeouts and responses lost after
+/// the request was sent, abort the upload, since the chunk may already have been written and
+/// retrying it could duplicate rows.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::table::ChunkedInsert;
+///
+/// let options = ChunkedInsert {
+///     max_chunk_bytes: 32 * 1024 * 1024,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkedInsert {
+    /// Upper bound on the size of one request body (a single larger line is sent on its own).
+    pub max_chunk_bytes: usize,
+    /// How often a failed chunk is retried.
+    pub max_retries: u32,
+    /// Base delay between retries.
+    pub retry_delay: Duration,
+}
+
+impl Default for ChunkedInsert {
+    /// 10 MiB chunks, 3 retries, 1 second base delay.
+    fn default() -> Self {
+        ChunkedInsert {
+            max_chunk_bytes: 10 * 1024 * 1024,
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Running totals of a chunked insert, passed to the progress callback after every chunk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InsertProgress {
+    /// Chunks successfully written.
+    pub chunks: u32,
+    /// Rows written, as reported by Dune.
+    pub rows_written: u64,
+    /// Bytes of request bodies successfully sent.
+    pub bytes_sent: u64,
+    /// Number of chunk requests that were retried.
+    pub retries: u32,
+}
+
+/// Accumulates lines into chunks of at most `max_bytes` (plus the header for CSV).
+pub(crate) struct Chunker {
+    header: Option<Vec<u8>>,
+    buf: Vec<u8>,
+    max_bytes: usize,
+}
+
+impl Chunker {
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Chunker {
+            header: None,
+            buf: vec![],
+            max_bytes,
+        }
+    }
+
+    /// Sets a line (including its newline) to prepend to every chunk.
+    pub(crate) fn set_header(&mut self, header: Vec<u8>) {
+        self.header = Some(header);
+    }
+
+    /// Adds a newline-terminated line, returning the previous chunk if it is full.
+    pub(crate) fn push(&mut self, line: &[u8]) -> Option<Vec<u8>> {
+        let full = !self.buf.is_empty() && self.buf.len() + line.len() > self.max_bytes;
+        let chunk = if full { self.take() } else { None };
+        self.buf.extend_from_slice(line);
+        chunk
+    }
+
+    /// The pending chunk, if any.
+    pub(crate) fn take(&mut self) -> Option<Vec<u8>> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let lines = mem::take(&mut self.buf);
+        Some(match &self.header {
+            Some(header) => [header.as_slice(), &lines].concat(),
+            None => lines,
+        })
+    }
+}
+
+/// Encodes `rows` as newline-delimited JSON, one object per line.
+pub(crate) fn to_ndjson<T: Serialize>(
+    rows: impl IntoIterator<Item = T>,
+) -> Result<Vec<u8>, serde_json::Error> {
+    let mut body = vec![];
+    for row in rows {
+        serde_json::to_writer(&mut body, &row)?;
+        body.push(b'\n');
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunking() {
+        let mut chunker = Chunker::new(10);
+        chunker.set_header(b"h\n".to_vec());
+        assert_eq!(chunker.push(b"aaaa\n"), None);
+        assert_eq!(chunker.push(b"bbbb\n"), None);
+        assert_eq!(chunker.push(b"c\n"), Some(b"h\naaaa\nbbbb\n".to_vec()));
+        // Oversized lines still go out, on their own.
+        assert_eq!(chunker.push(b"dddddddddddd\n"), Some(b"h\nc\n".to_vec()));
+        assert_eq!(chunker.take(), Some(b"h\ndddddddddddd\n".to_vec()));
+        assert_eq!(chunker.take(), None);
+    }
+
+    #[test]
+    fn ndjson_encoding() {
+        #[derive(Serialize)]
+        struct Label {
+            address: &'static str,
+            label: Option<&'static str>,
+        }
+        let body = to_ndjson([
+            Label {
+                address: "0x01",
+                label: Some("a"),
+            },
+            Label {
+                address: "0x02",
+                label: None,
+            },
+        ])
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "{\"address\":\"0x01\",\"label\":\"a\"}\n{\"address\":\"0x02\",\"label\":null}\n"
+        );
+    }
+
+    #[test]
+    fn schema_serialization() {
+        let schema = vec![
+            ColumnDef::new("amount", DuneType::Uint256),
+            ColumnDef::new("note", DuneType::Varchar).nullable(),
+        ];
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap(),
+            serde_json::json!([
+                {"name": "amount", "type": "uint256", "nullable": false},
+                {"name": "note", "type": "varchar", "nullable": true},
+            ])
+        );
+        assert_eq!(DuneType::Varbinary.to_string(), "varbinary");
+    }
+}
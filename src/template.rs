@@ -0,0 +1,148 @@
+//! Rendering SQL templates locally before saving or running them (feature `minijinja`).
+//!
+//! Dune substitutes `{{name}}` placeholders with single scalar values. For loops, conditionals
+//! and value lists, render the SQL with [minijinja](https://docs.rs/minijinja) first and pass
+//! the result to [`create_query`](crate::client::DuneClient::create_query) or
+//! [`run_sql`](crate::client::DuneClient::run_sql).
+//!
+//! Templates use Jinja's `{% … %}` statements and `{# … #}` comments, but `[[ … ]]` for
+//! expressions, so Dune's own `{{name}}` placeholders pass through untouched. Variables missing
+//! from the context are errors rather than empty strings, and the `sql` filter writes a value as
+//! a SQL literal: strings quoted (`'it''s'`), `none` as `NULL`, and lists as comma-separated
+//! literals for `IN (…)`.
+
+use crate::error::DuneRequestError;
+use minijinja::syntax::SyntaxConfig;
+use minijinja::value::{Serde, Value, ValueKind};
+use minijinja::{Environment, Error, ErrorKind, UndefinedBehavior};
+use serde::Serialize;
+
+/// Renders the SQL template `source` with the fields of `context`.
+///
+/// Rendering fails with [`DuneRequestError::Template`] on syntax errors, variables missing from
+/// `context`, and values the `sql` filter cannot write (maps).
+///
+/// # Example
+///
+/// ```rust
+/// use duners::template::render_sql;
+/// use serde_json::json;
+///
+/// let source = "\
+/// SELECT block_time, amount_usd FROM dex.trades
+/// WHERE token_bought_symbol IN ([[ tokens | sql ]])
+/// {% if chain %}  AND blockchain = [[ chain | sql ]]
+/// {% endif %}  AND block_time > now() - interval '{{Days}}' day";
+/// let sql = render_sql(source, json!({"tokens": ["ETH", "USDC"], "chain": "ethereum"})).unwrap();
+/// assert_eq!(
+///     sql,
+///     "\
+/// SELECT block_time, amount_usd FROM dex.trades
+/// WHERE token_bought_symbol IN ('ETH', 'USDC')
+///   AND blockchain = 'ethereum'
+///   AND block_time > now() - interval '{{Days}}' day"
+/// );
+/// ```
+pub fn render_sql(source: &str, context: impl Serialize) -> Result<String, DuneRequestError> {
+    let mut env = Environment::new();
+    let syntax = SyntaxConfig::builder()
+        .variable_delimiters("[[", "]]")
+        .trim_blocks(true)
+        .keep_trailing_newline(true)
+        .build()
+        .map_err(template_error)?;
+    env.set_syntax(syntax);
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.add_filter("sql", sql_literal);
+    env.render_str(source, Value::from(Serde(context)))
+        .map_err(template_error)
+}
+
+fn template_error(err: Error) -> DuneRequestError {
+    DuneRequestError::Template(err.to_string())
+}
+
+/// Key under which serde_json's `arbitrary_precision` (feature `arbitrary-precision`) serializes a
+/// number's digits, as the only entry of a map.
+const PRECISE_NUMBER_KEY: &str = "$serde_json::private::Number";
+
+/// The digits of a number serialized with `arbitrary_precision`, if `value` is one.
+fn precise_number(value: &Value) -> Option<String> {
+    if value.len() != Some(1) {
+        return None;
+    }
+    let digits = value.get_item(&Value::from(PRECISE_NUMBER_KEY)).ok()?;
+    // Only valid JSON numbers, so a map that merely uses the key cannot inject SQL.
+    let number: serde_json::Number = digits.as_str()?.parse().ok()?;
+    Some(number.to_string())
+}
+
+/// The `sql` filter: `value` as a SQL literal.
+fn sql_literal(value: Value) -> Result<String, Error> {
+    if let Some(digits) = precise_number(&value) {
+        return Ok(digits);
+    }
+    match value.kind() {
+        ValueKind::None | ValueKind::Undefined => Ok("NULL".to_string()),
+        ValueKind::Bool => Ok(value.is_true().to_string()),
+        ValueKind::Number => Ok(value.to_string()),
+        ValueKind::String => Ok(format!("'{}'", value.to_string().replace('\'', "''"))),
+        ValueKind::Seq | ValueKind::Iterable => {
+            let literals = value
+                .try_iter()?
+                .map(sql_literal)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(literals.join(", "))
+        }
+        kind => Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("cannot write a {kind} as a SQL literal"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rendered_templates() {
+        let source = "SELECT {% for c in columns %}[[ c ]]{% if not loop.last %}, {% endif %}\
+                      {% endfor %} FROM t WHERE name = [[ name | sql ]] AND x IN ([[ xs | sql ]]) \
+                      AND y = [[ none | sql ]] AND z = {{Z}}\n";
+        let context = json!({
+            "columns": ["a", "b"], "name": "it's", "xs": [1, 2.5, true], "none": null
+        });
+        assert_eq!(
+            render_sql(source, context).unwrap(),
+            "SELECT a, b FROM t WHERE name = 'it''s' AND x IN (1, 2.5, true) \
+             AND y = NULL AND z = {{Z}}\n"
+        );
+    }
+
+    #[cfg(feature = "arbitrary-precision")]
+    #[test]
+    fn precise_numbers() {
+        let context: serde_json::Value =
+            serde_json::from_str(r#"{"amount": 1000000000000000000001, "xs": [0.1, 2]}"#).unwrap();
+        assert_eq!(
+            render_sql("[[ amount | sql ]] IN ([[ xs | sql ]])", context).unwrap(),
+            "1000000000000000000001 IN (0.1, 2)"
+        );
+    }
+
+    #[test]
+    fn template_errors() {
+        let undefined = render_sql("SELECT [[ missing ]]", json!({})).unwrap_err();
+        assert!(matches!(undefined, DuneRequestError::Template(_)));
+        assert!(undefined.to_string().starts_with("template error: "));
+        assert!(render_sql("{% if %}", json!({})).is_err());
+        let map = render_sql("[[ m | sql ]]", json!({"m": {"a": 1}})).unwrap_err();
+        let spoofed = json!({"m": {PRECISE_NUMBER_KEY: "1; DROP TABLE t"}});
+        assert!(render_sql("[[ m | sql ]]", spoofed).is_err());
+        assert!(map
+            .to_string()
+            .contains("cannot write a map as a SQL literal"));
+    }
+}
@@ -0,0 +1,115 @@
+//! Client-side pacing of requests.
+//!
+//! A [`RateLimiter`] keeps every request of a client (or of several clients sharing it) under a
+//! requests-per-minute budget, so concurrent tasks do not collectively exceed Dune's rate limits
+//! and trigger a storm of 429 responses.
+
+use crate::poll::Timer;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting how many requests are sent per minute.
+///
+/// Tokens refill continuously at the configured rate, up to `burst` (default 1, i.e. requests
+/// are evenly spaced). Each request takes one token, waiting for it if the bucket is empty;
+/// waiting requests are served in the order they arrived.
+///
+/// # Example
+///
+/// One budget for two clients using the same API key:
+///
+/// ```rust,no_run
+/// use duners::client::DuneClient;
+/// use duners::throttle::RateLimiter;
+/// use std::sync::Arc;
+///
+/// let limiter = Arc::new(RateLimiter::per_minute(40).with_burst(5));
+/// let reports = DuneClient::builder().rate_limiter(limiter.clone()).build().unwrap();
+/// let alerts = DuneClient::builder().rate_limiter(limiter).build().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Time for one token to refill.
+    interval: Duration,
+    /// Bucket capacity.
+    burst: u32,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Available tokens; negative when requests are already waiting for future tokens.
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Allows `requests` requests per minute (at least one).
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimiter {
+            interval: Duration::from_secs(60) / requests.max(1),
+            burst: 1,
+            bucket: Mutex::new(Bucket {
+                tokens: 1.0,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Lets up to `burst` requests go out back to back after a quiet period.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self.bucket.get_mut().unwrap().tokens = f64::from(self.burst);
+        self
+    }
+
+    /// Takes a token, returning how long to wait before it is actually available.
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let refilled =
+            now.duration_since(bucket.updated).as_secs_f64() / self.interval.as_secs_f64();
+        bucket.tokens = (bucket.tokens + refilled).min(f64::from(self.burst));
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+        match bucket.tokens < 0.0 {
+            true => self.interval.mul_f64(-bucket.tokens),
+            false => Duration::ZERO,
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub(crate) async fn acquire(&self, timer: &dyn Timer) {
+        let delay = self.reserve();
+        if !delay.is_zero() {
+            timer.sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket() {
+        // One token every 100 ms, two at once.
+        let limiter = RateLimiter::per_minute(600).with_burst(2);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        let third = limiter.reserve();
+        let fourth = limiter.reserve();
+        assert!(third > Duration::from_millis(90) && third <= Duration::from_millis(100));
+        assert!(fourth > Duration::from_millis(190) && fourth <= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn acquire_waits() {
+        let limiter = RateLimiter::per_minute(1200);
+        let started = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire(&crate::poll::TokioTimer).await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
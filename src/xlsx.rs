@@ -0,0 +1,259 @@
+//! Writing results to Excel workbooks (feature `xlsx`).
+//!
+//! A [`Workbook`] holds one worksheet per query result: a bold header row of column names
+//! (frozen while scrolling) and one row per result row, with cells typed by the result's
+//! `column_types`:
+//!
+//! | Dune type                                          | Excel cell                 |
+//! |----------------------------------------------------|----------------------------|
+//! | `boolean`                                          | boolean                    |
+//! | integers, `real`, `double`, `decimal`              | number                     |
+//! | `date`                                             | date (`yyyy-mm-dd`)        |
+//! | `timestamp`                                        | date and time, UTC         |
+//! | everything else, including `uint256` and `int256`  | text                       |
+//!
+//! 256-bit integers stay text because Excel numbers are 64-bit floats. `null` cells are left
+//! empty. [`DuneClient::refresh_to_xlsx`](crate::client::DuneClient::refresh_to_xlsx) refreshes
+//! several queries into one file.
+
+use crate::error::DuneRequestError;
+use crate::response::{DuneColumnType, GetResultResponse, ResultRow};
+use rust_xlsxwriter::{Format, Worksheet, XlsxError};
+use serde_json::Value;
+use std::path::Path;
+
+/// An Excel workbook of query results, one worksheet per result.
+///
+/// # Example
+///
+/// ```rust
+/// use duners::response::GetResultResponse;
+/// use duners::xlsx::Workbook;
+/// use serde_json::Value;
+///
+/// let results: GetResultResponse<Value> = serde_json::from_value(serde_json::json!({
+///     "execution_id": "01HKZJ2683PHF9Q9PHHQ8FW4Q1", "query_id": 971694,
+///     "state": "QUERY_STATE_COMPLETED", "submitted_at": "2024-01-01T00:00:00.000Z",
+///     "result": {
+///         "rows": [{"day": "2024-01-01 00:00:00.000 UTC", "symbol": "ETH", "price": 2281.5}],
+///         "metadata": {
+///             "column_names": ["day", "symbol", "price"],
+///             "column_types": ["timestamp(3) with time zone", "varchar", "double"],
+///             "result_set_bytes": 60, "total_row_count": 1, "datapoint_count": 3,
+///             "pending_time_millis": null, "execution_time_millis": 1
+///         }
+///     }
+/// }))
+/// .unwrap();
+/// let mut workbook = Workbook::new();
+/// workbook.add_results("Prices", &results).unwrap();
+/// let bytes = workbook.to_bytes().unwrap();
+/// assert!(bytes.starts_with(b"PK"));
+/// ```
+pub struct Workbook {
+    inner: rust_xlsxwriter::Workbook,
+    /// Names of the worksheets added so far.
+    sheets: Vec<String>,
+}
+
+impl Default for Workbook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Workbook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Workbook")
+            .field("sheets", &self.sheets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Workbook {
+    /// An empty workbook.
+    pub fn new() -> Self {
+        Self {
+            inner: rust_xlsxwriter::Workbook::new(),
+            sheets: Vec::new(),
+        }
+    }
+
+    /// Adds a worksheet named `sheet_name` holding `results`.
+    ///
+    /// Fails with [`DuneRequestError::Encode`] for names Excel rejects (empty, longer than 31
+    /// characters, containing `[ ] : * ? / \` or already used) and results with more rows or
+    /// columns than a worksheet holds, and with [`DuneRequestError::Deserialization`] for values
+    /// that do not match their column type (e.g. `"abc"` in a `double` column).
+    pub fn add_results<T: ResultRow>(
+        &mut self,
+        sheet_name: &str,
+        results: &GetResultResponse<T>,
+    ) -> Result<&mut Self, DuneRequestError> {
+        let mut sheet = Worksheet::new();
+        sheet.set_name(sheet_name).map_err(xlsx_error)?;
+        // Excel compares sheet names ignoring case.
+        let lowercase = sheet_name.to_lowercase();
+        if self
+            .sheets
+            .iter()
+            .any(|name| name.to_lowercase() == lowercase)
+        {
+            return Err(DuneRequestError::Encode(format!(
+                "the workbook already has a sheet named {sheet_name:?}"
+            )));
+        }
+        let header = Format::new().set_bold();
+        let date = Format::new().set_num_format("yyyy-mm-dd");
+        let timestamp = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+        let metadata = &results.result.metadata;
+        for (index, (name, column_type)) in metadata.columns().enumerate() {
+            let column = u16::try_from(index).map_err(|_| too_large("columns"))?;
+            sheet
+                .write_string_with_format(0, column, name, &header)
+                .map_err(xlsx_error)?;
+            for (index, row) in results.result.rows.iter().enumerate() {
+                let value = match row.cell(name) {
+                    None | Some(Value::Null) => continue,
+                    Some(value) => value,
+                };
+                let line = u32::try_from(index + 1).map_err(|_| too_large("rows"))?;
+                let invalid = |expected: &str| DuneRequestError::Deserialization {
+                    row: Some(index),
+                    column: Some(name.to_string()),
+                    value: Some(value.to_string()),
+                    expected: Some(expected.to_string()),
+                    source: format!("{value} is not a valid {expected}"),
+                    context: Box::default(),
+                };
+                let written = match column_type {
+                    Some(DuneColumnType::Boolean) => {
+                        let boolean = crate::parse_utils::bool_from_value(value)
+                            .map_err(|_| invalid("bool"))?;
+                        sheet.write_boolean(line, column, boolean)
+                    }
+                    Some(DuneColumnType::Uint256 | DuneColumnType::Int256) => {
+                        sheet.write_string(line, column, text(value))
+                    }
+                    Some(column_type) if column_type.is_numeric() => {
+                        let number = number(value).ok_or_else(|| invalid("f64"))?;
+                        sheet.write_number(line, column, number)
+                    }
+                    Some(DuneColumnType::Date) => {
+                        let day = value
+                            .as_str()
+                            .and_then(|day| crate::parse_utils::parse_any_date(day).ok())
+                            .ok_or_else(|| invalid("date"))?;
+                        sheet.write_datetime_with_format(line, column, day, &date)
+                    }
+                    Some(DuneColumnType::Timestamp { .. }) => {
+                        let time = value
+                            .as_str()
+                            .and_then(|time| crate::parse_utils::parse_any_datetime(time).ok())
+                            .ok_or_else(|| invalid("timestamp"))?;
+                        sheet.write_datetime_with_format(line, column, time.naive_utc(), &timestamp)
+                    }
+                    _ => sheet.write_string(line, column, text(value)),
+                };
+                written.map_err(xlsx_error)?;
+            }
+        }
+        sheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+        sheet.autofit();
+        self.inner.push_worksheet(sheet);
+        self.sheets.push(sheet_name.to_string());
+        Ok(self)
+    }
+
+    /// Writes the workbook to an `.xlsx` file at `path`.
+    pub fn save(&mut self, path: impl AsRef<Path>) -> Result<(), DuneRequestError> {
+        self.inner.save(path.as_ref()).map_err(xlsx_error)
+    }
+
+    /// The workbook as the bytes of an `.xlsx` file.
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>, DuneRequestError> {
+        self.inner.save_to_buffer().map_err(xlsx_error)
+    }
+}
+
+/// Numbers as they are, numeric strings parsed.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Strings as they are, other values as JSON text.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn too_large(what: &str) -> DuneRequestError {
+    DuneRequestError::Encode(format!("results have more {what} than a worksheet holds"))
+}
+
+fn xlsx_error(err: XlsxError) -> DuneRequestError {
+    DuneRequestError::Encode(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn results(rows: Value) -> GetResultResponse<Value> {
+        serde_json::from_value(json!({
+            "execution_id": "jerb", "query_id": 1, "state": "QUERY_STATE_COMPLETED",
+            "submitted_at": "2024-01-01T00:00:00.000Z",
+            "result": {
+                "rows": rows,
+                "metadata": {
+                    "column_names": ["ok", "n", "amount", "day", "at", "name"],
+                    "column_types": [
+                        "boolean", "bigint", "uint256", "date",
+                        "timestamp(3) with time zone", "varchar"
+                    ],
+                    "result_set_bytes": 0, "total_row_count": 0, "datapoint_count": 0,
+                    "pending_time_millis": null, "execution_time_millis": 1
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn workbook_sheets() {
+        let rows = results(json!([
+            {
+                "ok": true, "n": "42", "amount": "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                "day": "2024-01-02", "at": "2024-01-02 03:04:05.000 UTC", "name": "ETH"
+            },
+            {"ok": null, "n": 7, "amount": null, "day": null, "at": null, "name": {"a": 1}}
+        ]));
+        let mut workbook = Workbook::new();
+        workbook
+            .add_results("First", &rows)
+            .unwrap()
+            .add_results("Second", &rows)
+            .unwrap();
+        assert!(workbook.to_bytes().unwrap().starts_with(b"PK"));
+
+        for name in ["first", "a/b", ""] {
+            let err = workbook.add_results(name, &rows).unwrap_err();
+            assert!(matches!(err, DuneRequestError::Encode(_)), "{name}: {err}");
+        }
+        let err = Workbook::new()
+            .add_results("Bad", &results(json!([{"n": "abc"}])))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DuneRequestError::Deserialization { row: Some(0), ref column, .. }
+                if column.as_deref() == Some("n")
+        ));
+    }
+}
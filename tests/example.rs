@@ -42,8 +42,8 @@ async fn test_blocks() {
         .refresh::<Block>(
             3238189,
             Some(vec![
-                Parameter::number("Start", &start.to_string()),
-                Parameter::number("Width", &(end - start).to_string()),
+                Parameter::number("Start", start),
+                Parameter::number("Width", end - start),
             ]),
             Some(1),
         )